@@ -0,0 +1,199 @@
+//! Baseline files recording known cycles that shouldn't fail the check.
+//!
+//! A baseline is a TOML file listing the packages and/or the fingerprint of each known cycle:
+//!
+//! ```toml
+//! [[cycles]]
+//! packages = ["crate_a", "crate_b"]
+//! fingerprint = "4c4c1d8a9f0e3b27"
+//! ```
+//!
+//! A cycle is known if either its packages or its fingerprint match an entry. Entries can carry an
+//! `expires = "2025-06-01"` date, after which they no longer suppress the cycle.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::date::Date;
+use crate::report::{Cycle, Report, StaleSuppression};
+
+/// Source of the stale entries of the allowlist in the configuration.
+pub const ALLOWLIST_SOURCE: &str = "allowlist";
+
+/// Known cycles loaded from or written to a baseline file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Baseline {
+    /// The known cycles.
+    #[serde(default)]
+    pub cycles: Vec<KnownCycle>,
+}
+
+/// A known cycle, as listed in a baseline or an allowlist.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KnownCycle {
+    /// Sorted names of the packages participating in the cycle.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub packages: Vec<String>,
+    /// Fingerprint of the cycle.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+    /// Date after which the entry no longer suppresses the cycle.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires: Option<Date>,
+    /// Why the cycle is accepted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl Baseline {
+    /// Records all reported cycles of the report.
+    #[must_use]
+    pub fn from_report(report: &Report) -> Self {
+        Self {
+            cycles: report
+                .reported_cycles()
+                .map(|c| KnownCycle {
+                    packages: c.package_names(),
+                    fingerprint: Some(c.fingerprint.clone()),
+                    expires: None,
+                    reason: None,
+                })
+                .collect(),
+        }
+    }
+
+    /// Loads a baseline file.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be read or isn't a valid baseline.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path).with_context(|| format!("Failed to read baseline {}", path.display()))?;
+        toml::from_str(&contents).with_context(|| format!("Failed to parse baseline {}", path.display()))
+    }
+
+    /// Writes the baseline to a file.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be written.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let contents = toml::to_string_pretty(self)?;
+        fs::write(path, contents).with_context(|| format!("Failed to write baseline {}", path.display()))
+    }
+
+    /// Marks the cycles of the report that are recorded in the baseline as suppressed.
+    pub fn apply(&self, report: &mut Report) {
+        suppress(report, &self.cycles);
+    }
+}
+
+/// Returns the entries that match none of the cycles of the report, attributed to `source`.
+#[must_use]
+pub fn stale(report: &Report, entries: &[KnownCycle], source: &str) -> Vec<StaleSuppression> {
+    entries
+        .iter()
+        .filter(|entry| !report.cycles.iter().any(|cycle| entry.matches(cycle)))
+        .map(|entry| StaleSuppression {
+            source: source.to_owned(),
+            packages: entry.packages.clone(),
+            fingerprint: entry.fingerprint.clone(),
+        })
+        .collect()
+}
+
+/// Records the cycles of the report that fail the check, and aren't already in the baseline file if
+/// one is given, as known, with the reason if any.
+///
+/// They are appended to the baseline file if one is given, creating it if missing, or else to the
+/// `allow` list of the configuration file, by default the one in the workspace root, see
+/// [`config::append_allowed`]. Returns the number of recorded cycles and the file they were
+/// recorded in, none if no cycle fails.
+///
+/// # Errors
+///
+/// Fails if the baseline or the configuration file can't be read or written.
+pub fn record_failing(
+    report: &mut Report,
+    baseline_path: Option<&Path>,
+    config_path: Option<&Path>,
+    reason: Option<&str>,
+) -> Result<Option<(usize, PathBuf)>> {
+    let mut baseline = match baseline_path {
+        Some(path) if path.exists() => Baseline::load(path)?,
+        _ => Baseline::default(),
+    };
+    baseline.apply(report);
+
+    let entries: Vec<KnownCycle> = report
+        .failing_cycles()
+        .map(|cycle| KnownCycle {
+            packages: cycle.package_names(),
+            fingerprint: Some(cycle.fingerprint.clone()),
+            expires: None,
+            reason: reason.map(str::to_owned),
+        })
+        .collect();
+    if entries.is_empty() {
+        return Ok(None);
+    }
+
+    let count = entries.len();
+    let path = if let Some(path) = baseline_path {
+        baseline.cycles.extend(entries);
+        baseline.save(path)?;
+        path.to_owned()
+    } else {
+        let path = config_path.map_or_else(
+            || report.workspace_root.join(config::CONFIG_FILE_NAME).into_std_path_buf(),
+            Path::to_owned,
+        );
+        config::append_allowed(&path, &entries)?;
+        path
+    };
+    Ok(Some((count, path)))
+}
+
+/// Marks the cycles of the report that match an entry which hasn't expired as suppressed.
+///
+/// Cycles only matched by expired entries stay failing and record the expiry date.
+pub fn suppress(report: &mut Report, entries: &[KnownCycle]) {
+    let today = Date::today();
+    for cycle in &mut report.cycles {
+        let (expired, active): (Vec<&KnownCycle>, Vec<&KnownCycle>) = entries
+            .iter()
+            .filter(|entry| entry.matches(cycle))
+            .partition(|entry| entry.expires.is_some_and(|date| date < today));
+
+        if !active.is_empty() {
+            cycle.suppressed = true;
+            cycle.suppression_expired = None;
+        } else if !cycle.suppressed {
+            cycle.suppression_expired = expired.iter().filter_map(|entry| entry.expires).max().or(cycle.suppression_expired);
+        }
+    }
+}
+
+impl KnownCycle {
+    /// Returns whether the entry describes the cycle.
+    #[must_use]
+    pub fn matches(&self, cycle: &Cycle) -> bool {
+        if self.fingerprint.as_ref().is_some_and(|f| *f == cycle.fingerprint) {
+            return true;
+        }
+        if self.packages.is_empty() {
+            return false;
+        }
+        let mut names = cycle.package_names();
+        names.dedup();
+        let mut packages = self.packages.clone();
+        packages.sort_unstable();
+        packages.dedup();
+        packages == names
+    }
+}
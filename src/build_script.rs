@@ -0,0 +1,77 @@
+//! Running the check from the build script of a workspace member, so it is enforced on every local
+//! `cargo build`.
+
+use core::fmt::Write;
+use std::env;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+
+use crate::checker::Checker;
+use crate::config::CONFIG_FILE_NAME;
+use crate::report::Report;
+
+/// Checks the workspace of the crate whose build script calls it.
+///
+/// Every cycle that isn't suppressed is emitted as a `cargo:warning=` line, and the build script is
+/// rerun when a manifest of the workspace or its configuration file changes. The workspace
+/// configuration applies as for the command line, with `severity = "warning"` the cycles are only
+/// reported.
+///
+/// ```no_run
+/// // build.rs
+/// fn main() -> anyhow::Result<()> {
+///     cargo_ensure_no_cyclic_deps::build_script::check_from_build_script()
+/// }
+/// ```
+///
+/// # Errors
+///
+/// Fails if not called from a build script, if the check can't be run, or if it fails, which fails
+/// the build.
+pub fn check_from_build_script() -> Result<()> {
+    let manifest_dir = env::var_os("CARGO_MANIFEST_DIR").context("CARGO_MANIFEST_DIR isn't set, not running in a build script")?;
+    let report = Checker::new()
+        .manifest_path(Path::new(&manifest_dir).join("Cargo.toml"))
+        .analyze()?
+        .report;
+
+    print!("{}", instructions(&report));
+    if report.is_failure() {
+        bail!("Cyclic dependencies detected in the workspace at {}", report.workspace_root);
+    }
+    Ok(())
+}
+
+/// Renders the instructions for cargo printed by [`check_from_build_script`] for the report.
+#[must_use]
+pub fn instructions(report: &Report) -> String {
+    let mut out = String::new();
+
+    let root_manifest = report.workspace_root.join("Cargo.toml");
+    let _ = writeln!(out, "cargo:rerun-if-changed={root_manifest}");
+    for member in report.members.iter().filter(|m| m.manifest_path != root_manifest) {
+        let _ = writeln!(out, "cargo:rerun-if-changed={}", member.manifest_path);
+    }
+    // Cargo reruns the build script on every build for paths that don't exist
+    let config_path = report.workspace_root.join(CONFIG_FILE_NAME);
+    if config_path.is_file() {
+        let _ = writeln!(out, "cargo:rerun-if-changed={config_path}");
+    }
+
+    for cycle in report.failing_cycles() {
+        let _ = writeln!(
+            out,
+            "cargo:warning=Cyclic dependency: {} (fingerprint {})",
+            cycle.path(),
+            cycle.fingerprint
+        );
+    }
+    for cycle in &report.feature_cycles {
+        let _ = writeln!(out, "cargo:warning=Cyclic feature definition: {}", cycle.path());
+    }
+    for stale in &report.stale_suppressions {
+        let _ = writeln!(out, "cargo:warning=Stale suppression: {} ({})", stale.describe(), stale.source);
+    }
+    out
+}
@@ -0,0 +1,526 @@
+//! Programmatic access to the check with the same options as the command line.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use cargo_metadata::Metadata;
+
+use crate::baseline::{self, Baseline, KnownCycle};
+use crate::config::Config;
+use crate::features::{EnabledFeatures, FeatureGraph, FeatureSelection};
+use crate::graph::DepKind;
+use crate::ratchet::{self, Outcome};
+use crate::report::{FeatureCycle, Report, Severity};
+use crate::rules::{self, Rule};
+use crate::workspaces::include_path_workspaces;
+use crate::{
+    analyze_with_rules, condensation, cuts, elementary, extraction, include_excluded, include_path_dependencies, load_metadata,
+    load_resolved_metadata, near_cycles, powerset, retain_default_members,
+};
+
+/// Builder configuring and running the check.
+///
+/// The configuration of the workspace, from its metadata and configuration file, is loaded as for
+/// the command line, options set on the builder take precedence:
+///
+/// ```no_run
+/// use cargo_ensure_no_cyclic_deps::checker::Checker;
+/// use cargo_ensure_no_cyclic_deps::graph::DepKind;
+///
+/// let report = Checker::new()
+///     .manifest_path("path/to/Cargo.toml")
+///     .dependency_kinds([DepKind::Normal, DepKind::Build])
+///     .allow_dev_cycles(true)
+///     .run()?;
+/// println!("{} suppressed cycle(s)", report.suppressed_cycles().count());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct Checker {
+    manifest_path: Option<PathBuf>,
+    metadata: Option<Metadata>,
+    loading: Loading,
+    config_path: Option<PathBuf>,
+    overrides: Config,
+    features: FeatureSelection,
+    feature_powerset_depth: Option<usize>,
+    elementary_cycles_limit: Option<usize>,
+    baselines: Vec<PathBuf>,
+    strict_baseline: bool,
+    write_baseline: Option<PathBuf>,
+    ratchet: Option<PathBuf>,
+    analyses: Analyses,
+    rules: Vec<Box<dyn Rule>>,
+}
+
+/// Which packages and dependencies the [`Checker`] loads.
+#[derive(Debug, Default)]
+struct Loading {
+    resolved: bool,
+    default_members_only: bool,
+    include: Includes,
+}
+
+/// Crates besides the workspace members that the [`Checker`] analyzes as if they were members.
+#[derive(Debug, Default)]
+struct Includes {
+    path_deps: bool,
+    path_workspaces: bool,
+    excluded: bool,
+}
+
+/// Optional analyses run by the [`Checker`].
+#[derive(Debug, Default)]
+struct Analyses {
+    suggest_cuts: bool,
+    condense: bool,
+    near_cycles: bool,
+}
+
+/// Why [`Checker::run`] didn't pass.
+#[derive(Debug)]
+pub enum CheckError {
+    /// The check couldn't be run, e.g. because the metadata or the configuration can't be loaded.
+    Load(anyhow::Error),
+    /// The check ran and failed, the report has the cycles, feature cycles or stale suppressions
+    /// that caused it.
+    Failed(Box<Report>),
+}
+
+impl core::fmt::Display for CheckError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Load(err) => write!(f, "{err:#}"),
+            Self::Failed(report) => write!(
+                f,
+                "Cyclic dependencies detected in the workspace at {}: {} cycle(s), {} feature cycle(s), {} stale suppression(s)",
+                report.workspace_root,
+                report.failing_cycles().count(),
+                report.feature_cycles.len(),
+                report.stale_suppressions.len()
+            ),
+        }
+    }
+}
+
+impl core::error::Error for CheckError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Load(err) => Some(err.as_ref()),
+            Self::Failed(_) => None,
+        }
+    }
+}
+
+/// Everything the check loaded and produced.
+#[derive(Debug)]
+pub struct Analysis {
+    /// Metadata of the workspace.
+    pub metadata: Metadata,
+    /// Configuration of the workspace combined with the options of the builder.
+    pub config: Config,
+    /// Result of the check.
+    pub report: Report,
+    /// Outcome of checking the report against the ratchet state, if one is, see [`Checker::ratchet`].
+    pub ratchet: Option<Outcome>,
+}
+
+impl Checker {
+    /// Creates a checker for the workspace in the current directory.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checks the workspace of this manifest instead of the one in the current directory.
+    #[must_use]
+    pub fn manifest_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.manifest_path = Some(path.into());
+        self
+    }
+
+    /// Checks the workspace of already loaded metadata instead of running `cargo metadata`.
+    #[must_use]
+    pub fn metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Sets whether crates outside the workspace that are depended on with `path = ...` are analyzed
+    /// as if they were members, see [`include_path_dependencies`].
+    #[must_use]
+    pub const fn include_path_deps(mut self, include: bool) -> Self {
+        self.loading.include.path_deps = include;
+        self
+    }
+
+    /// Sets whether the workspaces that workspace members depend on with `path = ...` are analyzed
+    /// along with the workspace, as one graph, see [`include_path_workspaces`].
+    #[must_use]
+    pub const fn include_path_workspaces(mut self, include: bool) -> Self {
+        self.loading.include.path_workspaces = include;
+        self
+    }
+
+    /// Sets whether the crates listed in `workspace.exclude` are analyzed as if they were members, see
+    /// [`include_excluded`].
+    #[must_use]
+    pub const fn include_excluded(mut self, include: bool) -> Self {
+        self.loading.include.excluded = include;
+        self
+    }
+
+    /// Sets whether only the workspace's `default-members` are analyzed, as for a plain `cargo build`
+    /// in the workspace root, see [`retain_default_members`].
+    #[must_use]
+    pub const fn default_members_only(mut self, default_members_only: bool) -> Self {
+        self.loading.default_members_only = default_members_only;
+        self
+    }
+
+    /// Sets whether only the dependencies in cargo's resolve graph count, i.e. those activated for
+    /// the selected features. If the dependencies can't be resolved, e.g. because of a cycle cargo
+    /// rejects, the declared ones are checked and [`Report::resolve_error`] tells why.
+    ///
+    /// Only applies when the metadata is loaded by the checker.
+    #[must_use]
+    pub const fn resolved(mut self, resolved: bool) -> Self {
+        self.loading.resolved = resolved;
+        self
+    }
+
+    /// Reads this configuration file instead of the one in the workspace root.
+    #[must_use]
+    pub fn config_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config_path = Some(path.into());
+        self
+    }
+
+    /// Only counts dependencies of these kinds.
+    #[must_use]
+    pub fn dependency_kinds(mut self, kinds: impl IntoIterator<Item = DepKind>) -> Self {
+        self.overrides.kinds = Some(kinds.into_iter().collect());
+        self
+    }
+
+    /// Leaves the workspace packages with this name, or matching this glob pattern, out of the
+    /// analysis.
+    #[must_use]
+    pub fn ignore(mut self, package: impl Into<String>) -> Self {
+        self.overrides.ignore.push(package.into());
+        self
+    }
+
+    /// Limits the analysis to the workspace packages with this name, or matching this glob pattern,
+    /// and the dependencies between them, can be called repeatedly to include several packages.
+    #[must_use]
+    pub fn package(mut self, spec: impl Into<String>) -> Self {
+        self.overrides.packages.push(spec.into());
+        self
+    }
+
+    /// Only reports and fails on the cycles involving a workspace package whose name matches the
+    /// glob pattern, can be called repeatedly to focus on several packages.
+    #[must_use]
+    pub fn focus(mut self, pattern: impl Into<String>) -> Self {
+        self.overrides.focus.push(pattern.into());
+        self
+    }
+
+    /// Only reports and fails on the cycles involving a workspace package whose manifest is under the
+    /// path prefix, relative to the workspace root, can be called repeatedly for several prefixes.
+    ///
+    /// The cycles may still pass through packages outside the scope.
+    #[must_use]
+    pub fn scope(mut self, prefix: impl Into<String>) -> Self {
+        self.overrides.scope.push(prefix.into());
+        self
+    }
+
+    /// Accepts a known cycle.
+    #[must_use]
+    pub fn allow(mut self, cycle: KnownCycle) -> Self {
+        self.overrides.allow.push(cycle);
+        self
+    }
+
+    /// Sets whether cycles that only exist because of dev-dependencies are allowed.
+    #[must_use]
+    pub const fn allow_dev_cycles(mut self, allow: bool) -> Self {
+        self.overrides.allow_dev_cycles = Some(allow);
+        self
+    }
+
+    /// Sets whether a package's dev-dependency on itself is allowed.
+    #[must_use]
+    pub const fn allow_self_dev_dependency(mut self, allow: bool) -> Self {
+        self.overrides.allow_self_dev_dependency = Some(allow);
+        self
+    }
+
+    /// Sets whether the workspace packages with `publish = false` are left out of the analysis, e.g.
+    /// to check the order the others can be published in.
+    #[must_use]
+    pub const fn publishable_only(mut self, publishable_only: bool) -> Self {
+        self.overrides.publishable_only = Some(publishable_only);
+        self
+    }
+
+    /// Only counts platform-specific dependencies that apply to this target triple.
+    #[must_use]
+    pub fn target(mut self, target: impl Into<String>) -> Self {
+        self.overrides.target = Some(target.into());
+        self
+    }
+
+    /// Enables features, `feature` for every workspace package that has it or `package/feature`.
+    #[must_use]
+    pub fn features(mut self, features: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.features.features.extend(features.into_iter().map(Into::into));
+        self
+    }
+
+    /// Enables all features of all workspace packages.
+    #[must_use]
+    pub const fn all_features(mut self, all: bool) -> Self {
+        self.features.all_features = all;
+        self
+    }
+
+    /// Doesn't enable the `default` feature of the workspace packages.
+    #[must_use]
+    pub const fn no_default_features(mut self, no_default: bool) -> Self {
+        self.features.no_default_features = no_default;
+        self
+    }
+
+    /// Finds the smallest combination of up to `depth` features under which each cycle exists.
+    #[must_use]
+    pub const fn feature_powerset(mut self, depth: usize) -> Self {
+        self.feature_powerset_depth = Some(depth);
+        self
+    }
+
+    /// Lists the individual elementary cycles within each cycle, up to `limit` per cycle.
+    #[must_use]
+    pub const fn all_cycles(mut self, limit: usize) -> Self {
+        self.elementary_cycles_limit = Some(limit);
+        self
+    }
+
+    /// Sets whether dependencies to remove to break each cycle are suggested, along with a shared
+    /// crate to extract from it.
+    #[must_use]
+    pub const fn suggest_cuts(mut self, suggest: bool) -> Self {
+        self.analyses.suggest_cuts = suggest;
+        self
+    }
+
+    /// Sets whether the condensation of the workspace graph, with each cycle collapsed into a single
+    /// node, is computed.
+    #[must_use]
+    pub const fn condense(mut self, condense: bool) -> Self {
+        self.analyses.condense = condense;
+        self
+    }
+
+    /// Sets whether dependencies that would create a new cycle when added are reported.
+    #[must_use]
+    pub const fn near_cycles(mut self, near_cycles: bool) -> Self {
+        self.analyses.near_cycles = near_cycles;
+        self
+    }
+
+    /// Sets whether feature definitions are checked for cycles as well.
+    #[must_use]
+    pub const fn check_features(mut self, check: bool) -> Self {
+        self.overrides.check_features = Some(check);
+        self
+    }
+
+    /// Sets how cycles that aren't suppressed are treated.
+    #[must_use]
+    pub const fn severity(mut self, severity: Severity) -> Self {
+        self.overrides.severity = Some(severity);
+        self
+    }
+
+    /// Tolerates this many cycles before the check fails.
+    #[must_use]
+    pub const fn max_cycles(mut self, max: usize) -> Self {
+        self.overrides.max_cycles = Some(max);
+        self
+    }
+
+    /// Accepts the cycles recorded in this baseline file, can be called several times.
+    #[must_use]
+    pub fn baseline(mut self, path: impl AsRef<Path>) -> Self {
+        self.baselines.push(path.as_ref().to_owned());
+        self
+    }
+
+    /// Sets whether baseline and allowlist entries whose cycles no longer exist fail the check.
+    #[must_use]
+    pub const fn strict_baseline(mut self, strict: bool) -> Self {
+        self.strict_baseline = strict;
+        self
+    }
+
+    /// Records the cycles in this baseline file, overwriting it, and accepts them. The baselines
+    /// given with [`Checker::baseline`] aren't applied, the new one replaces them.
+    #[must_use]
+    pub fn write_baseline(mut self, path: impl AsRef<Path>) -> Self {
+        self.write_baseline = Some(path.as_ref().to_owned());
+        self
+    }
+
+    /// Checks the report against the ratchet state in this file, creating or updating it, see
+    /// [`ratchet::apply`]. Its outcome is in [`Analysis::ratchet`].
+    #[must_use]
+    pub fn ratchet(mut self, path: impl AsRef<Path>) -> Self {
+        self.ratchet = Some(path.as_ref().to_owned());
+        self
+    }
+
+    /// Also checks the rule, after the built-in ones.
+    #[must_use]
+    pub fn rule(mut self, rule: impl Rule + 'static) -> Self {
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    /// Runs the check, returning the report if it passes, possibly with suppressed cycles.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`CheckError::Failed`] if the check fails, or with [`CheckError::Load`] if it can't
+    /// be run, see [`Checker::analyze`].
+    pub fn run(self) -> Result<Report, CheckError> {
+        let report = self.analyze().map_err(CheckError::Load)?.report;
+        if report.is_failure() {
+            return Err(CheckError::Failed(Box::new(report)));
+        }
+        Ok(report)
+    }
+
+    /// Runs the check, also returning the loaded metadata and configuration. Unlike
+    /// [`Checker::run`], the report is returned whether the check passes or not.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the metadata, including the default members, the excluded crates or the path
+    /// dependencies outside the workspace if they are needed, the configuration, a baseline or the
+    /// ratchet state can't be loaded, if the feature selection or the target is invalid, or if the
+    /// baseline or the ratchet state can't be written.
+    pub fn analyze(mut self) -> Result<Analysis> {
+        let (metadata, config, resolve_error) = self.load()?;
+
+        let mut options = config.graph_options()?;
+        options.resolved = self.loading.resolved;
+        // Cargo's resolve graph already only has the dependencies the selected features activate
+        let resolved_features = self.loading.resolved && metadata.resolve.is_some();
+        if !self.features.is_all() && !resolved_features {
+            options.features = Some(EnabledFeatures::resolve(&metadata, &self.features)?);
+        }
+
+        let rules = self.enabled_rules(&config)?;
+        let mut report = analyze_with_rules(&metadata, &options, &rules);
+        report.resolve_error = resolve_error;
+        config.apply_focus(&mut report)?;
+        if let Some(depth) = self.feature_powerset_depth {
+            powerset::apply(&metadata, &options, depth, &mut report)?;
+        }
+        match self.elementary_cycles_limit {
+            Some(limit) => elementary::apply(&mut report, limit),
+            None => elementary::count(&mut report),
+        }
+        if self.analyses.suggest_cuts {
+            cuts::apply(&mut report);
+            extraction::apply(&mut report);
+        }
+        if self.analyses.condense {
+            condensation::apply(&mut report);
+        }
+        if self.analyses.near_cycles {
+            near_cycles::apply(&mut report);
+        }
+        config.apply(&mut report);
+
+        if config.check_features == Some(true) {
+            report.feature_cycles = FeatureGraph::new(&metadata).cycles().iter().map(|c| FeatureCycle::new(c)).collect();
+        }
+
+        if self.strict_baseline {
+            report.stale_suppressions = baseline::stale(&report, &config.allow, baseline::ALLOWLIST_SOURCE);
+        }
+        if let Some(path) = &self.write_baseline {
+            let baseline = Baseline::from_report(&report);
+            baseline.save(path)?;
+            baseline.apply(&mut report);
+        } else {
+            for path in &self.baselines {
+                let baseline = Baseline::load(path)?;
+                baseline.apply(&mut report);
+                if self.strict_baseline {
+                    let stale = baseline::stale(&report, &baseline.cycles, &path.display().to_string());
+                    report.stale_suppressions.extend(stale);
+                }
+            }
+        }
+        let ratchet = self.ratchet.as_deref().map(|path| ratchet::apply(path, &mut report)).transpose()?;
+
+        Ok(Analysis {
+            metadata,
+            config,
+            report,
+            ratchet,
+        })
+    }
+
+    /// Returns the rules the check would run: the built-in ones, the ones enabled by the
+    /// configuration and the custom ones.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the metadata or the configuration can't be loaded, see [`Checker::analyze`].
+    pub fn rules(mut self) -> Result<Vec<Box<dyn Rule>>> {
+        let (_, config, _) = self.load()?;
+        self.enabled_rules(&config)
+    }
+
+    /// Loads the metadata and the configuration, with the error of resolving the dependencies if the
+    /// unresolved metadata was loaded instead.
+    fn load(&mut self) -> Result<(Metadata, Config, Option<String>)> {
+        let mut resolve_error = None;
+        let mut metadata = match self.metadata.take() {
+            Some(metadata) => metadata,
+            None if self.loading.resolved => load_resolved_metadata(self.manifest_path.as_deref(), &self.features).or_else(|err| {
+                resolve_error = Some(format!("{err:#}"));
+                load_metadata(self.manifest_path.as_deref())
+            })?,
+            None => load_metadata(self.manifest_path.as_deref())?,
+        };
+        if self.loading.default_members_only {
+            retain_default_members(&mut metadata)?;
+        }
+        if self.loading.include.excluded {
+            include_excluded(&mut metadata)?;
+        }
+        if self.loading.include.path_workspaces {
+            include_path_workspaces(&mut metadata)?;
+        }
+        if self.loading.include.path_deps {
+            include_path_dependencies(&mut metadata)?;
+        }
+        let config = Config::load(&metadata, self.config_path.as_deref())?.merge(core::mem::take(&mut self.overrides));
+        Ok((metadata, config, resolve_error))
+    }
+
+    /// Returns the built-in rules, the ones enabled by the configuration and the custom ones.
+    fn enabled_rules(&mut self, config: &Config) -> Result<Vec<Box<dyn Rule>>> {
+        let mut rules = rules::builtin();
+        rules.extend(config.rules()?);
+        rules.extend(core::mem::take(&mut self.rules));
+        Ok(rules)
+    }
+}
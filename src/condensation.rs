@@ -0,0 +1,99 @@
+//! The condensation of the workspace graph, with each cycle collapsed into a single node.
+
+use petgraph::algo::tarjan_scc;
+use petgraph::graph::{DiGraph, NodeIndex};
+use serde::{Deserialize, Serialize};
+
+use crate::report::Report;
+
+/// The workspace graph with the packages of each cycle collapsed into a single component.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Condensation {
+    /// Components of the graph, a cycle or a single package that isn't part of any.
+    pub components: Vec<Component>,
+    /// Dependencies between the components.
+    pub dependencies: Vec<ComponentDependency>,
+}
+
+/// A node of the condensation.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Component {
+    /// Sorted names of the packages of the component.
+    pub packages: Vec<String>,
+    /// Fingerprint of the cycle the component collapses, none for a single package.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cycle: Option<String>,
+}
+
+/// Dependencies of the packages of one component on those of another.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ComponentDependency {
+    /// Position of the dependent component in [`Condensation::components`].
+    pub from: usize,
+    /// Position of the component being depended on.
+    pub to: usize,
+    /// Number of package dependencies between the components.
+    pub count: usize,
+}
+
+/// Records the condensation of the workspace graph on the report.
+pub fn apply(report: &mut Report) {
+    report.condensation = Some(condense(report));
+}
+
+/// Computes the condensation of the workspace graph of the report.
+///
+/// The components are the strongly connected components of the dependencies of the report, not its
+/// cycles, which may be narrowed down to those of interest.
+pub(crate) fn condense(report: &Report) -> Condensation {
+    let mut graph = DiGraph::<usize, ()>::new();
+    let nodes: Vec<NodeIndex> = (0..report.members.len()).map(|i| graph.add_node(i)).collect();
+    for edge in &report.dependencies {
+        if let (Some(from), Some(to)) = (report.member_index(&edge.from.id), report.member_index(&edge.to.id)) {
+            graph.add_edge(nodes[from], nodes[to], ());
+        }
+    }
+    let mut scc_of = vec![0; report.members.len()];
+    for (i, scc) in tarjan_scc(&graph).iter().enumerate() {
+        for &node in scc {
+            scc_of[graph[node]] = i;
+        }
+    }
+
+    let mut components: Vec<Component> = Vec::new();
+    // Position of each member's component, in the order of the members
+    let mut membership: Vec<usize> = Vec::with_capacity(report.members.len());
+    for (i, member) in report.members.iter().enumerate() {
+        if let Some(first) = scc_of[..i].iter().position(|&scc| scc == scc_of[i]) {
+            membership.push(membership[first]);
+            continue;
+        }
+        let mut packages: Vec<String> = (i..report.members.len())
+            .filter(|&j| scc_of[j] == scc_of[i])
+            .map(|j| report.members[j].name.clone())
+            .collect();
+        packages.sort_unstable();
+        membership.push(components.len());
+        components.push(Component {
+            packages,
+            cycle: report.cycle_containing(&member.id).map(|c| c.fingerprint.clone()),
+        });
+    }
+
+    let mut dependencies: Vec<ComponentDependency> = Vec::new();
+    for edge in &report.dependencies {
+        let (Some(from), Some(to)) = (report.member_index(&edge.from.id), report.member_index(&edge.to.id)) else {
+            continue;
+        };
+        let (from, to) = (membership[from], membership[to]);
+        if from == to {
+            continue;
+        }
+        match dependencies.iter_mut().find(|d| d.from == from && d.to == to) {
+            Some(dependency) => dependency.count += 1,
+            None => dependencies.push(ComponentDependency { from, to, count: 1 }),
+        }
+    }
+
+    Condensation { components, dependencies }
+}
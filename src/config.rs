@@ -0,0 +1,373 @@
+//! Configuration read from the `[workspace.metadata.ensure-no-cyclic-deps]` table of the workspace
+//! manifest and from the `.ensure-no-cyclic-deps.toml` file.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use cargo_metadata::{Metadata, PackageId};
+use glob::Pattern;
+use serde::Deserialize;
+use toml_edit::{Array, ArrayOfTables, DocumentMut, Item, Table, Value, value};
+
+use crate::baseline::{self, KnownCycle};
+use crate::graph::{DepKind, GraphOptions};
+use crate::output::OutputFormat;
+use crate::report::{Report, Severity};
+use crate::rules::{
+    DeniedDependency, DenyRule, DepthRule, FanLimit, FanRule, Group, GroupRule, Layer, LayerRule, PluginRule, ProcMacroRule, Rule,
+    TestUtilsRule, Visibility, VisibilityRule,
+};
+use crate::target::Target;
+
+/// Key of the configuration table in the workspace metadata.
+const METADATA_KEY: &str = "ensure-no-cyclic-deps";
+
+/// Name of the configuration file looked up in the workspace root.
+pub const CONFIG_FILE_NAME: &str = ".ensure-no-cyclic-deps.toml";
+
+/// Configuration of the check.
+///
+/// The same options are accepted in the workspace metadata and in the configuration file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    /// Names or glob patterns of workspace packages left out of the analysis.
+    pub ignore: Vec<String>,
+    /// Names or glob patterns of the workspace packages the analysis is limited to, all if empty.
+    pub packages: Vec<String>,
+    /// Names or glob patterns of the workspace packages whose cycles are reported, all if empty.
+    pub focus: Vec<String>,
+    /// Path prefixes, relative to the workspace root, of the workspace packages whose cycles are
+    /// reported, all if empty.
+    pub scope: Vec<String>,
+    /// Accepted cycles that don't fail the check.
+    pub allow: Vec<KnownCycle>,
+    /// Dependency kinds that contribute edges to the graph.
+    pub kinds: Option<Vec<DepKind>>,
+    /// Default output format.
+    pub format: Option<OutputFormat>,
+    /// Severity of detected cycles.
+    pub severity: Option<Severity>,
+    /// Number of cycles that are tolerated before the check fails.
+    pub max_cycles: Option<usize>,
+    /// Whether cycles that only exist because of dev-dependencies are allowed.
+    pub allow_dev_cycles: Option<bool>,
+    /// Whether a package's dev-dependency on itself is allowed.
+    pub allow_self_dev_dependency: Option<bool>,
+    /// Whether the workspace packages with `publish = false` are left out of the analysis.
+    pub publishable_only: Option<bool>,
+    /// Target triple for which platform-specific dependencies are evaluated.
+    pub target: Option<String>,
+    /// Whether feature definitions are checked for cycles as well.
+    pub check_features: Option<bool>,
+    /// Layers of workspace crates, from the lowest to the highest, crates must not depend on crates of
+    /// a higher layer.
+    pub layers: Vec<Layer>,
+    /// Whether crates may only depend on crates of their own layer or the one right below it.
+    pub strict_layering: Option<bool>,
+    /// Dependencies between workspace crates that aren't allowed, even if they don't create a cycle.
+    pub deny: Vec<DeniedDependency>,
+    /// Workspace crates that only some crates may depend on, besides those restricted in their
+    /// package metadata.
+    pub visibility: Vec<Visibility>,
+    /// Maximum number of hops of a dependency chain between workspace crates.
+    pub max_depth: Option<usize>,
+    /// Maximum number of direct workspace dependencies of a workspace crate.
+    pub max_dependencies: Option<usize>,
+    /// Maximum number of direct workspace dependents of a workspace crate.
+    pub max_dependents: Option<usize>,
+    /// Limits of the direct dependencies and dependents of some crates, instead of the defaults.
+    pub fan_limits: Vec<FanLimit>,
+    /// Names or glob patterns of the runtime crates that proc-macro crates must not depend on.
+    pub runtime_crates: Vec<String>,
+    /// Names or glob patterns of the test utility crates, which may only be depended on through
+    /// dev-dependencies.
+    pub test_utils: Vec<String>,
+    /// Named groups of workspace crates that must not cyclically depend on each other.
+    pub groups: Vec<Group>,
+    /// Whether the workspace crates in no group are grouped by their top-level directory.
+    pub group_by_directory: Option<bool>,
+    /// Paths of WASM modules checking the workspace graph, relative to the workspace root, see
+    /// [`PluginRule`].
+    pub plugins: Vec<PathBuf>,
+    /// Command running the plugins, to which the path of the module is appended, `wasmtime run` by
+    /// default. The runtime is an external program, it has to be installed and on the `PATH`.
+    pub wasm_runtime: Vec<String>,
+}
+
+impl Config {
+    /// Loads the configuration from the workspace metadata and the configuration file.
+    ///
+    /// The configuration file is `config_path` if given, otherwise [`CONFIG_FILE_NAME`] in the
+    /// workspace root if it exists. Its options take precedence over the workspace metadata, lists
+    /// are combined.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the workspace metadata or the configuration file can't be read or parsed.
+    pub fn load(metadata: &Metadata, config_path: Option<&Path>) -> Result<Self> {
+        let config = Self::from_metadata(metadata)?;
+
+        let default_path = metadata.workspace_root.join(CONFIG_FILE_NAME).into_std_path_buf();
+        match config_path {
+            Some(path) => Ok(config.merge(Self::from_file(path)?)),
+            None if default_path.is_file() => Ok(config.merge(Self::from_file(&default_path)?)),
+            None => Ok(config),
+        }
+    }
+
+    /// Reads the configuration from the workspace metadata, which may be absent.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the table doesn't match the configuration options.
+    pub fn from_metadata(metadata: &Metadata) -> Result<Self> {
+        metadata.workspace_metadata.get(METADATA_KEY).map_or_else(
+            || Ok(Self::default()),
+            |value| Self::deserialize(value).with_context(|| format!("Failed to parse [workspace.metadata.{METADATA_KEY}]")),
+        )
+    }
+
+    /// Reads a configuration file.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be read or parsed.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path).with_context(|| format!("Failed to read config {}", path.display()))?;
+        toml::from_str(&contents).with_context(|| format!("Failed to parse config {}", path.display()))
+    }
+
+    /// Combines two configurations, options set in `other` take precedence.
+    #[must_use]
+    pub fn merge(mut self, other: Self) -> Self {
+        self.ignore.extend(other.ignore);
+        self.packages.extend(other.packages);
+        self.focus.extend(other.focus);
+        self.scope.extend(other.scope);
+        self.allow.extend(other.allow);
+        self.deny.extend(other.deny);
+        self.visibility.extend(other.visibility);
+        self.fan_limits.extend(other.fan_limits);
+        self.runtime_crates.extend(other.runtime_crates);
+        self.test_utils.extend(other.test_utils);
+        self.groups.extend(other.groups);
+        self.plugins.extend(other.plugins);
+        Self {
+            ignore: self.ignore,
+            packages: self.packages,
+            focus: self.focus,
+            scope: self.scope,
+            allow: self.allow,
+            kinds: other.kinds.or(self.kinds),
+            format: other.format.or(self.format),
+            severity: other.severity.or(self.severity),
+            max_cycles: other.max_cycles.or(self.max_cycles),
+            allow_dev_cycles: other.allow_dev_cycles.or(self.allow_dev_cycles),
+            allow_self_dev_dependency: other.allow_self_dev_dependency.or(self.allow_self_dev_dependency),
+            publishable_only: other.publishable_only.or(self.publishable_only),
+            target: other.target.or(self.target),
+            check_features: other.check_features.or(self.check_features),
+            // The layers are ordered, so they are replaced rather than combined
+            layers: if other.layers.is_empty() { self.layers } else { other.layers },
+            strict_layering: other.strict_layering.or(self.strict_layering),
+            deny: self.deny,
+            visibility: self.visibility,
+            max_depth: other.max_depth.or(self.max_depth),
+            max_dependencies: other.max_dependencies.or(self.max_dependencies),
+            max_dependents: other.max_dependents.or(self.max_dependents),
+            fan_limits: self.fan_limits,
+            runtime_crates: self.runtime_crates,
+            test_utils: self.test_utils,
+            groups: self.groups,
+            group_by_directory: other.group_by_directory.or(self.group_by_directory),
+            plugins: self.plugins,
+            wasm_runtime: if other.wasm_runtime.is_empty() {
+                self.wasm_runtime
+            } else {
+                other.wasm_runtime
+            },
+        }
+    }
+
+    /// Returns the options for building the workspace graph.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a package pattern isn't a valid glob pattern or if the `cfg` values of the
+    /// configured target can't be queried.
+    pub fn graph_options(&self) -> Result<GraphOptions> {
+        for pattern in self.ignore.iter().chain(&self.packages) {
+            Pattern::new(pattern).with_context(|| format!("Invalid package pattern `{pattern}`"))?;
+        }
+        let defaults = GraphOptions::default();
+        Ok(GraphOptions {
+            kinds: self.kinds.clone().unwrap_or(defaults.kinds),
+            ignore: self.ignore.clone(),
+            packages: self.packages.clone(),
+            allow_self_dev_dependency: self.allow_self_dev_dependency.unwrap_or(defaults.allow_self_dev_dependency),
+            publishable_only: self.publishable_only.unwrap_or(defaults.publishable_only),
+            target: self.target.as_deref().map(Target::load).transpose()?,
+            features: None,
+            resolved: defaults.resolved,
+        })
+    }
+
+    /// Returns the rules configured besides the [built-in ones](crate::rules::builtin).
+    ///
+    /// # Errors
+    ///
+    /// Fails if a rule's options are invalid, e.g. a pattern isn't a valid glob pattern.
+    pub fn rules(&self) -> Result<Vec<Box<dyn Rule>>> {
+        let mut rules: Vec<Box<dyn Rule>> = Vec::new();
+        if !self.layers.is_empty() {
+            rules.push(Box::new(LayerRule::new(&self.layers, self.strict_layering == Some(true))?));
+        }
+        if !self.deny.is_empty() {
+            rules.push(Box::new(DenyRule::new(&self.deny)?));
+        }
+        if let Some(max_depth) = self.max_depth {
+            rules.push(Box::new(DepthRule::new(max_depth)));
+        }
+        if self.max_dependencies.is_some() || self.max_dependents.is_some() || !self.fan_limits.is_empty() {
+            rules.push(Box::new(FanRule::new(
+                self.max_dependencies,
+                self.max_dependents,
+                &self.fan_limits,
+            )?));
+        }
+        if !self.runtime_crates.is_empty() {
+            rules.push(Box::new(ProcMacroRule::new(&self.runtime_crates)?));
+        }
+        if !self.test_utils.is_empty() {
+            rules.push(Box::new(TestUtilsRule::new(&self.test_utils)?));
+        }
+        let group_by_directory = self.group_by_directory == Some(true);
+        if !self.groups.is_empty() || group_by_directory {
+            rules.push(Box::new(GroupRule::new(&self.groups, group_by_directory)?));
+        }
+        // Crates may also restrict their visibility in their package metadata
+        rules.push(Box::new(VisibilityRule::new(&self.visibility)?));
+        for path in &self.plugins {
+            rules.push(Box::new(PluginRule::new(path, &self.wasm_runtime)));
+        }
+        Ok(rules)
+    }
+
+    /// Records the cycles that don't involve any of the focused packages, or any of the packages in
+    /// scope, as out of focus, and removes the rule violations that don't involve any of the packages
+    /// in scope.
+    ///
+    /// The cycles stay in the report, the analyses of the graph rely on all of them.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a focus pattern isn't a valid glob pattern.
+    pub fn apply_focus(&self, report: &mut Report) -> Result<()> {
+        if !self.focus.is_empty() {
+            let patterns = self
+                .focus
+                .iter()
+                .map(|p| Pattern::new(p).with_context(|| format!("Invalid focus pattern `{p}`")))
+                .collect::<Result<Vec<_>>>()?;
+            let unfocused = report
+                .cycles
+                .iter()
+                .filter(|c| !c.packages.iter().any(|p| patterns.iter().any(|pattern| pattern.matches(&p.name))));
+            report.out_of_focus.extend(unfocused.map(|c| c.fingerprint.clone()));
+        }
+        if !self.scope.is_empty() {
+            let in_scope: HashSet<PackageId> = report
+                .members
+                .iter()
+                .filter(|m| {
+                    m.manifest_path
+                        .strip_prefix(&report.workspace_root)
+                        .is_ok_and(|path| self.scope.iter().any(|prefix| path.starts_with(prefix.trim_start_matches("./"))))
+                })
+                .map(|m| m.id.clone())
+                .collect();
+            let out_of_scope = report
+                .cycles
+                .iter()
+                .filter(|c| !c.packages.iter().any(|p| in_scope.contains(&p.id)));
+            report.out_of_focus.extend(out_of_scope.map(|c| c.fingerprint.clone()));
+            report
+                .violations
+                .retain(|v| v.edges.iter().any(|e| in_scope.contains(&e.from.id) || in_scope.contains(&e.to.id)));
+        }
+        Ok(())
+    }
+
+    /// Applies the allowed cycles, the severity and the cycle budget to the report, and records the
+    /// layers of the members.
+    pub fn apply(&self, report: &mut Report) {
+        if self.allow_dev_cycles == Some(true) {
+            report.allow_dev_cycles();
+        }
+        baseline::suppress(report, &self.allow);
+        report.severity = self.severity.unwrap_or_default();
+        report.max_cycles = self.max_cycles;
+        // Invalid layers already failed the check of the rules
+        if let Ok(layers) = LayerRule::new(&self.layers, false) {
+            for member in &mut report.members {
+                member.layer = layers.layer(&member.name).map(String::from);
+            }
+        }
+    }
+}
+
+/// Appends the entries to the `allow` list of the configuration file, creating the file if missing.
+///
+/// The rest of the file, including comments and formatting, is preserved.
+///
+/// # Errors
+///
+/// Fails if the file can't be read, parsed or written, or if its `allow` key isn't a list.
+pub fn append_allowed(path: &Path, entries: &[KnownCycle]) -> Result<()> {
+    let contents = if path.exists() {
+        fs::read_to_string(path).with_context(|| format!("Failed to read config {}", path.display()))?
+    } else {
+        String::new()
+    };
+    let mut document: DocumentMut = contents
+        .parse()
+        .with_context(|| format!("Failed to parse config {}", path.display()))?;
+
+    let allow = document.entry("allow").or_insert_with(|| Item::ArrayOfTables(ArrayOfTables::new()));
+    match allow {
+        Item::ArrayOfTables(tables) => {
+            for entry in entries {
+                tables.push(entry_table(entry));
+            }
+        }
+        Item::Value(Value::Array(array)) => {
+            for entry in entries {
+                array.push(entry_table(entry).into_inline_table());
+            }
+            array.fmt();
+        }
+        _ => bail!("Failed to update config {}: `allow` is not a list", path.display()),
+    }
+
+    fs::write(path, document.to_string()).with_context(|| format!("Failed to write config {}", path.display()))
+}
+
+fn entry_table(entry: &KnownCycle) -> Table {
+    let mut table = Table::new();
+    if let Some(fingerprint) = &entry.fingerprint {
+        table.insert("fingerprint", value(fingerprint.as_str()));
+    }
+    if !entry.packages.is_empty() {
+        table.insert("packages", value(entry.packages.iter().map(String::as_str).collect::<Array>()));
+    }
+    if let Some(expires) = entry.expires {
+        table.insert("expires", value(expires.to_string()));
+    }
+    if let Some(reason) = &entry.reason {
+        table.insert("reason", value(reason.as_str()));
+    }
+    table
+}
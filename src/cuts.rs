@@ -0,0 +1,87 @@
+//! Suggestions of dependency edges to remove to break the cycles, a heuristic minimum feedback arc
+//! set.
+
+use cargo_metadata::PackageId;
+
+use crate::graph;
+use crate::report::{Cycle, Edge, Report};
+
+/// Records on each cycle of the report a small set of its edges whose removal breaks it.
+///
+/// The edges are chosen with the heuristic of Eades, Lin and Smyth, after which edges that aren't
+/// needed to break the cycle are dropped again.
+pub fn apply(report: &mut Report) {
+    for cycle in &mut report.cycles {
+        cycle.suggested_cuts = Some(suggest_cuts(cycle));
+    }
+}
+
+fn suggest_cuts(cycle: &Cycle) -> Vec<Edge> {
+    let nodes: Vec<&PackageId> = cycle.packages.iter().map(|p| &p.id).collect();
+    let position = |id: &PackageId| nodes.iter().position(|n| *n == id);
+    let order = ordering(&nodes, &cycle.edges);
+    let rank = |id: &PackageId| position(id).map(|idx| order[idx]);
+
+    // Edges pointing backwards in the ordering, including self-loops, break all cycles when removed
+    let mut cuts: Vec<&Edge> = cycle.edges.iter().filter(|e| rank(&e.to.id) <= rank(&e.from.id)).collect();
+
+    let mut idx = 0;
+    while idx < cuts.len() {
+        let candidate = cuts[idx];
+        let remaining = cycle
+            .edges
+            .iter()
+            .filter(|e| !cuts.iter().any(|c| core::ptr::eq(*c, *e)) || core::ptr::eq(*e, candidate));
+        if graph::has_cycle(remaining.map(|e| (&e.from.id, &e.to.id))) {
+            idx += 1;
+        } else {
+            cuts.remove(idx);
+        }
+    }
+
+    cuts.into_iter().cloned().collect()
+}
+
+/// Orders the nodes so few edges point backwards, returning the rank of each node.
+fn ordering(nodes: &[&PackageId], edges: &[Edge]) -> Vec<usize> {
+    let index = |id: &PackageId| nodes.iter().position(|n| *n == id);
+    let arcs: Vec<(usize, usize)> = edges
+        .iter()
+        .filter_map(|e| Some((index(&e.from.id)?, index(&e.to.id)?)))
+        .filter(|(from, to)| from != to)
+        .collect();
+
+    let mut removed = vec![false; nodes.len()];
+    let mut front = Vec::new();
+    let mut back = Vec::new();
+    let degree = |node: usize, removed: &[bool]| {
+        let live = |&&(from, to): &&(usize, usize)| !removed[from] && !removed[to];
+        let out = arcs.iter().filter(live).filter(|(from, _)| *from == node).count();
+        let into = arcs.iter().filter(live).filter(|(_, to)| *to == node).count();
+        (out, into)
+    };
+
+    while removed.iter().any(|r| !r) {
+        let live: Vec<usize> = (0..nodes.len()).filter(|&n| !removed[n]).collect();
+        if let Some(&sink) = live.iter().find(|&&n| degree(n, &removed).0 == 0) {
+            removed[sink] = true;
+            back.push(sink);
+        } else if let Some(&source) = live.iter().find(|&&n| degree(n, &removed).1 == 0) {
+            removed[source] = true;
+            front.push(source);
+        } else if let Some(&node) = live.iter().max_by_key(|&&n| {
+            let (out, into) = degree(n, &removed);
+            // Ties go to the first node
+            (out.cast_signed() - into.cast_signed(), core::cmp::Reverse(n))
+        }) {
+            removed[node] = true;
+            front.push(node);
+        }
+    }
+
+    let mut rank = vec![0; nodes.len()];
+    for (position, node) in front.into_iter().chain(back.into_iter().rev()).enumerate() {
+        rank[node] = position;
+    }
+    rank
+}
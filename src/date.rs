@@ -0,0 +1,96 @@
+//! Calendar dates used for expiring suppressions.
+
+use core::fmt;
+use core::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Error, bail};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A calendar date in the `YYYY-MM-DD` format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    year: i64,
+    month: u32,
+    day: u32,
+}
+
+impl Date {
+    /// Returns the current date in UTC.
+    #[must_use]
+    pub fn today() -> Self {
+        let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        Self::from_days_since_epoch(i64::try_from(seconds / 86_400).unwrap_or(i64::MAX))
+    }
+
+    /// Converts days since 1970-01-01 to a date, using Howard Hinnant's `civil_from_days` algorithm.
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "month and day are always in range"
+    )]
+    const fn from_days_since_epoch(days: i64) -> Self {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+        Self { year, month, day }
+    }
+}
+
+impl FromStr for Date {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(3, '-');
+        let (Some(year), Some(month), Some(day)) = (parts.next(), parts.next(), parts.next()) else {
+            bail!("expected a date in the YYYY-MM-DD format, got `{s}`");
+        };
+        let date = Self {
+            year: year.parse().with_context(|| format!("invalid year in `{s}`"))?,
+            month: month.parse().with_context(|| format!("invalid month in `{s}`"))?,
+            day: day.parse().with_context(|| format!("invalid day in `{s}`"))?,
+        };
+        if !(1..=12).contains(&date.month) {
+            bail!("expected a date in the YYYY-MM-DD format, got `{s}`");
+        }
+        if !(1..=days_in_month(date.year, date.month)).contains(&date.day) {
+            bail!("invalid day in `{s}`, the month has {} days", days_in_month(date.year, date.month));
+        }
+        Ok(date)
+    }
+}
+
+/// Returns the number of days of the month, February has 29 in leap years.
+const fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl Serialize for Date {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Date {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
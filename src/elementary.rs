@@ -0,0 +1,246 @@
+//! Enumeration of the elementary cycles within each reported cycle, using Johnson's algorithm.
+
+use std::collections::{BTreeMap, HashMap};
+
+use cargo_metadata::PackageId;
+
+use crate::report::{Culprit, Cycle, PackageRef, Report};
+
+/// Number of elementary cycles after which counting the elementary cycles of a cycle stops.
+pub const COUNT_LIMIT: usize = 100_000;
+
+/// Lists the shortest `limit` elementary cycles on each cycle of the report.
+///
+/// Elementary cycles visit each package at most once. They are counted up to [`COUNT_LIMIT`], and
+/// each is listed once, starting with its alphabetically first package, not once per package it
+/// could start from.
+///
+/// The dependencies are also ranked by the number of elementary cycles they appear in, see
+/// [`Report::culprits`].
+pub fn apply(report: &mut Report, limit: usize) {
+    report.elementary_cycles_limit = Some(limit);
+    let mut counts: BTreeMap<DependencyKey, usize> = BTreeMap::new();
+    for cycle in &mut report.cycles {
+        let search = elementary_cycles(cycle, limit);
+        cycle.elementary_cycles = Some(search.cycles);
+        cycle.elementary_cycle_count = Some(search.count);
+        // Packages of different workspaces may share names, so their dependencies are told apart by id
+        for (key, count) in search.edge_counts {
+            *counts.entry(key).or_default() += count;
+        }
+    }
+    report.culprits = culprits(counts);
+}
+
+/// Counts the elementary cycles on each cycle of the report without listing them.
+///
+/// Counting stops at [`COUNT_LIMIT`] per cycle.
+pub fn count(report: &mut Report) {
+    for cycle in &mut report.cycles {
+        cycle.elementary_cycle_count = Some(elementary_cycles(cycle, 0).count);
+    }
+}
+
+/// Ranks the dependencies by the number of elementary cycles they appear in, most frequent first.
+fn culprits(counts: BTreeMap<DependencyKey, usize>) -> Vec<Culprit> {
+    let mut culprits: Vec<Culprit> = counts
+        .into_iter()
+        .map(|((from_name, to_name, from_id, to_id), cycles)| Culprit {
+            from: PackageRef {
+                name: from_name,
+                id: from_id,
+            },
+            to: PackageRef { name: to_name, id: to_id },
+            cycles,
+        })
+        .collect();
+    // The sort is stable, so equally frequent dependencies stay sorted by name
+    culprits.sort_by_key(|c| core::cmp::Reverse(c.cycles));
+    culprits
+}
+
+/// A dependency by the names and then the ids of the dependent package and the dependency, so
+/// dependencies sort by name.
+type DependencyKey = (String, String, PackageId, PackageId);
+
+/// Elementary cycles between the packages of a cycle.
+struct Search {
+    /// The shortest cycles as package names in dependency order, shortest first.
+    cycles: Vec<Vec<String>>,
+    /// Total number of cycles.
+    count: usize,
+    /// Number of cycles each dependency appears in.
+    edge_counts: Vec<(DependencyKey, usize)>,
+}
+
+/// Searches the shortest `limit` elementary cycles between the packages of the cycle.
+fn elementary_cycles(cycle: &Cycle, limit: usize) -> Search {
+    let mut packages: Vec<&PackageRef> = cycle.packages.iter().collect();
+    packages.sort_unstable_by(|a, b| (&a.name, &a.id).cmp(&(&b.name, &b.id)));
+    packages.dedup_by(|a, b| a.id == b.id);
+    let position = |id: &PackageId| packages.iter().position(|p| &p.id == id);
+    let mut adjacency = vec![Vec::new(); packages.len()];
+    for edge in &cycle.edges {
+        if let (Some(from), Some(to)) = (position(&edge.from.id), position(&edge.to.id)) {
+            adjacency[from].push(to);
+        }
+    }
+
+    let mut search = Johnson::new(&adjacency, limit);
+    search.run();
+    // The search starts each cycle at its smallest node, i.e. its alphabetically first package
+    let cycles = search
+        .cycles
+        .into_iter()
+        .map(|c| c.into_iter().map(|idx| packages[idx].name.clone()).collect())
+        .collect();
+    let edge_counts = search
+        .edge_counts
+        .into_iter()
+        .map(|((from, to), count)| {
+            let (from, to) = (packages[from], packages[to]);
+            ((from.name.clone(), to.name.clone(), from.id.clone(), to.id.clone()), count)
+        })
+        .collect();
+    Search {
+        cycles,
+        count: search.count,
+        edge_counts,
+    }
+}
+
+/// State of Johnson's algorithm over a graph given by adjacency lists.
+struct Johnson<'a> {
+    adjacency: &'a [Vec<usize>],
+    /// Maximum number of cycles kept.
+    limit: usize,
+    /// Number of cycles found.
+    count: usize,
+    /// Number of cycles found that each edge appears in.
+    edge_counts: HashMap<(usize, usize), usize>,
+    /// Node the current search starts from, only nodes from it upwards are considered.
+    start: usize,
+    /// Nodes of the strongly connected component of `start` among the considered nodes.
+    component: Vec<bool>,
+    blocked: Vec<bool>,
+    blocked_by: Vec<Vec<usize>>,
+    stack: Vec<usize>,
+    cycles: Vec<Vec<usize>>,
+}
+
+impl<'a> Johnson<'a> {
+    fn new(adjacency: &'a [Vec<usize>], limit: usize) -> Self {
+        let n = adjacency.len();
+        Self {
+            adjacency,
+            limit,
+            count: 0,
+            edge_counts: HashMap::new(),
+            start: 0,
+            component: vec![false; n],
+            blocked: vec![false; n],
+            blocked_by: vec![Vec::new(); n],
+            stack: Vec::new(),
+            cycles: Vec::new(),
+        }
+    }
+
+    fn run(&mut self) {
+        for start in 0..self.adjacency.len() {
+            if self.count >= COUNT_LIMIT {
+                return;
+            }
+            self.start = start;
+            self.component = self.component_of(start);
+            for node in start..self.adjacency.len() {
+                self.blocked[node] = false;
+                self.blocked_by[node].clear();
+            }
+            self.circuit(start);
+        }
+    }
+
+    /// Returns the nodes from `start` upwards that are reachable from `start` and reach it.
+    fn component_of(&self, start: usize) -> Vec<bool> {
+        let n = self.adjacency.len();
+        let forward = reachable(start, n, |node| {
+            self.adjacency[node].iter().copied().filter(|&w| w >= start).collect()
+        });
+        let backward = reachable(start, n, |node| (start..n).filter(|&v| self.adjacency[v].contains(&node)).collect());
+        forward.iter().zip(&backward).map(|(&f, &b)| f && b).collect()
+    }
+
+    /// Searches for cycles through `node` back to the start, returning whether any was found.
+    fn circuit(&mut self, node: usize) -> bool {
+        let mut found = false;
+        self.stack.push(node);
+        self.blocked[node] = true;
+
+        for &next in &self.adjacency[node] {
+            if self.count >= COUNT_LIMIT {
+                break;
+            }
+            if !self.component[next] {
+                continue;
+            }
+            if next == self.start {
+                self.record();
+                found = true;
+            } else if !self.blocked[next] && self.circuit(next) {
+                found = true;
+            }
+        }
+
+        if found {
+            self.unblock(node);
+        } else {
+            for &next in &self.adjacency[node] {
+                if self.component[next] && !self.blocked_by[next].contains(&node) {
+                    self.blocked_by[next].push(node);
+                }
+            }
+        }
+        self.stack.pop();
+        found
+    }
+
+    /// Counts the cycle on the stack and its edges, keeping it if it is among the `limit` shortest
+    /// ones, which are sorted by length and then by the order they are found in.
+    fn record(&mut self) {
+        self.count += 1;
+        for (i, &from) in self.stack.iter().enumerate() {
+            let to = self.stack.get(i + 1).copied().unwrap_or(self.start);
+            *self.edge_counts.entry((from, to)).or_default() += 1;
+        }
+        let position = self.cycles.partition_point(|c| c.len() <= self.stack.len());
+        if position < self.limit {
+            self.cycles.insert(position, self.stack.clone());
+            self.cycles.truncate(self.limit);
+        }
+    }
+
+    fn unblock(&mut self, node: usize) {
+        self.blocked[node] = false;
+        while let Some(other) = self.blocked_by[node].pop() {
+            if self.blocked[other] {
+                self.unblock(other);
+            }
+        }
+    }
+}
+
+/// Returns the nodes reachable from `start` following `next`.
+fn reachable(start: usize, n: usize, next: impl Fn(usize) -> Vec<usize>) -> Vec<bool> {
+    let mut seen = vec![false; n];
+    seen[start] = true;
+    let mut queue = vec![start];
+    while let Some(node) = queue.pop() {
+        for other in next(node) {
+            if !seen[other] {
+                seen[other] = true;
+                queue.push(other);
+            }
+        }
+    }
+    seen
+}
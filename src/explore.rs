@@ -0,0 +1,143 @@
+//! Interactive exploration of the workspace graph and its cycles, one command per line.
+
+use std::io::{self, BufRead, Write};
+
+use crate::query;
+use crate::report::{Edge, Report};
+
+const HELP: &str = "\
+Commands:
+  list [TEXT]  List the workspace crates, those containing TEXT if given, `*` marks crates in cycles
+  show CRATE   Show the dependencies and dependents of the crate
+  cycles       List the detected cycles
+  next, prev   Step through the cycles, showing the dependencies forming them
+  help         Show this help
+  quit         Stop exploring";
+
+/// Reads commands from the input until it ends or `quit` is entered, writing the answers and a
+/// prompt before each command to the output.
+///
+/// # Errors
+///
+/// Fails if the input can't be read or the output can't be written.
+pub fn run(report: &Report, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    let mut explorer = Explorer { report, current: None };
+    writeln!(
+        output,
+        "Exploring {} workspace crates with {} cycle(s), type `help` for the commands.",
+        report.members.len(),
+        report.cycles.len()
+    )?;
+    write!(output, "> ")?;
+    output.flush()?;
+    for line in input.lines() {
+        let line = line?;
+        let line = line.trim();
+        let (command, argument) = line.split_once(' ').unwrap_or((line, ""));
+        if matches!(command, "quit" | "exit") {
+            return Ok(());
+        }
+        explorer.execute(command, argument.trim(), &mut output)?;
+        write!(output, "> ")?;
+        output.flush()?;
+    }
+    writeln!(output)
+}
+
+struct Explorer<'a> {
+    report: &'a Report,
+    /// Position of the cycle last stepped to.
+    current: Option<usize>,
+}
+
+impl Explorer<'_> {
+    fn execute(&mut self, command: &str, argument: &str, output: &mut impl Write) -> io::Result<()> {
+        match command {
+            "" => Ok(()),
+            "help" | "?" => writeln!(output, "{HELP}"),
+            "list" | "search" => self.list(argument, output),
+            "show" => self.show(argument, output),
+            "cycles" => self.cycles(output),
+            "next" => self.step(true, output),
+            "prev" => self.step(false, output),
+            _ => writeln!(output, "Unknown command `{command}`, type `help` for the commands."),
+        }
+    }
+
+    fn list(&self, text: &str, output: &mut impl Write) -> io::Result<()> {
+        let mut members: Vec<_> = self.report.members.iter().filter(|m| m.name.contains(text)).collect();
+        if members.is_empty() {
+            return writeln!(output, "No workspace crate contains `{text}`.");
+        }
+        members.sort_by(|a, b| a.name.cmp(&b.name));
+        for member in members {
+            let marker = if self.report.is_in_cycle(&member.id) { "*" } else { " " };
+            writeln!(output, "{marker} {}", member.name)?;
+        }
+        Ok(())
+    }
+
+    fn show(&self, name: &str, output: &mut impl Write) -> io::Result<()> {
+        let member = match query::member(self.report, name) {
+            Ok(member) => member,
+            Err(err) => return writeln!(output, "{err}"),
+        };
+        writeln!(output, "{}", member.name)?;
+        let mut dependencies: Vec<&Edge> = self.report.dependencies.iter().filter(|e| e.from.id == member.id).collect();
+        dependencies.sort_by(|a, b| a.to.name.cmp(&b.to.name));
+        let mut dependents: Vec<&Edge> = self.report.dependencies.iter().filter(|e| e.to.id == member.id).collect();
+        dependents.sort_by(|a, b| a.from.name.cmp(&b.from.name));
+
+        writeln!(output, "  dependencies:")?;
+        for edge in &dependencies {
+            writeln!(output, "    {} ({}){}", edge.to.name, edge.label(), self.cycle_marker(edge))?;
+        }
+        writeln!(output, "  dependents:")?;
+        for edge in &dependents {
+            writeln!(output, "    {} ({}){}", edge.from.name, edge.label(), self.cycle_marker(edge))?;
+        }
+        Ok(())
+    }
+
+    fn cycles(&self, output: &mut impl Write) -> io::Result<()> {
+        if self.report.cycles.is_empty() {
+            return writeln!(output, "No cyclic dependencies found.");
+        }
+        for (i, cycle) in self.report.cycles.iter().enumerate() {
+            writeln!(output, "  {}. {} ({})", i + 1, cycle.path(), cycle.fingerprint)?;
+        }
+        Ok(())
+    }
+
+    /// Moves to the next or the previous cycle, wrapping around, and shows it.
+    fn step(&mut self, forward: bool, output: &mut impl Write) -> io::Result<()> {
+        let count = self.report.cycles.len();
+        if count == 0 {
+            return writeln!(output, "No cyclic dependencies found.");
+        }
+        let current = match (self.current, forward) {
+            (None, true) => 0,
+            (None, false) => count - 1,
+            (Some(current), true) => (current + 1) % count,
+            (Some(current), false) => (current + count - 1) % count,
+        };
+        self.current = Some(current);
+        let cycle = &self.report.cycles[current];
+        writeln!(output, "Cycle {} of {count}: {} ({})", current + 1, cycle.path(), cycle.fingerprint)?;
+        for edge in &cycle.edges {
+            writeln!(
+                output,
+                "    {} -> {} ({}) at {}",
+                edge.from.name,
+                edge.to.name,
+                edge.label(),
+                self.report.declaration_location(edge)
+            )?;
+        }
+        Ok(())
+    }
+
+    fn cycle_marker(&self, edge: &Edge) -> &'static str {
+        if self.report.is_cycle_edge(edge) { " [cycle]" } else { "" }
+    }
+}
@@ -0,0 +1,74 @@
+//! Suggestions of a shared lower-level crate to extract from a cycle, so its crates depend on the
+//! new crate instead of on each other.
+
+use core::cmp::Reverse;
+
+use serde::{Deserialize, Serialize};
+
+use crate::graph;
+use crate::report::{Cycle, Edge, Report};
+
+/// Crates of a cycle whose parts used within the cycle could move into a new crate.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Extraction {
+    /// Sorted names of the crates whose used parts move into the new crate.
+    pub crates: Vec<String>,
+    /// Sorted names of the crates of the cycle using these parts, which depend on the new crate.
+    pub dependents: Vec<String>,
+    /// Dependencies within the cycle that point at the new crate afterwards.
+    pub edges: Vec<Edge>,
+}
+
+/// Records on each cycle of the report the crates to extract a shared crate from.
+///
+/// A critical crate is preferred, being part of every path through the cycle, moving its used parts
+/// out breaks the cycle on its own. Otherwise the crates depended on the most within the cycle are
+/// chosen until the cycle is broken.
+pub fn apply(report: &mut Report) {
+    for cycle in &mut report.cycles {
+        cycle.suggested_extraction = Some(suggest_extraction(cycle));
+    }
+}
+
+fn suggest_extraction(cycle: &Cycle) -> Extraction {
+    let dependents_of = |name: &str| cycle.edges.iter().filter(|e| e.to.name == name && e.from.name != name).count();
+    let remaining = |crates: &[String]| {
+        cycle
+            .edges
+            .iter()
+            .filter(|e| !crates.contains(&e.to.name))
+            .map(|e| (&e.from.id, &e.to.id))
+            .collect::<Vec<_>>()
+    };
+
+    let mut crates: Vec<String> = cycle
+        .critical_packages
+        .iter()
+        .max_by_key(|name| (dependents_of(name), Reverse(*name)))
+        .cloned()
+        .into_iter()
+        .collect();
+    if crates.is_empty() {
+        let mut candidates = cycle.package_names();
+        candidates.dedup();
+        // The sort is stable, so equally used crates stay sorted by name
+        candidates.sort_by_key(|name| Reverse(dependents_of(name)));
+        for candidate in candidates {
+            if !graph::has_cycle(remaining(&crates)) {
+                break;
+            }
+            crates.push(candidate);
+        }
+        crates.sort_unstable();
+    }
+
+    let edges: Vec<Edge> = cycle.edges.iter().filter(|e| crates.contains(&e.to.name)).cloned().collect();
+    let mut dependents: Vec<String> = edges
+        .iter()
+        .filter(|e| e.from.name != e.to.name)
+        .map(|e| e.from.name.clone())
+        .collect();
+    dependents.sort_unstable();
+    dependents.dedup();
+    Extraction { crates, dependents, edges }
+}
@@ -0,0 +1,340 @@
+//! Resolution of the features enabled on workspace packages, deciding which optional dependencies
+//! exist.
+
+use std::collections::{BTreeSet, HashMap};
+
+use anyhow::{Result, bail};
+use cargo_metadata::{Dependency, Metadata, Package, PackageId};
+use petgraph::algo::tarjan_scc;
+use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::visit::{Bfs, Reversed};
+
+use crate::graph::workspace_dependency;
+
+/// Features requested on the command line, with the same meaning as for cargo.
+#[derive(Debug, Clone, Default)]
+pub struct FeatureSelection {
+    /// Features to enable, either `feature` for every workspace package that has it or
+    /// `package/feature`.
+    pub features: Vec<String>,
+    /// Whether all features of all workspace packages are enabled.
+    pub all_features: bool,
+    /// Whether the `default` feature of the workspace packages isn't enabled.
+    pub no_default_features: bool,
+}
+
+impl FeatureSelection {
+    /// Returns whether the selection enables every optional dependency, i.e. nothing needs to be
+    /// resolved.
+    #[must_use]
+    pub const fn is_all(&self) -> bool {
+        self.all_features || (self.features.is_empty() && !self.no_default_features)
+    }
+}
+
+/// Features and optional dependencies enabled on each workspace package.
+#[derive(Debug, Clone, Default)]
+pub struct EnabledFeatures {
+    features: HashMap<PackageId, BTreeSet<String>>,
+    dependencies: HashMap<PackageId, BTreeSet<String>>,
+}
+
+impl EnabledFeatures {
+    /// Resolves the features enabled by the selection, following feature definitions and the
+    /// features enabled by dependency declarations between workspace packages until nothing changes.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a requested feature doesn't exist.
+    pub fn resolve(metadata: &Metadata, selection: &FeatureSelection) -> Result<Self> {
+        let packages = metadata.workspace_packages();
+        let mut enabled = Self::default();
+
+        let requested: Vec<&str> = selection
+            .features
+            .iter()
+            .flat_map(|f| f.split([',', ' ']))
+            .filter(|f| !f.is_empty())
+            .collect();
+        for feature in &requested {
+            if let Some((name, feature)) = feature.split_once('/') {
+                // Metadata combined from several workspaces can have several packages of that name
+                let named: Vec<&&Package> = packages.iter().filter(|p| p.name == name).collect();
+                if named.is_empty() {
+                    bail!("Feature `{name}/{feature}` refers to `{name}`, which isn't a workspace package");
+                }
+                if !named.iter().any(|p| has_feature(p, feature)) {
+                    bail!("Package `{name}` has no feature `{feature}`");
+                }
+                for package in named.into_iter().filter(|p| has_feature(p, feature)) {
+                    enabled.enable_feature(&package.id, feature);
+                }
+            } else {
+                let matching: Vec<&&Package> = packages.iter().filter(|p| has_feature(p, feature)).collect();
+                if matching.is_empty() {
+                    bail!("None of the workspace packages has the feature `{feature}`");
+                }
+                for package in matching {
+                    enabled.enable_feature(&package.id, feature);
+                }
+            }
+        }
+        if !selection.no_default_features {
+            for package in &packages {
+                enabled.enable_feature(&package.id, "default");
+            }
+        }
+
+        while enabled.propagate(metadata, &packages) {}
+        Ok(enabled)
+    }
+
+    /// Returns whether the dependency of the package is enabled, which non-optional ones always are.
+    #[must_use]
+    pub fn is_enabled(&self, package: &PackageId, dependency: &Dependency) -> bool {
+        !dependency.optional || self.is_dependency_enabled(package, dependency_key(dependency))
+    }
+
+    /// Returns whether the optional dependency of the package declared under `key`, see
+    /// [`dependency_key`], is enabled.
+    #[must_use]
+    pub fn is_dependency_enabled(&self, package: &PackageId, key: &str) -> bool {
+        self.dependencies.get(package).is_some_and(|deps| deps.contains(key))
+    }
+
+    /// Returns the features enabled on the package.
+    pub fn features(&self, package: &PackageId) -> impl Iterator<Item = &str> {
+        self.features.get(package).into_iter().flatten().map(String::as_str)
+    }
+
+    /// Enables a feature of the package, returning whether it wasn't enabled before.
+    fn enable_feature(&mut self, package: &PackageId, feature: &str) -> bool {
+        self.features.entry(package.clone()).or_default().insert(feature.to_owned())
+    }
+
+    /// Enables an optional dependency of the package, returning whether it wasn't enabled before.
+    fn enable_dependency(&mut self, package: &PackageId, key: &str) -> bool {
+        self.dependencies.entry(package.clone()).or_default().insert(key.to_owned())
+    }
+
+    /// Applies the enabled features once, returning whether anything new was enabled.
+    fn propagate(&mut self, metadata: &Metadata, packages: &[&Package]) -> bool {
+        let mut changed = false;
+
+        for package in packages {
+            let active: Vec<String> = self.features(&package.id).map(str::to_owned).collect();
+            for feature in &active {
+                let Some(values) = package.features.get(feature) else {
+                    // Optional dependencies without `dep:` references are features of their own
+                    if package.dependencies.iter().any(|d| d.optional && dependency_key(d) == feature) {
+                        changed |= self.enable_dependency(&package.id, feature);
+                    }
+                    continue;
+                };
+                for value in values {
+                    changed |= self.apply_value(metadata, package, value);
+                }
+            }
+
+            // Enabled dependencies on workspace packages enable the features they request
+            for dependency in &package.dependencies {
+                let Some(target) = workspace_dependency(metadata, dependency).filter(|_| self.is_enabled(&package.id, dependency)) else {
+                    continue;
+                };
+                if dependency.uses_default_features {
+                    changed |= self.enable_feature(&target.id, "default");
+                }
+                for feature in &dependency.features {
+                    changed |= self.enable_feature(&target.id, feature);
+                }
+            }
+        }
+
+        changed
+    }
+
+    /// Applies one entry of a feature definition, e.g. `dep:foo`, `foo/bar`, `foo?/bar` or `baz`.
+    fn apply_value(&mut self, metadata: &Metadata, package: &Package, value: &str) -> bool {
+        if let Some(key) = value.strip_prefix("dep:") {
+            return self.enable_dependency(&package.id, key);
+        }
+        let Some((key, feature)) = value.split_once('/') else {
+            return self.enable_feature(&package.id, value);
+        };
+
+        let (key, weak) = key.strip_suffix('?').map_or((key, false), |key| (key, true));
+        let dependencies: Vec<&Dependency> = package.dependencies.iter().filter(|d| dependency_key(d) == key).collect();
+
+        let mut changed = false;
+        if !weak && dependencies.iter().any(|d| d.optional) {
+            changed |= self.enable_dependency(&package.id, key);
+        }
+        for dependency in dependencies {
+            if self.is_enabled(&package.id, dependency)
+                && let Some(target) = workspace_dependency(metadata, dependency)
+            {
+                changed |= self.enable_feature(&target.id, feature);
+            }
+        }
+        changed
+    }
+}
+
+/// Returns the name the dependency is referred to by in the manifest of the dependent.
+#[must_use]
+pub fn dependency_key(dependency: &Dependency) -> &str {
+    dependency.rename.as_deref().unwrap_or(&dependency.name)
+}
+
+/// Returns whether the package has the feature, explicitly or as an optional dependency.
+fn has_feature(package: &Package, feature: &str) -> bool {
+    package.features.contains_key(feature) || package.dependencies.iter().any(|d| d.optional && dependency_key(d) == feature)
+}
+
+/// A feature of a workspace package, or an optional dependency as `dep:name`.
+///
+/// Nodes sort by the name of the package, then by its id.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FeatureNode {
+    /// Name of the package.
+    pub name: String,
+    /// Id of the package, telling apart packages of the same name in different workspaces.
+    pub package: PackageId,
+    /// Name of the feature, or `dep:name` for an optional dependency.
+    pub feature: String,
+}
+
+impl FeatureNode {
+    /// Creates the node of a feature of the package.
+    #[must_use]
+    pub fn new(package: &Package, feature: &str) -> Self {
+        Self {
+            name: package.name.clone(),
+            package: package.id.clone(),
+            feature: feature.to_owned(),
+        }
+    }
+}
+
+impl core::fmt::Display for FeatureNode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}/{}", self.name, self.feature)
+    }
+}
+
+/// Graph of the features of the workspace packages, edges point from a feature to the features and
+/// optional dependencies it enables, also across the workspace packages.
+#[derive(Debug, Default)]
+pub struct FeatureGraph {
+    graph: DiGraph<FeatureNode, ()>,
+    node_map: HashMap<FeatureNode, NodeIndex>,
+}
+
+impl FeatureGraph {
+    /// Builds the graph from the feature definitions and dependency declarations of the workspace
+    /// packages.
+    #[must_use]
+    pub fn new(metadata: &Metadata) -> Self {
+        let mut graph = Self::default();
+
+        for package in metadata.workspace_packages() {
+            for (feature, values) in &package.features {
+                let from = FeatureNode::new(package, feature);
+                graph.node(&from);
+                for value in values {
+                    for to in enabled_by_value(metadata, package, value) {
+                        graph.add_edge(&from, &to);
+                    }
+                }
+            }
+
+            // Enabling an optional dependency enables the features its declaration requests
+            for dependency in package.dependencies.iter().filter(|d| d.optional) {
+                let Some(target) = workspace_dependency(metadata, dependency) else {
+                    continue;
+                };
+                let from = FeatureNode::new(package, &format!("dep:{}", dependency_key(dependency)));
+                for feature in &dependency.features {
+                    graph.add_edge(&from, &FeatureNode::new(target, feature));
+                }
+                if dependency.uses_default_features && target.features.contains_key("default") {
+                    graph.add_edge(&from, &FeatureNode::new(target, "default"));
+                }
+            }
+        }
+
+        graph
+    }
+
+    /// Returns the features that enable the node, directly or through other features, sorted.
+    #[must_use]
+    pub fn enabled_by(&self, node: &FeatureNode) -> Vec<FeatureNode> {
+        let Some(&start) = self.node_map.get(node) else {
+            return Vec::new();
+        };
+        let reversed = Reversed(&self.graph);
+        let mut bfs = Bfs::new(reversed, start);
+        let mut features = Vec::new();
+        while let Some(idx) = bfs.next(reversed) {
+            let feature = &self.graph[idx];
+            if idx != start && !feature.feature.starts_with("dep:") {
+                features.push(feature.clone());
+            }
+        }
+        features.sort_unstable();
+        features
+    }
+
+    /// Detects cycles of features, e.g. `a = ["b"]` and `b = ["a"]` within a package, or
+    /// `a = ["dep/b"]` where `dep` has `b = ["parent/a"]` across packages.
+    ///
+    /// Each cycle lists its features in the direction they enable each other.
+    #[must_use]
+    pub fn cycles(&self) -> Vec<Vec<FeatureNode>> {
+        let mut cycles: Vec<Vec<FeatureNode>> = tarjan_scc(&self.graph)
+            .into_iter()
+            .filter(|scc| scc.len() > 1 || self.graph.contains_edge(scc[0], scc[0]))
+            // Tarjan's algorithm yields the nodes against the direction of the edges
+            .map(|scc| scc.iter().rev().map(|&idx| self.graph[idx].clone()).collect::<Vec<_>>())
+            .collect();
+        cycles.sort();
+        cycles
+    }
+
+    fn node(&mut self, node: &FeatureNode) -> NodeIndex {
+        if let Some(&idx) = self.node_map.get(node) {
+            return idx;
+        }
+        let idx = self.graph.add_node(node.clone());
+        self.node_map.insert(node.clone(), idx);
+        idx
+    }
+
+    fn add_edge(&mut self, from: &FeatureNode, to: &FeatureNode) {
+        let from = self.node(from);
+        let to = self.node(to);
+        self.graph.update_edge(from, to, ());
+    }
+}
+
+/// Returns the nodes enabled by one entry of a feature definition of the package.
+fn enabled_by_value(metadata: &Metadata, package: &Package, value: &str) -> Vec<FeatureNode> {
+    if value.starts_with("dep:") {
+        return vec![FeatureNode::new(package, value)];
+    }
+    let Some((key, feature)) = value.split_once('/') else {
+        return vec![FeatureNode::new(package, value)];
+    };
+
+    let (key, weak) = key.strip_suffix('?').map_or((key, false), |key| (key, true));
+    let dependencies: Vec<&Dependency> = package.dependencies.iter().filter(|d| dependency_key(d) == key).collect();
+
+    let mut nodes = Vec::new();
+    if !weak && dependencies.iter().any(|d| d.optional) {
+        nodes.push(FeatureNode::new(package, &format!("dep:{key}")));
+    }
+    if let Some(target) = dependencies.iter().find_map(|d| workspace_dependency(metadata, d)) {
+        nodes.push(FeatureNode::new(target, feature));
+    }
+    nodes
+}
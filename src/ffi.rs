@@ -0,0 +1,123 @@
+//! C ABI for running the check in-process from other languages, enabled by the `cdylib` feature.
+//!
+//! Build the shared library with
+//! `cargo rustc --lib --release --features cdylib --crate-type cdylib`, then e.g. from Python:
+//!
+//! ```python
+//! import ctypes, json
+//!
+//! lib = ctypes.CDLL("libcargo_ensure_no_cyclic_deps.so")
+//! output = ctypes.c_char_p()
+//! status = lib.ensure_no_cyclic_deps_check(b"path/to/Cargo.toml", ctypes.byref(output))
+//! report = json.loads(output.value) if status >= 0 else None
+//! lib.ensure_no_cyclic_deps_free_string(output)
+//! ```
+
+use core::ffi::{CStr, c_char, c_int};
+use core::panic::AssertUnwindSafe;
+use std::ffi::CString;
+use std::panic;
+use std::path::PathBuf;
+
+use crate::checker::Checker;
+use crate::output::{self, OutputFormat};
+
+/// The check passed.
+pub const STATUS_PASSED: c_int = 0;
+/// The check failed.
+pub const STATUS_FAILED: c_int = 1;
+/// The check couldn't be run.
+pub const STATUS_ERROR: c_int = -1;
+/// The check panicked, a bug of the library rather than a problem of the workspace.
+pub const STATUS_PANIC: c_int = -2;
+
+/// Checks the workspace of the manifest, or of the current directory if `manifest_path` is null.
+///
+/// Returns [`STATUS_PASSED`] or [`STATUS_FAILED`] and stores the JSON report in `*output`, or
+/// [`STATUS_ERROR`] or [`STATUS_PANIC`] and stores the error or panic message in `*output`. The
+/// string must be released with [`ensure_no_cyclic_deps_free_string`].
+///
+/// # Safety
+///
+/// `manifest_path` must be null or point to a NUL-terminated string, and `output` must point to
+/// memory where a pointer can be written.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ensure_no_cyclic_deps_check(manifest_path: *const c_char, output: *mut *mut c_char) -> c_int {
+    let manifest_path = if manifest_path.is_null() {
+        None
+    } else {
+        // SAFETY: the caller guarantees a NUL-terminated string
+        let path = unsafe { CStr::from_ptr(manifest_path) };
+        match path.to_str() {
+            Ok(path) => Some(PathBuf::from(path)),
+            Err(err) => {
+                // SAFETY: the caller guarantees `output` is writable
+                unsafe { output.write(into_raw(format!("Invalid manifest path: {err}"))) };
+                return STATUS_ERROR;
+            }
+        }
+    };
+
+    let mut checker = Checker::new();
+    if let Some(path) = manifest_path {
+        checker = checker.manifest_path(path);
+    }
+    let (status, text) = check(checker);
+    // SAFETY: the caller guarantees `output` is writable
+    unsafe { output.write(into_raw(text)) };
+    status
+}
+
+/// Releases a string returned by [`ensure_no_cyclic_deps_check`], null is ignored.
+///
+/// # Safety
+///
+/// `string` must be null or returned by [`ensure_no_cyclic_deps_check`], and not released before.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ensure_no_cyclic_deps_free_string(string: *mut c_char) {
+    if !string.is_null() {
+        // SAFETY: the caller guarantees the string was created by `CString::into_raw`
+        drop(unsafe { CString::from_raw(string) });
+    }
+}
+
+/// Runs the check like [`ensure_no_cyclic_deps_check`], returning the status and the JSON report
+/// or the error message.
+///
+/// Panics don't unwind into the caller, which is undefined behavior across the C ABI, they are
+/// returned as [`STATUS_PANIC`] with the panic message.
+#[must_use]
+pub fn check(checker: Checker) -> (c_int, String) {
+    panic::catch_unwind(AssertUnwindSafe(|| run(checker))).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|&message| message.to_owned())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_owned());
+        (STATUS_PANIC, format!("The check panicked: {message}"))
+    })
+}
+
+/// Runs the check, returning the status and the JSON report or the error message.
+fn run(checker: Checker) -> (c_int, String) {
+    let report = match checker.analyze() {
+        Ok(analysis) => analysis.report,
+        Err(err) => return (STATUS_ERROR, format!("{err:#}")),
+    };
+    match output::render(&report, OutputFormat::Json) {
+        Ok(json) if report.is_failure() => (STATUS_FAILED, json),
+        Ok(json) => (STATUS_PASSED, json),
+        Err(err) => (STATUS_ERROR, format!("{err:#}")),
+    }
+}
+
+/// Converts the text into a C string, dropping NUL bytes that can't be represented.
+fn into_raw(text: String) -> *mut c_char {
+    CString::new(text)
+        .unwrap_or_else(|err| {
+            let mut bytes = err.into_vec();
+            bytes.retain(|&b| b != 0);
+            CString::new(bytes).unwrap_or_default()
+        })
+        .into_raw()
+}
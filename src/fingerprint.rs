@@ -0,0 +1,27 @@
+//! Stable identifiers for detected cycles.
+//!
+//! Fingerprints only depend on package names, not on paths or package ids, so they are the same
+//! on every machine and every run as long as the cycle itself doesn't change.
+
+/// Computes the fingerprint of a cycle from the names of its packages and its edges.
+///
+/// The order of the packages and edges doesn't matter.
+pub fn cycle_fingerprint(packages: &[&str], edges: &[(&str, &str)]) -> String {
+    let mut packages = packages.to_vec();
+    packages.sort_unstable();
+    packages.dedup();
+    let mut edges: Vec<String> = edges.iter().map(|(from, to)| format!("{from}->{to}")).collect();
+    edges.sort_unstable();
+    edges.dedup();
+
+    hash(&format!("{}|{}", packages.join(","), edges.join(",")))
+}
+
+/// Hashes the string with 64-bit FNV-1a, which is stable across platforms and compiler versions,
+/// and formats the hash as 16 hexadecimal digits.
+pub fn hash(s: &str) -> String {
+    let hash = s.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{hash:016x}")
+}
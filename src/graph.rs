@@ -0,0 +1,417 @@
+//! Dependency graph of the workspace crates.
+
+use std::collections::{HashMap, HashSet};
+
+use cargo_metadata::{Dependency, DependencyKind, Metadata, Package, PackageId, Resolve};
+use clap::ValueEnum;
+use glob::Pattern;
+use petgraph::algo::{is_cyclic_directed, tarjan_scc};
+use petgraph::graph::{DiGraph, NodeIndex};
+use serde::{Deserialize, Serialize};
+
+use crate::features::{EnabledFeatures, FeatureGraph, FeatureNode, dependency_key};
+use crate::manifest::{self, Override};
+use crate::target::Target;
+
+/// Key of the table in a package's metadata with its options.
+const PACKAGE_METADATA_KEY: &str = "ensure-no-cyclic-deps";
+
+/// Kind of a dependency, determining which section of the manifest it is declared in.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DepKind {
+    /// A `[dependencies]` entry.
+    Normal,
+    /// A `[dev-dependencies]` entry.
+    Dev,
+    /// A `[build-dependencies]` entry.
+    Build,
+}
+
+impl DepKind {
+    /// All dependency kinds.
+    pub const ALL: [Self; 3] = [Self::Normal, Self::Dev, Self::Build];
+}
+
+impl From<DependencyKind> for DepKind {
+    fn from(kind: DependencyKind) -> Self {
+        match kind {
+            DependencyKind::Development => Self::Dev,
+            DependencyKind::Build => Self::Build,
+            _ => Self::Normal,
+        }
+    }
+}
+
+/// How a dependency between two workspace packages is declared in the manifest of the dependent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Declaration {
+    /// Section of the manifest the dependency is declared in.
+    pub kind: DepKind,
+    /// Whether the dependency is optional, i.e. only enabled by a feature.
+    pub optional: bool,
+    /// The target triple or `cfg(..)` expression the dependency is restricted to, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// Features, as `package/feature`, any of which enables the dependency if it is optional.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub enabled_by: Vec<String>,
+    /// Name the dependency is declared under in the manifest, if it differs from the package name,
+    /// e.g. `foo` for `foo = { package = "my-foo", ... }`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rename: Option<String>,
+    /// Artifacts the dependency is on, e.g. `bin`, if it is an artifact dependency declared with
+    /// `artifact = "bin"` (nightly `-Z bindeps`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artifact: Option<String>,
+    /// Section of the workspace manifest redirecting the dependency to the workspace package, e.g.
+    /// `patch.crates-io` or `replace`, if it isn't a path dependency.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patched: Option<String>,
+}
+
+impl DepKind {
+    /// Returns the lowercase name of the kind.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Dev => "dev",
+            Self::Build => "build",
+        }
+    }
+}
+
+/// Options controlling which packages and dependencies make up the graph.
+#[derive(Debug, Clone)]
+pub struct GraphOptions {
+    /// Dependency kinds that contribute edges.
+    pub kinds: Vec<DepKind>,
+    /// Names or glob patterns of workspace packages left out of the graph.
+    pub ignore: Vec<String>,
+    /// Names or glob patterns of the workspace packages the graph is limited to, all if empty.
+    pub packages: Vec<String>,
+    /// Whether a package's dev-dependency on itself is left out of the graph.
+    pub allow_self_dev_dependency: bool,
+    /// Whether the packages with `publish = false` are left out of the graph.
+    pub publishable_only: bool,
+    /// Target for which the graph is built, platform-specific dependencies of other targets are left
+    /// out. All dependencies are included if unset.
+    pub target: Option<Target>,
+    /// Features enabled on the workspace packages, optional dependencies that aren't enabled are left
+    /// out. All dependencies are included if unset.
+    pub features: Option<EnabledFeatures>,
+    /// Whether only the dependencies in cargo's resolve graph count, if the metadata has one. It
+    /// only has dependencies that are activated for the resolved features and platform.
+    pub resolved: bool,
+}
+
+impl Default for GraphOptions {
+    fn default() -> Self {
+        Self {
+            kinds: DepKind::ALL.to_vec(),
+            ignore: Vec::new(),
+            packages: Vec::new(),
+            allow_self_dev_dependency: false,
+            publishable_only: false,
+            target: None,
+            features: None,
+            resolved: false,
+        }
+    }
+}
+
+/// Graph of dependencies between workspace crates.
+///
+/// Nodes are workspace packages, edges point from a package to the workspace package it depends on.
+/// Nodes are keyed by package id and dependencies are matched by path, never by name alone, so
+/// packages with the same name, e.g. in metadata combined from several workspaces, stay distinct.
+#[derive(Debug)]
+pub struct WorkspaceGraph {
+    graph: DiGraph<PackageId, Vec<Declaration>>,
+    node_map: HashMap<PackageId, NodeIndex>,
+}
+
+impl WorkspaceGraph {
+    /// Builds the graph from the workspace packages in the metadata.
+    pub fn new(metadata: &Metadata, options: &GraphOptions) -> Self {
+        let mut graph = DiGraph::<PackageId, Vec<Declaration>>::new();
+        let features = FeatureGraph::new(metadata);
+        let mut node_map = HashMap::new();
+        let overrides = manifest::overrides(&metadata.workspace_root);
+        let resolved = metadata.resolve.as_ref().filter(|_| options.resolved).map(resolved_dependencies);
+
+        let packages: Vec<_> = metadata
+            .workspace_packages()
+            .into_iter()
+            .filter(|p| !matches_any(&options.ignore, &p.name) && !is_skipped(p))
+            .filter(|p| !options.publishable_only || is_publishable(p))
+            .filter(|p| options.packages.is_empty() || matches_any(&options.packages, &p.name))
+            .collect();
+
+        // Add nodes for each workspace package
+        for package in &packages {
+            let idx = graph.add_node(package.id.clone());
+            node_map.insert(package.id.clone(), idx);
+        }
+
+        // Add edges for dependencies (only workspace dependencies)
+        for package in &packages {
+            let from_idx = node_map[&package.id];
+            // cargo metadata doesn't describe artifact dependencies, they are read from the manifest
+            let manifest = manifest::read(&package.manifest_path);
+
+            let dependencies = package.dependencies.iter().filter(|d| {
+                options.kinds.contains(&d.kind.into())
+                    && options
+                        .target
+                        .as_ref()
+                        .zip(d.target.as_ref())
+                        .is_none_or(|(target, platform)| platform.matches(&target.name, &target.cfg))
+                    && options.features.as_ref().is_none_or(|features| features.is_enabled(&package.id, d))
+            });
+            for dep in dependencies {
+                // Only consider workspace dependencies
+                let target_package = workspace_dependency(metadata, dep)
+                    .map(|p| (p, None))
+                    .or_else(|| overridden_dependency(metadata, &overrides, dep).map(|(p, section)| (p, Some(section.to_owned()))));
+                if let Some((dep_pkg, patched)) = target_package
+                    && let Some(&to_idx) = node_map.get(&dep_pkg.id)
+                    && !(options.allow_self_dev_dependency && to_idx == from_idx && DepKind::from(dep.kind) == DepKind::Dev)
+                    && resolved
+                        .as_ref()
+                        .is_none_or(|r| r.contains(&(package.id.clone(), dep_pkg.id.clone(), dep.kind.into())))
+                {
+                    let target = dep.target.as_ref().map(ToString::to_string);
+                    let artifact = manifest
+                        .as_ref()
+                        .and_then(|m| manifest::artifact(m, dep.kind.into(), target.as_deref(), dependency_key(dep)));
+                    let declaration = Declaration {
+                        kind: dep.kind.into(),
+                        optional: dep.optional,
+                        target,
+                        enabled_by: if dep.optional {
+                            let node = FeatureNode::new(package, &format!("dep:{}", dependency_key(dep)));
+                            features.enabled_by(&node).iter().map(ToString::to_string).collect()
+                        } else {
+                            Vec::new()
+                        },
+                        rename: dep.rename.clone(),
+                        artifact,
+                        patched,
+                    };
+                    // A package may declare the same dependency in several sections, keep one edge
+                    match graph.find_edge(from_idx, to_idx) {
+                        Some(edge) if !graph[edge].contains(&declaration) => graph[edge].push(declaration),
+                        Some(_) => {}
+                        None => {
+                            graph.add_edge(from_idx, to_idx, vec![declaration]);
+                        }
+                    }
+                }
+            }
+        }
+
+        Self { graph, node_map }
+    }
+
+    /// Detects cycles using Tarjan's strongly connected components algorithm.
+    ///
+    /// Every strongly connected component with more than one package is reported as a cycle,
+    /// as is every package depending on itself.
+    #[must_use]
+    pub fn cycles(&self) -> Vec<Vec<PackageId>> {
+        cycles(&self.graph)
+    }
+
+    /// Detects the cycles of the graph with only the dependencies that have a declaration `keep`
+    /// returns true for, given the dependent package, the dependency and the declaration.
+    ///
+    /// Evaluating the graph under several selections this way doesn't rebuild it from the metadata
+    /// and the manifests each time.
+    #[must_use]
+    pub fn cycles_with(&self, keep: impl Fn(&PackageId, &PackageId, &Declaration) -> bool) -> Vec<Vec<PackageId>> {
+        let graph = self.graph.filter_map(
+            |_, id| Some(id.clone()),
+            |edge, declarations| {
+                let (from, to) = self.graph.edge_endpoints(edge)?;
+                declarations
+                    .iter()
+                    .any(|d| keep(&self.graph[from], &self.graph[to], d))
+                    .then_some(())
+            },
+        );
+        cycles(&graph)
+    }
+
+    /// Returns the packages in the graph.
+    pub fn packages(&self) -> impl Iterator<Item = &PackageId> {
+        self.graph.node_weights()
+    }
+
+    /// Returns all dependency edges between workspace packages, without duplicates.
+    #[must_use]
+    pub fn edges(&self) -> Vec<(PackageId, PackageId, Vec<Declaration>)> {
+        let packages: Vec<PackageId> = self.graph.node_weights().cloned().collect();
+        self.edges_within(&packages)
+    }
+
+    /// Returns the dependency edges between the given packages, without duplicates, along with their
+    /// declarations.
+    #[must_use]
+    pub fn edges_within(&self, packages: &[PackageId]) -> Vec<(PackageId, PackageId, Vec<Declaration>)> {
+        let nodes: Vec<NodeIndex> = packages.iter().filter_map(|id| self.node_map.get(id).copied()).collect();
+
+        let mut edges = Vec::new();
+        for &from in &nodes {
+            for &to in &nodes {
+                if let Some(edge) = self.graph.find_edge(from, to) {
+                    edges.push((self.graph[from].clone(), self.graph[to].clone(), self.graph[edge].clone()));
+                }
+            }
+        }
+        edges
+    }
+}
+
+/// Detects the cycles of the graph using Tarjan's strongly connected components algorithm, see
+/// [`WorkspaceGraph::cycles`].
+fn cycles<E>(graph: &DiGraph<PackageId, E>) -> Vec<Vec<PackageId>> {
+    // Find strongly connected components using Tarjan's algorithm
+    let sccs = tarjan_scc(graph);
+
+    // Extract cycles (SCCs with more than one node indicate a cycle)
+    let mut cycles: Vec<Vec<PackageId>> = sccs
+        .into_iter()
+        .filter(|scc| scc.len() > 1)
+        // Tarjan's algorithm yields the packages against the direction of the dependencies
+        .map(|scc| scc.iter().rev().map(|&idx| graph[idx].clone()).collect())
+        .collect();
+
+    // Detect self-loops (a node depending on itself)
+    for idx in graph.node_indices() {
+        if graph.contains_edge(idx, idx) {
+            cycles.push(vec![graph[idx].clone()]);
+        }
+    }
+
+    cycles
+}
+
+/// Returns the workspace package the dependency refers to, if any.
+///
+/// The dependency must point at the directory of the package with a path, a dependency from a
+/// registry or git with the same name as a workspace package refers to a different package. A
+/// path dependency that also has a version for publishing, `{ path = "..", version = "1.2" }`,
+/// refers to the workspace package. The name of the dependency is the name of the package even if
+/// it is renamed.
+#[must_use]
+pub fn workspace_dependency<'a>(metadata: &'a Metadata, dependency: &Dependency) -> Option<&'a Package> {
+    let path = dependency.path.as_deref()?;
+    metadata
+        .packages
+        .iter()
+        .find(|p| p.name == dependency.name && p.manifest_path.parent() == Some(path) && metadata.workspace_members.contains(&p.id))
+}
+
+/// Returns whether the package opted out of the analysis with `skip = true` in the
+/// `[package.metadata.ensure-no-cyclic-deps]` table of its manifest, e.g. because it is generated.
+#[must_use]
+pub fn is_skipped(package: &Package) -> bool {
+    package_option(package, "skip")
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(false)
+}
+
+/// Returns the option in the `[package.metadata.ensure-no-cyclic-deps]` table of the package's
+/// manifest, if set.
+pub(crate) fn package_option<'a>(package: &'a Package, key: &str) -> Option<&'a serde_json::Value> {
+    package.metadata.get(PACKAGE_METADATA_KEY)?.get(key)
+}
+
+/// Returns whether the package can be published to some registry, i.e. it doesn't have
+/// `publish = false` or an empty list of registries.
+fn is_publishable(package: &Package) -> bool {
+    package.publish.as_ref().is_none_or(|registries| !registries.is_empty())
+}
+
+/// Returns whether the package name is one of the names or matches one of the glob patterns.
+pub(crate) fn matches_any(patterns: &[String], name: &str) -> bool {
+    patterns.iter().any(|p| p == name || Pattern::new(p).is_ok_and(|p| p.matches(name)))
+}
+
+/// Returns the dependencies between packages in cargo's resolve graph, with their kinds.
+fn resolved_dependencies(resolve: &Resolve) -> HashSet<(PackageId, PackageId, DepKind)> {
+    resolve
+        .nodes
+        .iter()
+        .flat_map(|node| {
+            node.deps.iter().flat_map(move |dep| {
+                dep.dep_kinds
+                    .iter()
+                    .map(move |info| (node.id.clone(), dep.pkg.clone(), DepKind::from(info.kind)))
+            })
+        })
+        .collect()
+}
+
+/// Returns the workspace package a `[patch]` or `[replace]` override redirects the dependency to,
+/// if its version satisfies the requirement, along with the section of the override.
+fn overridden_dependency<'a>(metadata: &'a Metadata, overrides: &'a [Override], dependency: &Dependency) -> Option<(&'a Package, &'a str)> {
+    if dependency.path.is_some() {
+        return None;
+    }
+    overrides
+        .iter()
+        .filter(|o| o.package == dependency.name && o.source.as_deref().is_none_or(|source| patches(source, dependency)))
+        .find_map(|o| {
+            let package = metadata.packages.iter().find(|p| {
+                p.name == o.package
+                    && p.manifest_path.parent() == Some(o.path.as_path())
+                    && metadata.workspace_members.contains(&p.id)
+                    && dependency.req.matches(&p.version)
+            })?;
+            Some((package, o.section.as_str()))
+        })
+}
+
+/// Returns whether `[patch]` of the registry name or git URL applies to the dependency.
+fn patches(source: &str, dependency: &Dependency) -> bool {
+    let Some(dependency_source) = dependency.source.as_deref() else {
+        return false;
+    };
+    if source == "crates-io" {
+        dependency_source == "registry+https://github.com/rust-lang/crates.io-index"
+    } else {
+        dependency_source.contains(source)
+    }
+}
+
+/// Returns whether the given dependency edges form a cycle, including a package depending on itself.
+pub fn has_cycle<'a>(edges: impl IntoIterator<Item = (&'a PackageId, &'a PackageId)>) -> bool {
+    let mut graph = DiGraph::<(), ()>::new();
+    let mut nodes: HashMap<&PackageId, NodeIndex> = HashMap::new();
+    for (from, to) in edges {
+        let from = *nodes.entry(from).or_insert_with(|| graph.add_node(()));
+        let to = *nodes.entry(to).or_insert_with(|| graph.add_node(()));
+        graph.add_edge(from, to, ());
+    }
+    is_cyclic_directed(&graph)
+}
+
+/// Returns whether the packages all cyclically depend on each other through the given dependency
+/// edges, i.e. form a single cycle as reported by [`WorkspaceGraph::cycles`].
+pub fn is_single_cycle<'a>(packages: &[&'a PackageId], edges: impl IntoIterator<Item = (&'a PackageId, &'a PackageId)>) -> bool {
+    let mut graph = DiGraph::<(), ()>::new();
+    let nodes: HashMap<&PackageId, NodeIndex> = packages.iter().map(|&id| (id, graph.add_node(()))).collect();
+    for (from, to) in edges {
+        if let (Some(&from), Some(&to)) = (nodes.get(from), nodes.get(to)) {
+            graph.add_edge(from, to, ());
+        }
+    }
+    let sccs = tarjan_scc(&graph);
+    match sccs.as_slice() {
+        [scc] => scc.len() > 1 || graph.contains_edge(scc[0], scc[0]),
+        _ => false,
+    }
+}
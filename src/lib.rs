@@ -0,0 +1,215 @@
+//! Detection of cyclic dependencies between the crates of a cargo workspace.
+//!
+//! This is the library behind the `cargo ensure-no-cyclic-deps` subcommand, for running the check
+//! from other tools, e.g. an xtask, without spawning the binary and parsing its output:
+//!
+//! ```no_run
+//! use cargo_ensure_no_cyclic_deps::graph::GraphOptions;
+//!
+//! let metadata = cargo_ensure_no_cyclic_deps::load_metadata(None)?;
+//! let report = cargo_ensure_no_cyclic_deps::analyze(&metadata, &GraphOptions::default());
+//! for cycle in &report.cycles {
+//!     println!("{}", cycle.path());
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use cargo_metadata::camino::Utf8Path;
+use cargo_metadata::{CargoOpt, Metadata, MetadataCommand, PackageId};
+
+use crate::features::FeatureSelection;
+use crate::graph::{GraphOptions, WorkspaceGraph};
+use crate::report::{Cycle, Report};
+use crate::rules::Rule;
+
+pub mod baseline;
+pub mod build_script;
+pub mod checker;
+pub mod condensation;
+pub mod config;
+pub mod cuts;
+pub mod date;
+pub mod elementary;
+pub mod explore;
+pub mod extraction;
+pub mod features;
+#[cfg(feature = "cdylib")]
+pub mod ffi;
+mod fingerprint;
+pub mod graph;
+mod manifest;
+pub mod near_cycles;
+pub mod output;
+pub mod powerset;
+pub mod query;
+pub mod ratchet;
+mod regex;
+pub mod report;
+pub mod resolution;
+pub mod rules;
+pub mod stats;
+pub mod target;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+pub mod workspaces;
+
+/// Loads the metadata of the workspace containing `manifest_path`, or the current directory.
+///
+/// Dependencies aren't resolved, which would fail on cycles.
+///
+/// # Errors
+///
+/// Fails if `cargo metadata` can't be run or fails, e.g. because the manifest is invalid.
+pub fn load_metadata(manifest_path: Option<&Path>) -> Result<Metadata> {
+    let mut cmd = MetadataCommand::new();
+    if let Some(path) = manifest_path {
+        cmd.manifest_path(path);
+    }
+    // Use --no-deps to avoid Cargo resolving dependencies (which would fail on cycles)
+    cmd.no_deps();
+
+    cmd.exec().context("Failed to load cargo metadata")
+}
+
+/// Loads the metadata of the workspace with cargo's resolve graph, for the features selected as
+/// for cargo.
+///
+/// Resolving fails on cycles of normal or build dependencies, which cargo doesn't allow, and may
+/// need network access for registry dependencies.
+///
+/// # Errors
+///
+/// Fails if `cargo metadata` can't be run or fails, e.g. because the dependencies can't be resolved.
+pub fn load_resolved_metadata(manifest_path: Option<&Path>, features: &FeatureSelection) -> Result<Metadata> {
+    let mut cmd = MetadataCommand::new();
+    if let Some(path) = manifest_path {
+        cmd.manifest_path(path);
+    }
+    if features.all_features {
+        cmd.features(CargoOpt::AllFeatures);
+    }
+    if features.no_default_features {
+        cmd.features(CargoOpt::NoDefaultFeatures);
+    }
+    if !features.features.is_empty() {
+        cmd.features(CargoOpt::SomeFeatures(features.features.clone()));
+    }
+
+    cmd.exec().context("Failed to resolve the dependencies")
+}
+
+/// Removes the workspace members that aren't in `default-members` from the metadata's members, as
+/// if they weren't part of the workspace. All members are default members if it isn't set.
+///
+/// # Errors
+///
+/// Fails if the metadata doesn't list the default members, which needs cargo 1.71 or newer.
+pub fn retain_default_members(metadata: &mut Metadata) -> Result<()> {
+    // Dereferencing the default members of older cargo versions panics, they serialize as null
+    let default_members: Option<Vec<PackageId>> = serde_json::to_value(&metadata.workspace_default_members)
+        .and_then(serde_json::from_value)
+        .context("Failed to read the default members")?;
+    let Some(default_members) = default_members else {
+        bail!("cargo metadata doesn't list the default members, cargo 1.71 or newer is needed");
+    };
+    metadata.workspace_members.retain(|id| default_members.contains(id));
+    Ok(())
+}
+
+/// Adds the crates outside the workspace that workspace members depend on with `path = ...` to the
+/// metadata as if they were members, along with the path dependencies of those crates in turn.
+///
+/// Each crate's manifest is loaded with its own `cargo metadata` run.
+///
+/// # Errors
+///
+/// Fails if the metadata of one of the crates can't be loaded.
+pub fn include_path_dependencies(metadata: &mut Metadata) -> Result<()> {
+    let mut pending: Vec<_> = metadata.workspace_packages().into_iter().map(|p| p.id.clone()).collect();
+    while let Some(id) = pending.pop() {
+        let paths: Vec<_> = metadata
+            .packages
+            .iter()
+            .filter(|p| p.id == id)
+            .flat_map(|p| &p.dependencies)
+            .filter_map(|d| d.path.clone())
+            .collect();
+        for path in paths {
+            let added =
+                add_member(metadata, &path.join("Cargo.toml")).with_context(|| format!("Failed to load the path dependency at {path}"))?;
+            pending.extend(added);
+        }
+    }
+    Ok(())
+}
+
+/// Adds the crates listed in `workspace.exclude` of the workspace manifest to the metadata as if
+/// they were members.
+///
+/// An excluded directory without a manifest of its own adds the crates in its subdirectories, e.g.
+/// for `exclude = ["vendor"]`.
+///
+/// # Errors
+///
+/// Fails if the metadata of one of the crates can't be loaded.
+pub fn include_excluded(metadata: &mut Metadata) -> Result<()> {
+    for manifest_path in manifest::excluded(&metadata.workspace_root) {
+        add_member(metadata, &manifest_path).with_context(|| format!("Failed to load the excluded crate at {manifest_path}"))?;
+    }
+    Ok(())
+}
+
+/// Adds the package of the manifest to the metadata's members, loading it if the metadata doesn't
+/// have it yet, returns its id if it wasn't a member before.
+fn add_member(metadata: &mut Metadata, manifest_path: &Utf8Path) -> Result<Option<PackageId>> {
+    // With the resolve graph, the metadata may already have the crate, it just isn't a member
+    if let Some(package) = metadata.packages.iter().find(|p| p.manifest_path == manifest_path) {
+        if metadata.workspace_members.contains(&package.id) {
+            return Ok(None);
+        }
+        metadata.workspace_members.push(package.id.clone());
+        return Ok(Some(package.id.clone()));
+    }
+    let outside = load_metadata(Some(manifest_path.as_std_path()))?;
+    let Some(package) = outside.packages.into_iter().find(|p| p.manifest_path == manifest_path) else {
+        return Ok(None);
+    };
+    let id = package.id.clone();
+    metadata.workspace_members.push(id.clone());
+    metadata.packages.push(package);
+    Ok(Some(id))
+}
+
+/// Builds the dependency graph of the workspace and reports its cycles.
+#[must_use]
+pub fn analyze(metadata: &Metadata, options: &GraphOptions) -> Report {
+    let graph = WorkspaceGraph::new(metadata, options);
+    Report::new(metadata, &graph)
+}
+
+/// Builds the dependency graph of the workspace and reports the problems the rules find, the
+/// built-in ones are [`rules::builtin`].
+#[must_use]
+pub fn analyze_with_rules(metadata: &Metadata, options: &GraphOptions, rules: &[Box<dyn Rule>]) -> Report {
+    let graph = WorkspaceGraph::new(metadata, options);
+    Report::with_rules(metadata, &graph, rules)
+}
+
+/// Detects the cycles between the crates of the workspace, counting all dependencies.
+///
+/// The metadata can come from any `cargo metadata` invocation, e.g. one a tool already ran, as long
+/// as it lists the workspace packages.
+#[must_use]
+pub fn detect_cycles(metadata: &Metadata) -> Vec<Cycle> {
+    detect_cycles_with(metadata, &GraphOptions::default())
+}
+
+/// Detects the cycles between the crates of the workspace, counting the dependencies selected by the
+/// options.
+#[must_use]
+pub fn detect_cycles_with(metadata: &Metadata, options: &GraphOptions) -> Vec<Cycle> {
+    analyze(metadata, options).cycles
+}
@@ -14,14 +14,40 @@
 //! cargo ensure-no-cyclic-deps --manifest-path path/to/Cargo.toml
 //! ```
 //!
+//! To consume the results from scripts, request machine-readable output:
+//!
+//! ```bash
+//! cargo ensure-no-cyclic-deps --format json
+//! ```
+//!
+//! To accept the cycles that currently fail the check, e.g. when adopting the tool in a large
+//! workspace, append them to the allowlist in the configuration file (or a baseline):
+//!
+//! ```bash
+//! cargo ensure-no-cyclic-deps suppress --reason "tracked in #123"
+//! ```
+//!
 //! The tool will exit with code 0 if no cycles are found, or code 1 if cycles are detected.
 
-use anyhow::{Context, Result};
-use cargo_metadata::{Metadata, MetadataCommand, PackageId};
-use clap::Parser;
-use petgraph::algo::tarjan_scc;
-use petgraph::graph::DiGraph;
-use std::collections::HashMap;
+use core::num::NonZeroUsize;
+use std::env;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use anyhow::{Context, Result, bail};
+use cargo_ensure_no_cyclic_deps::baseline;
+use cargo_ensure_no_cyclic_deps::checker::{Analysis, Checker};
+use cargo_ensure_no_cyclic_deps::graph::DepKind;
+use cargo_ensure_no_cyclic_deps::output::{self, OutputFormat, WorkspaceSection};
+use cargo_ensure_no_cyclic_deps::query;
+use cargo_ensure_no_cyclic_deps::ratchet::Outcome;
+use cargo_ensure_no_cyclic_deps::report::Report;
+use cargo_ensure_no_cyclic_deps::resolution::ResolutionDiff;
+use cargo_ensure_no_cyclic_deps::stats::Stats;
+use cargo_ensure_no_cyclic_deps::{explore, workspaces};
+use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
 #[derive(Parser, Debug)]
 #[command(
@@ -38,116 +64,942 @@ struct Cli {
 #[derive(Parser, Debug)]
 enum Command {
     #[command(name = "ensure-no-cyclic-deps")]
-    EnsureNoCyclicDeps {
-        /// Path to Cargo.toml
-        #[arg(long, value_name = "PATH")]
-        manifest_path: Option<std::path::PathBuf>,
-    },
+    EnsureNoCyclicDeps(EnsureNoCyclicDepsArgs),
+}
+
+#[derive(Args, Debug, Default)]
+#[command(args_conflicts_with_subcommands = true)]
+struct EnsureNoCyclicDepsArgs {
+    #[command(subcommand)]
+    action: Option<Action>,
+
+    #[command(flatten)]
+    check: CheckArgs,
+}
+
+#[derive(Subcommand, Debug)]
+enum Action {
+    /// Accept the cycles that currently fail the check by appending them to the allowlist in the
+    /// configuration file, or to a baseline
+    Suppress(SuppressArgs),
+    /// Explain why one workspace member depends on another by printing the path of dependencies
+    /// between them
+    Why(WhyArgs),
+    /// List the workspace members that depend on a member, even transitively
+    Rdeps(RdepsArgs),
+    /// Emit the workspace dependency graph, whether or not it has cycles
+    Graph(GraphCommandArgs),
+    /// Print the workspace members in dependency order, e.g. to publish them, fails if they have
+    /// cyclic dependencies
+    Order(OrderArgs),
+    /// Check whether adding a dependency would create a cycle, without changing any manifest
+    CheckAdd(CheckAddArgs),
+    /// Print metrics of the workspace dependency graph
+    Stats(StatsArgs),
+    /// Print the details of a cycle by its fingerprint, e.g. from a baseline or a CI log
+    Explain(ExplainArgs),
+    /// Compare the declared dependencies with those cargo's resolver activates, fails if they differ
+    ResolveDiff(ResolveDiffArgs),
+    /// Browse the workspace crates, their dependencies and dependents, and step through the cycles,
+    /// with commands such as `list`, `show CRATE` and `next` read line by line from stdin
+    Explore(ExploreArgs),
+}
+
+/// Options selecting the workspace and how it is analyzed, shared by all subcommands.
+#[derive(Args, Debug, Default)]
+struct WorkspaceArgs {
+    /// Path to Cargo.toml, the check accepts several to check their workspaces in one run, repeated or
+    /// comma-separated
+    #[arg(long, value_name = "PATH", value_delimiter = ',')]
+    manifest_path: Vec<PathBuf>,
+
+    /// Path to the configuration file [default: .ensure-no-cyclic-deps.toml in the workspace root]
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// Dependency kinds that contribute edges to the graph [default: all]
+    #[arg(long, value_enum, value_delimiter = ',')]
+    kind: Vec<DepKind>,
+
+    /// Don't fail on cycles that only exist because of dev-dependencies
+    #[arg(long)]
+    allow_dev_cycles: bool,
+
+    /// Don't count a crate's dev-dependency on itself, e.g. for enabling features in its tests
+    #[arg(long)]
+    allow_self_dev_dependency: bool,
+
+    /// Leave the workspace crates with `publish = false` out of the analysis, e.g. to check the
+    /// publish order
+    #[arg(long)]
+    publishable_only: bool,
+
+    /// Only count platform-specific dependencies that apply to this target triple [default: all]
+    #[arg(long, value_name = "TRIPLE")]
+    target: Option<String>,
+
+    /// Leave the workspace crates matching this name or glob pattern out of the analysis, can be
+    /// repeated
+    #[arg(long, value_name = "PATTERN")]
+    exclude: Vec<String>,
+
+    #[command(flatten)]
+    load: LoadArgs,
+
+    #[command(flatten)]
+    features: FeatureArgs,
+}
+
+/// Which crates and dependencies are loaded from cargo.
+#[derive(Args, Debug, Default)]
+struct LoadArgs {
+    #[command(flatten)]
+    include: IncludeArgs,
+
+    /// Only count the dependencies cargo's resolver activates, falling back to the declared ones if
+    /// the dependencies can't be resolved
+    #[arg(long)]
+    resolved: bool,
+
+    /// Only analyze the workspace's `default-members`, as a plain `cargo build` would
+    #[arg(long)]
+    default_members_only: bool,
+}
+
+/// Crates besides the workspace members that are analyzed as well.
+#[derive(Args, Debug, Default)]
+struct IncludeArgs {
+    /// Also analyze crates outside the workspace that are depended on with `path = ...`
+    #[arg(long)]
+    include_path_deps: bool,
+
+    /// Also analyze the workspaces that path dependencies lead into, and those their path
+    /// dependencies lead into in turn, as one graph with the workspace
+    #[arg(long)]
+    include_path_workspaces: bool,
+
+    /// Also analyze the crates listed in `workspace.exclude`
+    #[arg(long)]
+    include_excluded: bool,
+}
+
+/// Feature selection, with the same meaning as for cargo.
+#[derive(Args, Debug, Default)]
+struct FeatureArgs {
+    /// Features to enable, `feature` for every workspace crate that has it or `crate/feature`
+    /// [default: all optional dependencies count]
+    #[arg(long, short = 'F', value_name = "FEATURES", value_delimiter = ',')]
+    features: Vec<String>,
+
+    /// Enable all features of all workspace crates
+    #[arg(long, conflicts_with_all = ["features", "no_default_features"])]
+    all_features: bool,
+
+    /// Don't enable the `default` feature of the workspace crates
+    #[arg(long)]
+    no_default_features: bool,
+
+    /// Find the smallest combination of features under which each cycle exists
+    #[arg(long, conflicts_with_all = ["features", "all_features", "no_default_features"])]
+    feature_powerset: bool,
+
+    /// Maximum number of features combined with --feature-powerset
+    #[arg(long, value_name = "N", default_value_t = 2, requires = "feature_powerset")]
+    depth: usize,
+}
+
+/// Further analyses of the detected cycles.
+#[derive(Args, Debug, Default)]
+struct CycleArgs {
+    /// List the individual elementary cycles within each cycle
+    #[arg(long)]
+    all_cycles: bool,
+
+    /// Maximum number of elementary cycles listed per cycle with --all-cycles, the shortest ones
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 100,
+        requires = "all_cycles",
+        visible_alias = "all-cycles-limit"
+    )]
+    max_cycles_per_scc: usize,
+
+    /// Suggest dependencies to remove, or a shared crate to extract, so the workspace becomes acyclic
+    #[arg(long)]
+    suggest_cuts: bool,
+}
+
+/// Analyses of the whole workspace graph.
+#[derive(Args, Debug, Default)]
+struct GraphArgs {
+    /// Collapse each cycle into a single node in the `dot` and `json` graphs
+    #[arg(long)]
+    condense: bool,
+
+    /// Report the dependencies between workspace members that would create a cycle when added
+    #[arg(long)]
+    near_cycles: bool,
+}
+
+#[derive(Args, Debug, Default)]
+struct CheckArgs {
+    #[command(flatten)]
+    workspace: WorkspaceArgs,
+
+    #[command(flatten)]
+    cycles: CycleArgs,
+
+    #[command(flatten)]
+    graph: GraphArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    /// Only fail on cycles that aren't recorded in this baseline file, can be repeated to combine
+    /// several baselines
+    #[arg(long, value_name = "PATH")]
+    baseline: Vec<PathBuf>,
+
+    /// Record all detected cycles in this baseline file and don't fail on them
+    #[arg(long, value_name = "PATH", conflicts_with = "baseline")]
+    write_baseline: Option<PathBuf>,
+
+    /// Only analyze the workspace crates matching this name or glob pattern and the dependencies
+    /// between them, can be repeated
+    #[arg(long, short = 'p', value_name = "SPEC")]
+    package: Vec<String>,
+
+    /// Only report and fail on cycles involving a crate matching this name or glob pattern, can be
+    /// repeated
+    #[arg(long, value_name = "PATTERN")]
+    focus: Vec<String>,
+
+    /// Only report and fail on cycles involving a crate whose manifest is under this path, relative
+    /// to the workspace root, can be repeated
+    #[arg(long, value_name = "PATH")]
+    scope: Vec<String>,
+
+    /// Only fail when more than this many cycles are detected
+    #[arg(long, value_name = "N")]
+    max_cycles: Option<usize>,
+
+    /// Only fail when the number of cycles grows compared to this state file, which is created or
+    /// updated when it doesn't
+    #[arg(long, value_name = "PATH")]
+    ratchet: Option<PathBuf>,
+
+    /// Also check the feature definitions for cycles, within a crate and across crates
+    #[arg(long)]
+    check_features: bool,
+
+    /// List the rules that are checked and exit
+    #[arg(long)]
+    rules: bool,
+
+    /// Print the analyzed workspace members or the dependency edges between them and exit, can be
+    /// repeated
+    #[arg(long, value_enum, value_name = "WHAT")]
+    print: Vec<PrintRequest>,
+
+    /// Fail on baseline and allowlist entries whose cycles no longer exist
+    #[arg(long)]
+    strict_baseline: bool,
+
+    #[command(flatten)]
+    workspaces: WorkspacesArgs,
+}
+
+/// How the results of the check are emitted.
+#[derive(Args, Debug, Default)]
+struct OutputArgs {
+    /// Output format [default: human]
+    #[arg(long, value_enum)]
+    format: Option<OutputFormat>,
+
+    /// Write the output to a file instead of the terminal
+    #[arg(long, value_name = "PATH")]
+    output: Option<PathBuf>,
+
+    /// Also list the workspace crates that opted out of the analysis in their package metadata
+    #[arg(long, short = 'v')]
+    verbose: bool,
+
+    /// Open the output in the browser, an SVG image of the cycles unless the html format is given
+    #[arg(long)]
+    open: bool,
+
+    /// Only export the crates and dependencies participating in cycles, with the graph formats
+    #[arg(long)]
+    only_sccs: bool,
+}
+
+/// Workspaces checked besides the one of the manifest.
+#[derive(Args, Debug, Default)]
+struct WorkspacesArgs {
+    /// Also check the independent workspaces nested in the directory tree of the workspace, each in
+    /// isolation
+    #[arg(long, conflicts_with_all = ["print", "write_baseline", "ratchet"])]
+    nested: bool,
+
+    /// Check every workspace found in the directory tree instead, each in isolation
+    #[arg(
+        long,
+        value_name = "DIR",
+        conflicts_with_all = ["manifest_path", "nested", "print", "write_baseline", "ratchet"]
+    )]
+    recurse: Option<PathBuf>,
+
+    /// When checking several workspaces, also check all of them as one combined graph, which has the
+    /// dependencies between them
+    #[arg(long)]
+    combine: bool,
+}
+
+#[derive(Args, Debug)]
+struct SuppressArgs {
+    #[command(flatten)]
+    workspace: WorkspaceArgs,
+
+    /// Append to this baseline file (created if missing) instead of the configuration file
+    #[arg(long, value_name = "PATH")]
+    baseline: Option<PathBuf>,
+
+    /// Reason for accepting the cycles, recorded with each entry
+    #[arg(long)]
+    reason: Option<String>,
+}
+
+#[derive(Args, Debug)]
+struct WhyArgs {
+    #[command(flatten)]
+    workspace: WorkspaceArgs,
+
+    /// Name of the dependent workspace member
+    #[arg(long, value_name = "CRATE")]
+    from: String,
+
+    /// Name of the workspace member depended on
+    #[arg(long, value_name = "CRATE")]
+    to: String,
+
+    /// Number of paths to print, the shortest ones
+    #[arg(long, value_name = "K", default_value = "1")]
+    paths: NonZeroUsize,
+}
+
+#[derive(Args, Debug)]
+struct RdepsArgs {
+    #[command(flatten)]
+    workspace: WorkspaceArgs,
+
+    /// Name of the workspace member
+    #[arg(value_name = "CRATE")]
+    name: String,
 }
 
-fn main() -> Result<()> {
+#[derive(Args, Debug)]
+struct GraphCommandArgs {
+    #[command(flatten)]
+    workspace: WorkspaceArgs,
+
+    /// Only include these workspace members and the members they depend on, can be repeated
+    #[arg(long, short = 'p', value_name = "CRATE")]
+    package: Vec<String>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Dot)]
+    format: OutputFormat,
+
+    /// Write the output to a file instead of the terminal
+    #[arg(long, value_name = "PATH")]
+    output: Option<PathBuf>,
+
+    /// Only export the crates and dependencies participating in cycles
+    #[arg(long)]
+    only_sccs: bool,
+}
+
+#[derive(Args, Debug)]
+struct OrderArgs {
+    #[command(flatten)]
+    workspace: WorkspaceArgs,
+}
+
+#[derive(Args, Debug)]
+struct ResolveDiffArgs {
+    #[command(flatten)]
+    workspace: WorkspaceArgs,
+}
+
+#[derive(Args, Debug)]
+struct CheckAddArgs {
+    #[command(flatten)]
+    workspace: WorkspaceArgs,
+
+    /// Name of the workspace member the dependency would be added to
+    #[arg(long, value_name = "CRATE")]
+    package: String,
+
+    /// Name of the crate that would be depended on
+    #[arg(long, value_name = "CRATE")]
+    dep: String,
+}
+
+#[derive(Args, Debug)]
+struct StatsArgs {
+    #[command(flatten)]
+    workspace: WorkspaceArgs,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t)]
+    format: StatsFormat,
+}
+
+/// Information printed by `--print`.
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum PrintRequest {
+    /// The analyzed workspace members with their manifests.
+    Members,
+    /// The dependency edges between the members with their kinds and declarations.
+    Edges,
+}
+
+/// Format of the graph metrics.
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+enum StatsFormat {
+    /// Human readable text.
+    #[default]
+    Human,
+    /// Machine-readable JSON.
+    Json,
+}
+
+#[derive(Args, Debug)]
+struct ExplainArgs {
+    #[command(flatten)]
+    workspace: WorkspaceArgs,
+
+    /// Fingerprint of the cycle
+    fingerprint: String,
+}
+
+#[derive(Args, Debug)]
+struct ExploreArgs {
+    #[command(flatten)]
+    workspace: WorkspaceArgs,
+}
+
+fn main() -> Result<ExitCode> {
     let cli = Cli::parse();
 
-    let manifest_path = match cli.cmd {
-        Some(Command::EnsureNoCyclicDeps { manifest_path }) => manifest_path,
+    let args = match cli.cmd {
+        Some(Command::EnsureNoCyclicDeps(args)) => args,
         None => {
             // When called as `cargo-ensure-no-cyclic-deps` directly
             // (without the cargo wrapper), we still want it to work
-            None
+            EnsureNoCyclicDepsArgs::default()
         }
     };
 
-    let mut cmd = MetadataCommand::new();
-    if let Some(path) = manifest_path {
-        cmd.manifest_path(path);
+    match args.action {
+        None => check(&args.check),
+        Some(Action::Suppress(args)) => suppress(&args).map(|()| ExitCode::SUCCESS),
+        Some(Action::Why(args)) => why(&args),
+        Some(Action::Rdeps(args)) => rdeps(&args).map(|()| ExitCode::SUCCESS),
+        Some(Action::Graph(args)) => graph(&args).map(|()| ExitCode::SUCCESS),
+        Some(Action::Order(args)) => order(&args),
+        Some(Action::CheckAdd(args)) => check_add(&args),
+        Some(Action::Stats(args)) => stats(&args).map(|()| ExitCode::SUCCESS),
+        Some(Action::Explain(args)) => explain(&args),
+        Some(Action::ResolveDiff(args)) => resolve_diff(&args),
+        Some(Action::Explore(args)) => explore(&args).map(|()| ExitCode::SUCCESS),
     }
-    // Use --no-deps to avoid Cargo resolving dependencies (which would fail on cycles)
-    cmd.no_deps();
+}
 
-    let metadata = cmd.exec().context("Failed to load cargo metadata")?;
+/// Configures the check of the workspace of the manifest from the workspace options of the command
+/// line.
+fn checker(args: &WorkspaceArgs) -> Result<Checker> {
+    match args.manifest_path.as_slice() {
+        [] => Ok(options(args)),
+        [path] => Ok(options(args).manifest_path(path)),
+        _ => bail!("Only one --manifest-path can be given, except for the check"),
+    }
+}
 
-    let cycles = detect_cycles(&metadata);
+/// Configures the check from the workspace options of the command line, except for the manifest.
+fn options(args: &WorkspaceArgs) -> Checker {
+    let mut checker = Checker::new()
+        .features(args.features.features.iter().cloned())
+        .all_features(args.features.all_features)
+        .no_default_features(args.features.no_default_features);
+    if let Some(path) = &args.config {
+        checker = checker.config_path(path);
+    }
+    if !args.kind.is_empty() {
+        checker = checker.dependency_kinds(args.kind.iter().copied());
+    }
+    for pattern in &args.exclude {
+        checker = checker.ignore(pattern);
+    }
+    if args.allow_dev_cycles {
+        checker = checker.allow_dev_cycles(true);
+    }
+    if args.allow_self_dev_dependency {
+        checker = checker.allow_self_dev_dependency(true);
+    }
+    if args.publishable_only {
+        checker = checker.publishable_only(true);
+    }
+    if args.load.include.include_path_deps {
+        checker = checker.include_path_deps(true);
+    }
+    if args.load.include.include_path_workspaces {
+        checker = checker.include_path_workspaces(true);
+    }
+    if args.load.include.include_excluded {
+        checker = checker.include_excluded(true);
+    }
+    if args.load.resolved {
+        checker = checker.resolved(true);
+    }
+    if args.load.default_members_only {
+        checker = checker.default_members_only(true);
+    }
+    if let Some(target) = &args.target {
+        checker = checker.target(target);
+    }
+    if args.features.feature_powerset {
+        checker = checker.feature_powerset(args.features.depth);
+    }
+    checker
+}
 
-    if cycles.is_empty() {
-        println!("No cyclic dependencies found.");
-        Ok(())
+/// Runs the check, the exit code tells whether it passed.
+fn check(args: &CheckArgs) -> Result<ExitCode> {
+    if args.rules {
+        for rule in checker(&args.workspace)?.rules()? {
+            println!("{} - {}", rule.name(), rule.description());
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+    if !args.print.is_empty() {
+        print(packages(checker(&args.workspace)?, args), &args.print)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let open = opened_output(&args.output)?;
+    let (format, mut sections) = if let Some(manifests) = workspace_manifests(args)? {
+        check_workspaces(args, &manifests)?
+    } else if args.workspaces.combine {
+        bail!("--combine needs several workspaces, from --nested, --recurse or several --manifest-path arguments");
+    } else {
+        let (format, report) = check_workspace(args)?;
+        (format, vec![WorkspaceSection { combined: false, report }])
+    };
+
+    let format = open
+        .as_ref()
+        .map_or_else(|| args.output.format.or(format).unwrap_or_default(), |(format, _)| *format);
+    if args.output.only_sccs {
+        only_sccs(format)?;
+        for section in &mut sections {
+            query::retain_cycles(&mut section.report);
+        }
+    }
+    if let Some((_, path)) = &open {
+        output::emit_sections(&sections, format, Some(path))?;
+        output::open(path)?;
     } else {
-        eprintln!("Error: Cyclic dependencies detected!\n");
-        for (i, cycle) in cycles.iter().enumerate() {
-            eprintln!("Cycle {}:", i + 1);
-            eprintln!("  {}", format_cycle(cycle, &metadata));
-            eprintln!();
+        output::emit_sections(&sections, format, args.output.output.as_deref())?;
+    }
+    if args.output.verbose {
+        for report in sections.iter().map(|s| &s.report).filter(|r| !r.skipped.is_empty()) {
+            eprintln!("Note: Skipped by their package metadata: {}", report.skipped.join(", "));
         }
-        std::process::exit(1);
     }
+
+    Ok(if sections.iter().any(|s| s.report.is_failure()) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
 }
 
-/// Detects cycles in workspace crate dependencies using Tarjan's strongly connected components algorithm
-fn detect_cycles(metadata: &Metadata) -> Vec<Vec<PackageId>> {
-    let mut graph = DiGraph::<PackageId, ()>::new();
-    let mut node_map = HashMap::new();
+/// Checks that `--only-sccs` is given with a format exporting the graph.
+fn only_sccs(format: OutputFormat) -> Result<()> {
+    if !format.is_graph() {
+        bail!("--only-sccs needs a graph output format, such as dot, mermaid or graphml");
+    }
+    Ok(())
+}
 
-    // Add nodes for each workspace package
-    for package in metadata.workspace_packages() {
-        let idx = graph.add_node(package.id.clone());
-        node_map.insert(package.id.clone(), idx);
+/// Returns the format and the file of the output to open in the browser, if it is.
+fn opened_output(args: &OutputArgs) -> Result<Option<(OutputFormat, PathBuf)>> {
+    if !args.open {
+        return Ok(None);
     }
+    let format = args.format.unwrap_or(OutputFormat::Svg);
+    let extension = match format {
+        OutputFormat::Svg => "svg",
+        OutputFormat::Html | OutputFormat::HtmlInteractive => "html",
+        _ => bail!("--open needs the svg, html or html-interactive format"),
+    };
+    let path = args
+        .output
+        .clone()
+        .unwrap_or_else(|| env::temp_dir().join(format!("ensure-no-cyclic-deps.{extension}")));
+    Ok(Some((format, path)))
+}
 
-    // Add edges for dependencies (only workspace dependencies)
-    for package in metadata.workspace_packages() {
-        let from_idx = node_map[&package.id];
+/// Checks the workspace, recording or ratcheting its cycles if requested, returns the configured
+/// output format and the report.
+fn check_workspace(args: &CheckArgs) -> Result<(Option<OutputFormat>, Report)> {
+    let Analysis {
+        config, report, ratchet, ..
+    } = configure(checker(&args.workspace)?, args).analyze()?;
 
-        for dep in &package.dependencies {
-            // Only consider workspace dependencies
-            if let Some(dep_pkg) = metadata.packages.iter().find(|p| p.name == dep.name)
-                && let Some(&to_idx) = node_map.get(&dep_pkg.id)
-            {
-                graph.add_edge(from_idx, to_idx, ());
-            }
+    if let (Some(path), Some(outcome)) = (&args.ratchet, ratchet) {
+        match outcome {
+            Outcome::Increased { recorded, current } => eprintln!(
+                "Error: The number of cycles increased from {recorded} to {current} (ratchet {})",
+                path.display()
+            ),
+            Outcome::Decreased { recorded, current } => eprintln!(
+                "The number of cycles decreased from {recorded} to {current}, updated ratchet {}",
+                path.display()
+            ),
+            Outcome::Grown { recorded, current } => eprintln!(
+                "Error: A cycle grew, the cycle sizes went from {recorded:?} to {current:?} (ratchet {})",
+                path.display()
+            ),
+            Outcome::Shrunk { recorded, current } => eprintln!(
+                "The cycle sizes shrank from {recorded:?} to {current:?}, updated ratchet {}",
+                path.display()
+            ),
+            Outcome::Created | Outcome::Unchanged => {}
+        }
+    }
+
+    Ok((config.format, report))
+}
+
+/// Returns the manifests of the workspaces to check when several are, the first one's
+/// configuration selects the output format.
+fn workspace_manifests(args: &CheckArgs) -> Result<Option<Vec<Utf8PathBuf>>> {
+    if let Some(dir) = &args.workspaces.recurse {
+        let dir = utf8_path(dir)?;
+        let manifests = workspaces::discover(dir);
+        if manifests.is_empty() {
+            bail!("No workspaces found in {dir}");
+        }
+        return Ok(Some(manifests));
+    }
+    let manifest_paths = &args.workspace.manifest_path;
+    if args.workspaces.nested {
+        return workspaces::with_nested(manifest_paths).map(Some);
+    }
+    if manifest_paths.len() > 1 {
+        if args.write_baseline.is_some() || args.ratchet.is_some() {
+            bail!("--write-baseline and --ratchet can't be used with several workspaces");
         }
+        let manifests = manifest_paths
+            .iter()
+            .map(|p| utf8_path(p).map(Utf8Path::to_owned))
+            .collect::<Result<_>>()?;
+        return Ok(Some(manifests));
     }
+    Ok(None)
+}
+
+/// Returns the path as a UTF-8 path, as cargo uses.
+fn utf8_path(path: &Path) -> Result<&Utf8Path> {
+    Utf8Path::from_path(path).with_context(|| format!("The path {} isn't valid UTF-8", path.display()))
+}
 
-    // Find strongly connected components using Tarjan's algorithm
-    let sccs = tarjan_scc(&graph);
+/// Checks each of the workspaces in isolation and optionally combined, returns the output format
+/// configured for the first one and a section per check.
+fn check_workspaces(args: &CheckArgs, manifests: &[Utf8PathBuf]) -> Result<(Option<OutputFormat>, Vec<WorkspaceSection>)> {
+    workspaces::check(manifests, args.workspaces.combine, || configure(options(&args.workspace), args))
+}
 
-    // Extract cycles (SCCs with more than one node indicate a cycle)
-    // Also check for self-loops (nodes with edges to themselves)
-    let mut cycles: Vec<Vec<PackageId>> = sccs
-        .into_iter()
-        .filter(|scc| scc.len() > 1)
-        .map(|scc| scc.iter().map(|&idx| graph[idx].clone()).collect())
-        .collect();
+/// Limits the check to the selected packages.
+fn packages(mut checker: Checker, args: &CheckArgs) -> Checker {
+    for spec in &args.package {
+        checker = checker.package(spec);
+    }
+    checker
+}
 
-    // Detect self-loops (a node depending on itself)
-    for package in metadata.workspace_packages() {
-        if let Some(&node_idx) = node_map.get(&package.id) {
-            // Check if there's an edge from this node to itself
-            if graph.contains_edge(node_idx, node_idx) {
-                cycles.push(vec![package.id.clone()]);
+/// Configures the check with the options of the check command.
+fn configure(checker: Checker, args: &CheckArgs) -> Checker {
+    let mut checker = packages(checker, args).strict_baseline(args.strict_baseline);
+    if args.check_features {
+        checker = checker.check_features(true);
+    }
+    if let Some(max) = args.max_cycles {
+        checker = checker.max_cycles(max);
+    }
+    for pattern in &args.focus {
+        checker = checker.focus(pattern);
+    }
+    for prefix in &args.scope {
+        checker = checker.scope(prefix);
+    }
+    if args.cycles.all_cycles {
+        checker = checker.all_cycles(args.cycles.max_cycles_per_scc);
+    }
+    if args.cycles.suggest_cuts {
+        checker = checker.suggest_cuts(true);
+    }
+    if args.graph.condense {
+        checker = checker.condense(true);
+    }
+    if args.graph.near_cycles {
+        checker = checker.near_cycles(true);
+    }
+    for path in &args.baseline {
+        checker = checker.baseline(path);
+    }
+    if let Some(path) = &args.write_baseline {
+        checker = checker.write_baseline(path);
+    }
+    if let Some(path) = &args.ratchet {
+        checker = checker.ratchet(path);
+    }
+    checker
+}
+
+/// Prints the members or edges of the graph the check is based on.
+fn print(checker: Checker, requests: &[PrintRequest]) -> Result<()> {
+    let report = checker.analyze()?.report;
+    for request in requests {
+        match request {
+            PrintRequest::Members => {
+                for member in &report.members {
+                    let manifest = report.relative_manifest_path(&member.id).unwrap_or_default();
+                    println!("{} ({manifest})", member.name);
+                }
             }
+            PrintRequest::Edges => {
+                for edge in &report.dependencies {
+                    println!(
+                        "{} -> {} ({}) at {}",
+                        edge.from.name,
+                        edge.to.name,
+                        edge.label(),
+                        report.declaration_location(edge)
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn suppress(args: &SuppressArgs) -> Result<()> {
+    let mut report = checker(&args.workspace)?.analyze()?.report;
+    let recorded = baseline::record_failing(
+        &mut report,
+        args.baseline.as_deref(),
+        args.workspace.config.as_deref(),
+        args.reason.as_deref(),
+    )?;
+    match recorded {
+        Some((count, path)) => println!("Suppressed {count} cycle(s) in {}", path.display()),
+        None => println!("No cycles to suppress."),
+    }
+    Ok(())
+}
+
+/// Prints the shortest paths of dependencies between the members, fails if there is none.
+fn why(args: &WhyArgs) -> Result<ExitCode> {
+    let report = checker(&args.workspace)?.analyze()?.report;
+    let from = query::member(&report, &args.from)?;
+    let to = query::member(&report, &args.to)?;
+
+    let paths = query::shortest_paths(&report, &from.id, &to.id, args.paths.get());
+    if paths.is_empty() {
+        println!("`{}` doesn't depend on `{}`.", from.name, to.name);
+        return Ok(ExitCode::FAILURE);
+    }
+    for path in paths {
+        println!("{}", query::annotated_path(&path));
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Prints the members depending on the member, with the length of the shortest path to it.
+fn rdeps(args: &RdepsArgs) -> Result<()> {
+    let report = checker(&args.workspace)?.analyze()?.report;
+    let member = query::member(&report, &args.name)?;
+
+    let dependents = query::dependents(&report, &member.id);
+    if dependents.is_empty() {
+        println!("No workspace member depends on `{}`.", member.name);
+    }
+    for (dependent, depth) in dependents {
+        println!("{} (depth {depth})", dependent.name);
+    }
+    Ok(())
+}
+
+/// Emits the graph of the selected members, without failing on cycles.
+fn graph(args: &GraphCommandArgs) -> Result<()> {
+    let mut report = checker(&args.workspace)?.analyze()?.report;
+    if !args.package.is_empty() {
+        let ids = args
+            .package
+            .iter()
+            .map(|name| query::member(&report, name).map(|m| m.id.clone()))
+            .collect::<Result<Vec<_>>>()?;
+        query::retain_dependencies_of(&mut report, &ids);
+    }
+    if args.only_sccs {
+        only_sccs(args.format)?;
+        query::retain_cycles(&mut report);
+    }
+    output::emit(&report, args.format, args.output.as_deref())
+}
+
+/// Prints the members in dependency order, or the cycles preventing it.
+fn order(args: &OrderArgs) -> Result<ExitCode> {
+    let report = checker(&args.workspace)?.analyze()?.report;
+
+    let Some(members) = query::dependency_order(&report) else {
+        eprintln!("Error: The workspace members can't be ordered, they have cyclic dependencies:");
+        for cycle in &report.cycles {
+            eprintln!("  {} ({})", cycle.path(), cycle.fingerprint);
         }
+        return Ok(ExitCode::FAILURE);
+    };
+    for member in members {
+        println!("{}", member.name);
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Checks whether the dependency would create a cycle, which fails.
+fn check_add(args: &CheckAddArgs) -> Result<ExitCode> {
+    let report = checker(&args.workspace)?.analyze()?.report;
+    let package = query::member(&report, &args.package)?;
+    // Crates outside of the workspace can't depend on its members
+    let Ok(dep) = query::member(&report, &args.dep) else {
+        println!(
+            "`{}` isn't a member of the workspace, depending on it doesn't create a cycle.",
+            args.dep
+        );
+        return Ok(ExitCode::SUCCESS);
+    };
+
+    let path = if package.id == dep.id {
+        Some(Vec::new())
+    } else {
+        query::shortest_path(&report, &dep.id, &package.id)
+    };
+    let Some(path) = path else {
+        println!("Adding `{}` to `{}` doesn't create a cycle.", dep.name, package.name);
+        return Ok(ExitCode::SUCCESS);
+    };
+    let existing = if path.is_empty() {
+        dep.name.clone()
+    } else {
+        query::annotated_path(&path)
+    };
+    println!(
+        "Adding `{}` to `{}` would create a cycle: {} -> {existing}",
+        dep.name, package.name, package.name
+    );
+    Ok(ExitCode::FAILURE)
+}
+
+/// Prints the metrics of the workspace graph.
+fn stats(args: &StatsArgs) -> Result<()> {
+    let report = checker(&args.workspace)?.analyze()?.report;
+    let stats = Stats::new(&report);
+    match args.format {
+        StatsFormat::Human => println!("{stats}"),
+        StatsFormat::Json => println!("{}", serde_json::to_string_pretty(&stats)?),
+    }
+    Ok(())
+}
+
+/// Explores the workspace graph with the commands read from stdin.
+fn explore(args: &ExploreArgs) -> Result<()> {
+    let report = checker(&args.workspace)?.analyze()?.report;
+    explore::run(&report, io::stdin().lock(), io::stdout().lock())?;
+    Ok(())
+}
+
+/// Prints the details of the cycle, fails if it no longer exists.
+fn explain(args: &ExplainArgs) -> Result<ExitCode> {
+    let report = checker(&args.workspace)?.analyze()?.report;
+    let Some(cycle) = report.cycle(&args.fingerprint) else {
+        println!("No cycle with fingerprint {} exists in the workspace.", args.fingerprint);
+        return Ok(ExitCode::FAILURE);
+    };
+
+    println!("Cycle {}: {}", cycle.fingerprint, cycle.path());
+    if cycle.suppressed {
+        println!("  suppressed");
+    }
+    if cycle.dev_only {
+        println!("  only exists because of dev-dependencies");
+    }
+    if cycle.patch_only {
+        println!("  only exists because of [patch] or [replace] in the workspace manifest");
+    }
+    println!("  dependencies:");
+    for edge in &cycle.edges {
+        println!(
+            "    {} -> {} ({}) at {}",
+            edge.from.name,
+            edge.to.name,
+            edge.label(),
+            report.declaration_location(edge)
+        );
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Prints the differences between the declared and the resolved dependencies, which fail.
+fn resolve_diff(args: &ResolveDiffArgs) -> Result<ExitCode> {
+    let declared = checker(&args.workspace)?.analyze()?.report;
+    let resolved = checker(&args.workspace)?.resolved(true).analyze()?.report;
+    if let Some(err) = &resolved.resolve_error {
+        bail!("The dependencies can't be resolved: {err}");
     }
 
-    cycles
-}
-
-/// Format a cycle for display
-fn format_cycle(cycle: &[PackageId], metadata: &Metadata) -> String {
-    let names: Vec<String> = cycle
-        .iter()
-        .map(|id| {
-            metadata
-                .packages
-                .iter()
-                .find(|p| &p.id == id)
-                .map_or_else(|| id.to_string(), |p| p.name.clone())
-        })
-        .collect();
-
-    names
-        .iter()
-        .chain(core::iter::once(&names[0]))
-        .map(String::as_str)
-        .collect::<Vec<_>>()
-        .join(" -> ")
+    let diff = ResolutionDiff::new(&declared, &resolved);
+    if diff.is_empty() {
+        println!("The declared and the resolved dependencies are the same.");
+        return Ok(ExitCode::SUCCESS);
+    }
+    let sections = [
+        ("Declared but not activated by cargo's resolver:", &diff.declared_only, &declared),
+        ("Only activated by cargo's resolver:", &diff.resolved_only, &resolved),
+    ];
+    for (title, edges, report) in sections.into_iter().filter(|(_, edges, _)| !edges.is_empty()) {
+        println!("{title}");
+        for edge in edges {
+            println!(
+                "  {} -> {} ({}) at {}",
+                edge.from.name,
+                edge.to.name,
+                edge.label(),
+                report.declaration_location(edge)
+            );
+        }
+    }
+    let sections = [
+        ("Cycles only between the declared dependencies:", &diff.declared_only_cycles),
+        ("Cycles only between the resolved dependencies:", &diff.resolved_only_cycles),
+    ];
+    for (title, cycles) in sections.into_iter().filter(|(_, cycles)| !cycles.is_empty()) {
+        println!("{title}");
+        for cycle in cycles {
+            println!("  {} ({})", cycle.path(), cycle.fingerprint);
+        }
+    }
+    Ok(ExitCode::FAILURE)
 }
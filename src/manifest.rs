@@ -0,0 +1,212 @@
+//! Lookups in the raw `Cargo.toml` files, for pointing diagnostics at declarations.
+
+use std::fs;
+
+use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
+use toml::{Table, Value};
+
+use crate::graph::DepKind;
+
+/// Formats the path relative to the base directory, using `/` as separator and `..` for paths
+/// outside of it, e.g. crates outside a workspace.
+pub fn relative_path(path: &Utf8Path, base: &Utf8Path) -> String {
+    let mut path = path.components().peekable();
+    let mut base = base.components().peekable();
+    while path.peek().is_some() && path.peek() == base.peek() {
+        path.next();
+        base.next();
+    }
+    base.map(|_| "..").chain(path.map(|c| c.as_str())).collect::<Vec<_>>().join("/")
+}
+
+/// Where a dependency is declared in a manifest.
+#[derive(Debug)]
+pub struct DependencyDeclaration {
+    /// 1-based line of the declaration.
+    pub line: usize,
+    /// Whether the declaration inherits the dependency from the workspace with `workspace = true`.
+    pub inherited: bool,
+}
+
+/// Finds the declaration of the dependency with the given key in the manifest.
+///
+/// Both inline declarations (`dep = ...` in a dependency table) and dedicated tables
+/// (`[dependencies.dep]`) are recognized. Returns `None` if the manifest can't be read or the
+/// declaration isn't found.
+pub fn dependency_declaration(manifest_path: &Utf8Path, key: &str) -> Option<DependencyDeclaration> {
+    let contents = fs::read_to_string(manifest_path).ok()?;
+    let lines: Vec<&str> = contents.lines().map(str::trim).collect();
+    let line = find_declaration(&lines, key, is_dependency_table)?;
+    Some(DependencyDeclaration {
+        line: line + 1,
+        inherited: inherits_workspace(&lines[line..]),
+    })
+}
+
+/// Returns the 1-based line on which the dependency with the given key is declared in the
+/// `[workspace.dependencies]` table of the workspace manifest.
+pub fn workspace_dependency_line(manifest_path: &Utf8Path, key: &str) -> Option<usize> {
+    let contents = fs::read_to_string(manifest_path).ok()?;
+    let lines: Vec<&str> = contents.lines().map(str::trim).collect();
+    find_declaration(&lines, key, |table| table == "workspace.dependencies").map(|line| line + 1)
+}
+
+/// Returns the 0-based index of the line declaring the dependency in a table accepted by `is_table`.
+fn find_declaration(lines: &[&str], key: &str, is_table: impl Fn(&str) -> bool) -> Option<usize> {
+    let mut in_dependency_table = false;
+    for (i, line) in lines.iter().enumerate() {
+        if let Some(header) = line.strip_prefix('[') {
+            let header = header.trim_end_matches(']').trim();
+            if let Some((table, name)) = header.rsplit_once('.')
+                && is_table(table)
+                && unquote(name) == key
+            {
+                return Some(i);
+            }
+            in_dependency_table = is_table(header);
+        } else if in_dependency_table
+            && let Some((name, _)) = line.split_once(['=', '.'])
+            && unquote(name) == key
+        {
+            return Some(i);
+        }
+    }
+    None
+}
+
+/// Returns whether the declaration on the first line sets `workspace = true`, inline
+/// (`dep = { workspace = true }`, `dep.workspace = true`) or in its dedicated table.
+fn inherits_workspace(lines: &[&str]) -> bool {
+    let sets_workspace = |s: &str| s.replace(' ', "").contains("workspace=true");
+    let Some((first, rest)) = lines.split_first() else {
+        return false;
+    };
+    if first.starts_with('[') {
+        rest.iter().take_while(|l| !l.starts_with('[')).any(|l| sets_workspace(l))
+    } else {
+        first.split_once(['=', '.']).is_some_and(|(_, value)| sets_workspace(value))
+    }
+}
+
+/// Reads the manifest as a TOML table, returns `None` if it can't be read or parsed.
+pub fn read(manifest_path: &Utf8Path) -> Option<Table> {
+    fs::read_to_string(manifest_path).ok()?.parse().ok()
+}
+
+/// Returns the artifacts, e.g. `bin` or `bin, cdylib`, of the dependency with the given key if it
+/// is declared as an artifact dependency with `artifact = ...`.
+///
+/// The dependency is looked up in the section of the kind, below `[target.<target>]` if it's
+/// restricted to a platform.
+pub fn artifact(manifest: &Table, kind: DepKind, target: Option<&str>, key: &str) -> Option<String> {
+    let section = match target {
+        Some(target) => manifest.get("target")?.get(target)?.as_table()?,
+        None => manifest,
+    };
+    let tables: &[&str] = match kind {
+        DepKind::Normal => &["dependencies"],
+        DepKind::Dev => &["dev-dependencies", "dev_dependencies"],
+        DepKind::Build => &["build-dependencies", "build_dependencies"],
+    };
+    let artifact = tables.iter().find_map(|table| section.get(*table)?.get(key)?.get("artifact"))?;
+    match artifact {
+        Value::String(artifact) => Some(artifact.clone()),
+        Value::Array(artifacts) => Some(artifacts.iter().filter_map(Value::as_str).collect::<Vec<_>>().join(", ")),
+        _ => None,
+    }
+}
+
+/// A dependency redirected to a local path by `[patch]` or `[replace]` in the workspace manifest.
+#[derive(Debug)]
+pub struct Override {
+    /// Section of the override, e.g. `patch.crates-io` or `replace`.
+    pub section: String,
+    /// For `[patch]`, the registry name or git URL whose dependencies are patched.
+    pub source: Option<String>,
+    /// Name of the overridden package.
+    pub package: String,
+    /// Directory the package is redirected to.
+    pub path: Utf8PathBuf,
+}
+
+/// Returns the overrides with a path in the `[patch]` and `[replace]` sections of the workspace
+/// manifest, paths are relative to the workspace root.
+pub fn overrides(workspace_root: &Utf8Path) -> Vec<Override> {
+    let Some(manifest) = read(&workspace_root.join("Cargo.toml")) else {
+        return Vec::new();
+    };
+    let mut overrides = Vec::new();
+    let path = |entry: &Value| {
+        let path = Utf8Path::new(entry.get("path")?.as_str()?);
+        // Paths like `./foo` have to match the manifest paths reported by cargo
+        Some(workspace_root.join(path.components().filter(|c| c.as_str() != ".").collect::<Utf8PathBuf>()))
+    };
+    let patches = manifest.get("patch").and_then(Value::as_table).into_iter().flatten();
+    for (source, entries) in patches {
+        for (key, entry) in entries.as_table().into_iter().flatten() {
+            if let Some(path) = path(entry) {
+                overrides.push(Override {
+                    section: format!("patch.{source}"),
+                    source: Some(source.clone()),
+                    package: entry.get("package").and_then(Value::as_str).unwrap_or(key).to_owned(),
+                    path,
+                });
+            }
+        }
+    }
+    // Replacements are keyed by package id specs like `foo:1.0.0`
+    for (spec, entry) in manifest.get("replace").and_then(Value::as_table).into_iter().flatten() {
+        if let Some(path) = path(entry) {
+            overrides.push(Override {
+                section: String::from("replace"),
+                source: None,
+                package: spec.split_once(':').map_or(spec.as_str(), |(name, _)| name).to_owned(),
+                path,
+            });
+        }
+    }
+    overrides
+}
+
+/// Returns the manifests of the crates listed in `workspace.exclude` of the workspace manifest, or
+/// in the direct subdirectories of excluded directories that have no manifest of their own.
+pub fn excluded(workspace_root: &Utf8Path) -> Vec<Utf8PathBuf> {
+    let Some(manifest) = read(&workspace_root.join("Cargo.toml")) else {
+        return Vec::new();
+    };
+    let excluded = manifest.get("workspace").and_then(|w| w.get("exclude")).and_then(Value::as_array);
+    let mut manifests = Vec::new();
+    for path in excluded.into_iter().flatten().filter_map(Value::as_str) {
+        let dir = workspace_root.join(path);
+        if dir.join("Cargo.toml").is_file() {
+            manifests.push(dir.join("Cargo.toml"));
+            continue;
+        }
+        let mut nested: Vec<Utf8PathBuf> = dir
+            .read_dir_utf8()
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| Some(entry.ok()?.path().join("Cargo.toml")))
+            .filter(|manifest| manifest.is_file())
+            .collect();
+        nested.sort();
+        manifests.extend(nested);
+    }
+    manifests
+}
+
+fn is_dependency_table(header: &str) -> bool {
+    // The dependencies members inherit from are looked up separately
+    if header.starts_with("workspace.") {
+        return false;
+    }
+    let table = header.rsplit('.').next().unwrap_or(header);
+    matches!(
+        table,
+        "dependencies" | "dev-dependencies" | "dev_dependencies" | "build-dependencies" | "build_dependencies"
+    )
+}
+
+fn unquote(s: &str) -> &str {
+    s.trim().trim_matches(['"', '\''])
+}
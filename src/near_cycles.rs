@@ -0,0 +1,89 @@
+//! Dependencies that don't exist yet but would create a cycle when added.
+
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::report::Report;
+
+/// A dependency between workspace members that would create a cycle, because the depended on
+/// member already depends on the dependent one.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NearCycle {
+    /// Name of the member the dependency would be added to.
+    pub from: String,
+    /// Name of the member that would be depended on.
+    pub to: String,
+    /// Shortest existing path from `to` back to `from`, as package names in dependency order.
+    pub path: Vec<String>,
+}
+
+/// Records on the report the dependencies that would create a new cycle.
+///
+/// Members already cyclically depending on each other are left out.
+pub fn apply(report: &mut Report) {
+    report.near_cycles = near_cycles(report);
+}
+
+fn near_cycles(report: &Report) -> Vec<NearCycle> {
+    let mut adjacency = vec![Vec::new(); report.members.len()];
+    for edge in &report.dependencies {
+        if let (Some(from), Some(to)) = (report.member_index(&edge.from.id), report.member_index(&edge.to.id))
+            && from != to
+        {
+            adjacency[from].push(to);
+        }
+    }
+    let same_cycle = |a: usize, b: usize| {
+        let (a, b) = (&report.members[a].id, &report.members[b].id);
+        report.cycles.iter().any(|c| c.contains(a) && c.contains(b))
+    };
+
+    let mut near_cycles = Vec::new();
+    for start in 0..report.members.len() {
+        for (end, path) in shortest_paths(&adjacency, start) {
+            if end != start && !same_cycle(start, end) {
+                near_cycles.push(NearCycle {
+                    from: report.members[end].name.clone(),
+                    to: report.members[start].name.clone(),
+                    path: path.into_iter().map(|idx| report.members[idx].name.clone()).collect(),
+                });
+            }
+        }
+    }
+    near_cycles.sort_by(|a, b| (&a.from, &a.to).cmp(&(&b.from, &b.to)));
+    near_cycles
+}
+
+/// Returns the nodes reachable from `start` with the shortest path to each, starting with `start`.
+fn shortest_paths(adjacency: &[Vec<usize>], start: usize) -> Vec<(usize, Vec<usize>)> {
+    let mut previous: Vec<Option<usize>> = vec![None; adjacency.len()];
+    let mut seen = vec![false; adjacency.len()];
+    seen[start] = true;
+    let mut order = Vec::new();
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        order.push(node);
+        for &next in &adjacency[node] {
+            if !seen[next] {
+                seen[next] = true;
+                previous[next] = Some(node);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    order
+        .into_iter()
+        .map(|end| {
+            let mut path = vec![end];
+            let mut node = end;
+            while let Some(prev) = previous[node] {
+                path.push(prev);
+                node = prev;
+            }
+            path.reverse();
+            (end, path)
+        })
+        .collect()
+}
@@ -0,0 +1,298 @@
+//! Rendering of the check results in the supported output formats.
+
+mod codeclimate;
+mod cytoscape;
+mod d2;
+mod dot;
+mod dsm;
+mod gexf;
+mod github;
+mod graphml;
+mod html;
+mod html_interactive;
+mod human;
+mod json;
+mod junit;
+mod markdown;
+mod mermaid;
+mod plantuml;
+mod sarif;
+mod svg;
+mod toml;
+mod tree;
+mod yaml;
+
+use std::path::Path;
+use std::process::Command;
+use std::{env, fs};
+
+use anyhow::{Context, Result, bail};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::report::Report;
+
+/// Format in which the check results are emitted.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Human readable text.
+    #[default]
+    Human,
+    /// Machine-readable JSON.
+    Json,
+    /// Machine-readable YAML, structured like the JSON output.
+    Yaml,
+    /// Machine-readable TOML, structured like the JSON output.
+    Toml,
+    /// SARIF log for GitHub code scanning.
+    Sarif,
+    /// Graphviz DOT graph of the workspace with cycles highlighted.
+    Dot,
+    /// Mermaid diagram of the workspace with cycles highlighted.
+    Mermaid,
+    /// JUnit XML report with one test case per workspace member.
+    Junit,
+    /// GitHub Actions error annotations.
+    Github,
+    /// Code Climate issues for GitLab Code Quality.
+    Codeclimate,
+    /// Self-contained HTML report with the cycles, crates and an SVG rendering of the affected subgraph.
+    Html,
+    /// Self-contained HTML page with an interactive rendering of the workspace graph.
+    #[serde(rename = "html-interactive")]
+    HtmlInteractive,
+    /// Markdown report for pull request comments.
+    Markdown,
+    /// SVG image of the packages participating in cycles.
+    Svg,
+    /// D2 diagram of the workspace with a container per directory and cycles highlighted.
+    D2,
+    /// PlantUML component diagram of the workspace with cycles highlighted.
+    Plantuml,
+    /// GraphML graph of the workspace with the attributes of the crates, for yEd or Gephi.
+    Graphml,
+    /// GEXF graph of the workspace with the attributes of the crates, for Gephi.
+    Gexf,
+    /// Cytoscape.js elements JSON of the workspace graph, for interactive viewers.
+    Cytoscape,
+    /// Tree of the dependencies within each cycle, like `cargo tree` with the dependencies closing the
+    /// cycle marked.
+    Tree,
+    /// Design Structure Matrix of the workspace crates as CSV, rows depending on columns.
+    #[serde(rename = "dsm-csv")]
+    DsmCsv,
+    /// Design Structure Matrix of the workspace crates as a self-contained HTML table, with the
+    /// dependencies above the diagonal highlighted.
+    #[serde(rename = "dsm-html")]
+    DsmHtml,
+}
+
+impl OutputFormat {
+    /// Returns whether the format is an export of the dependency graph, rather than a report of the
+    /// check results.
+    #[must_use]
+    pub const fn is_graph(self) -> bool {
+        matches!(
+            self,
+            Self::Dot
+                | Self::Mermaid
+                | Self::Svg
+                | Self::D2
+                | Self::Plantuml
+                | Self::Graphml
+                | Self::Gexf
+                | Self::Cytoscape
+                | Self::HtmlInteractive
+                | Self::DsmCsv
+                | Self::DsmHtml
+        )
+    }
+}
+
+/// Renders the report in the given format.
+///
+/// # Errors
+///
+/// Fails if the report can't be serialized in the format.
+pub fn render(report: &Report, format: OutputFormat) -> Result<String> {
+    let mut out = match format {
+        OutputFormat::Human => human::render(report),
+        OutputFormat::Json => json::render(report)?,
+        OutputFormat::Yaml => yaml::render(report)?,
+        OutputFormat::Toml => toml::render(report)?,
+        OutputFormat::Sarif => sarif::render(report)?,
+        OutputFormat::Dot => dot::render(report),
+        OutputFormat::Mermaid => mermaid::render(report),
+        OutputFormat::Junit => junit::render(report),
+        OutputFormat::Github => github::render(report),
+        OutputFormat::Codeclimate => codeclimate::render(report)?,
+        OutputFormat::Html => html::render(report),
+        OutputFormat::Markdown => markdown::render(report),
+        OutputFormat::Svg => svg::render(report),
+        OutputFormat::Tree => tree::render(report),
+        OutputFormat::D2 => d2::render(report),
+        OutputFormat::Plantuml => plantuml::render(report),
+        OutputFormat::Graphml => graphml::render(report),
+        OutputFormat::Gexf => gexf::render(report),
+        OutputFormat::Cytoscape => cytoscape::render(report)?,
+        OutputFormat::HtmlInteractive => html_interactive::render(report)?,
+        OutputFormat::DsmCsv => dsm::render_csv(report),
+        OutputFormat::DsmHtml => dsm::render_html(report),
+    };
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Emits the report in the given format, either to the output file or to the terminal.
+///
+/// On the terminal, the human readable report of detected cycles goes to stderr while all
+/// other output goes to stdout.
+///
+/// # Errors
+///
+/// Fails if the report can't be rendered or the output can't be written.
+pub fn emit(report: &Report, format: OutputFormat, output: Option<&Path>) -> Result<()> {
+    let rendered = render(report, format)?;
+
+    if let Some(path) = output {
+        return fs::write(path, rendered).with_context(|| format!("Failed to write output to {}", path.display()));
+    }
+
+    if format == OutputFormat::Human {
+        print_human(report, &rendered);
+    } else {
+        print!("{rendered}");
+    }
+    Ok(())
+}
+
+/// Report of one of the workspaces checked in a run.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkspaceSection {
+    /// Whether the report is of the graph combining all checked workspaces.
+    #[serde(default)]
+    pub combined: bool,
+    /// Result of checking the workspace.
+    pub report: Report,
+}
+
+impl WorkspaceSection {
+    /// Returns the heading of the section in text output.
+    #[must_use]
+    pub fn heading(&self) -> String {
+        if self.combined {
+            String::from("All workspaces combined")
+        } else {
+            format!("Workspace {}", self.report.workspace_root)
+        }
+    }
+}
+
+/// Structure of the `json`, `yaml` and `toml` output of several workspaces.
+#[derive(Serialize)]
+struct Workspaces<'a> {
+    workspaces: &'a [WorkspaceSection],
+}
+
+/// Emits the reports of several workspaces in the given format, either to the output file or to
+/// the terminal, with a section per workspace.
+///
+/// A single report is emitted as by [`emit`]. Formats describing a single workspace, e.g. `sarif`,
+/// can't be used for several.
+///
+/// # Errors
+///
+/// Fails if the format only supports a single workspace, or the reports can't be rendered or the
+/// output can't be written.
+pub fn emit_sections(sections: &[WorkspaceSection], format: OutputFormat, output: Option<&Path>) -> Result<()> {
+    if let [section] = sections {
+        return emit(&section.report, format, output);
+    }
+    let rendered: Vec<String> = match format {
+        OutputFormat::Human => sections
+            .iter()
+            .map(|s| format!("{}:\n{}\n\n", s.heading(), human::render(&s.report).trim_end()))
+            .collect(),
+        OutputFormat::Markdown => sections
+            .iter()
+            .map(|s| Ok(format!("## {}\n\n{}\n", s.heading(), render(&s.report, format)?)))
+            .collect::<Result<_>>()?,
+        OutputFormat::Github => sections.iter().map(|s| render(&s.report, format)).collect::<Result<_>>()?,
+        OutputFormat::Json => vec![serde_json::to_string_pretty(&Workspaces { workspaces: sections })? + "\n"],
+        OutputFormat::Yaml => vec![serde_yaml_ng::to_string(&Workspaces { workspaces: sections })?],
+        OutputFormat::Toml => vec![::toml::to_string_pretty(&Workspaces { workspaces: sections })?],
+        _ => {
+            let name = format.to_possible_value().map(|v| v.get_name().to_owned()).unwrap_or_default();
+            bail!("The {name} format only supports a single workspace");
+        }
+    };
+
+    if let Some(path) = output {
+        return fs::write(path, rendered.concat()).with_context(|| format!("Failed to write output to {}", path.display()));
+    }
+
+    if format == OutputFormat::Human {
+        for (section, rendered) in sections.iter().zip(&rendered) {
+            print_human(&section.report, rendered);
+        }
+    } else {
+        print!("{}", rendered.concat());
+    }
+    Ok(())
+}
+
+/// Opens the file in the browser given by the `BROWSER` environment variable, or else in the
+/// default application for its type.
+///
+/// # Errors
+///
+/// Fails if the browser can't be started or reports an error.
+pub fn open(path: &Path) -> Result<()> {
+    let mut command = env::var_os("BROWSER").filter(|b| !b.is_empty()).map_or_else(opener, Command::new);
+    let status = command
+        .arg(path)
+        .status()
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    if !status.success() {
+        bail!("Failed to open {}: {status}", path.display());
+    }
+    Ok(())
+}
+
+/// Returns the command opening a file in the default application for its type.
+fn opener() -> Command {
+    if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    }
+}
+
+/// Prints the human readable report, of detected cycles to stderr and otherwise to stdout.
+fn print_human(report: &Report, rendered: &str) {
+    if report.has_failures() || !report.feature_cycles.is_empty() || !report.violations.is_empty() || !report.stale_suppressions.is_empty()
+    {
+        eprint!("{rendered}");
+    } else {
+        print!("{rendered}");
+    }
+    if github::is_running_in_actions() {
+        print!("{}", github::render(report));
+    }
+}
+
+/// Escapes the characters with special meaning in XML text and attribute values.
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -0,0 +1,122 @@
+//! Code Climate issues, consumed by GitLab's Code Quality reports.
+
+use anyhow::Result;
+use serde_json::{Value, json};
+
+use crate::config::CONFIG_FILE_NAME;
+use crate::fingerprint;
+use crate::report::{Cycle, Edge, FeatureCycle, Report, Severity, StaleSuppression, Violation};
+
+/// Renders the report as a Code Climate JSON array with one issue per dependency declaration
+/// that forms a cycle or violates a rule, per cycle of feature definitions and per stale
+/// suppression.
+pub fn render(report: &Report) -> Result<String> {
+    let mut issues: Vec<Value> = report
+        .failing_cycles()
+        .flat_map(|cycle| cycle.edges.iter().map(move |edge| issue(report, cycle, edge)))
+        .collect();
+    issues.extend(report.feature_cycles.iter().map(|cycle| feature_issue(report, cycle)));
+    for violation in &report.violations {
+        if violation.edges.is_empty() {
+            issues.push(violation_issue(report, violation, None));
+        }
+        issues.extend(violation.edges.iter().map(|edge| violation_issue(report, violation, Some(edge))));
+    }
+
+    issues.extend(report.stale_suppressions.iter().map(stale_issue));
+
+    Ok(serde_json::to_string_pretty(&issues)?)
+}
+
+fn issue(report: &Report, cycle: &Cycle, edge: &Edge) -> Value {
+    let path = report.relative_manifest_path(&edge.from.id).unwrap_or_default();
+
+    // Each issue needs its own fingerprint, so combine the cycle's with the edge
+    let fingerprint = fingerprint::hash(&format!("{}|{}->{}", cycle.fingerprint, edge.from.name, edge.to.name));
+
+    json!({
+        "type": "issue",
+        "check_name": "cyclic-dependency",
+        "description": format!(
+            "Cyclic dependency detected: {} ({} depends on {}, cycle {})",
+            cycle.path(),
+            edge.from.name,
+            edge.to.name,
+            cycle.fingerprint
+        ),
+        "categories": ["Complexity"],
+        "fingerprint": fingerprint,
+        "severity": severity(report),
+        "location": {
+            "path": path,
+            "lines": { "begin": edge.line.unwrap_or(1) },
+        },
+    })
+}
+
+/// Builds the issue of a cycle of feature definitions, in the manifest defining its first feature.
+fn feature_issue(report: &Report, cycle: &FeatureCycle) -> Value {
+    let path = report
+        .feature_cycle_member(cycle)
+        .and_then(|member| report.relative_manifest_path(&member.id))
+        .unwrap_or_else(|| String::from("Cargo.toml"));
+
+    json!({
+        "type": "issue",
+        "check_name": "cyclic-feature",
+        "description": cycle.describe(),
+        "categories": ["Complexity"],
+        "fingerprint": fingerprint::hash(&cycle.features.join("|")),
+        "severity": severity(report),
+        "location": {
+            "path": path,
+            "lines": { "begin": 1 },
+        },
+    })
+}
+
+/// Builds the issue of a stale suppression, in its baseline or the configuration file.
+fn stale_issue(stale: &StaleSuppression) -> Value {
+    json!({
+        "type": "issue",
+        "check_name": "stale-suppression",
+        "description": stale.message(),
+        "categories": ["Bug Risk"],
+        "fingerprint": fingerprint::hash(&format!("{}|{}", stale.source, stale.describe())),
+        "severity": "major",
+        "location": {
+            "path": stale.baseline_path().unwrap_or(CONFIG_FILE_NAME),
+            "lines": { "begin": 1 },
+        },
+    })
+}
+
+/// Builds the issue of a rule violation at the declaration of the dependency, or the workspace
+/// manifest if the violation isn't caused by a dependency.
+fn violation_issue(report: &Report, violation: &Violation, edge: Option<&Edge>) -> Value {
+    let path = edge
+        .and_then(|e| report.relative_manifest_path(&e.from.id))
+        .unwrap_or_else(|| String::from("Cargo.toml"));
+    let dependency = edge.map(|e| format!("{}->{}", e.from.name, e.to.name)).unwrap_or_default();
+    let fingerprint = fingerprint::hash(&format!("{}|{}|{dependency}", violation.rule, violation.message));
+
+    json!({
+        "type": "issue",
+        "check_name": violation.rule,
+        "description": violation.message,
+        "categories": ["Complexity"],
+        "fingerprint": fingerprint,
+        "severity": severity(report),
+        "location": {
+            "path": path,
+            "lines": { "begin": edge.and_then(|e| e.line).unwrap_or(1) },
+        },
+    })
+}
+
+const fn severity(report: &Report) -> &'static str {
+    match report.severity {
+        Severity::Error => "major",
+        Severity::Warning => "minor",
+    }
+}
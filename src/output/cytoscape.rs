@@ -0,0 +1,66 @@
+//! Cytoscape.js elements JSON, for embedding the graph in interactive viewers.
+
+use anyhow::Result;
+use serde_json::{Map, Value, json};
+
+use crate::report::Report;
+
+/// Renders the workspace dependency graph as the `elements` of a Cytoscape.js graph, with the
+/// attributes of the packages and dependencies in their `data`.
+///
+/// Packages and dependencies participating in cycles have `cycle` set and the `cycle` class, the
+/// `scc` of a package is the fingerprint of its cycle.
+pub fn render(report: &Report) -> Result<String> {
+    let nodes: Vec<Value> = report
+        .members
+        .iter()
+        .enumerate()
+        .map(|(i, member)| {
+            let mut data = Map::new();
+            data.insert("id".into(), json!(format!("n{i}")));
+            data.insert("label".into(), json!(member.name));
+            data.insert("version".into(), json!(member.version));
+            data.insert("path".into(), json!(report.relative_manifest_path(&member.id)));
+            if let Some(cycle) = report.cycle_containing(&member.id) {
+                data.insert("scc".into(), json!(cycle.fingerprint));
+            }
+            if let Some(layer) = &member.layer {
+                data.insert("layer".into(), json!(layer));
+            }
+            let cycle = report.is_in_cycle(&member.id);
+            data.insert("cycle".into(), json!(cycle));
+            element(Value::Object(data), cycle)
+        })
+        .collect();
+
+    let edges: Vec<Value> = report
+        .dependencies
+        .iter()
+        .enumerate()
+        .filter_map(|(i, edge)| {
+            let (from, to) = (report.member_index(&edge.from.id)?, report.member_index(&edge.to.id)?);
+            let cycle = report.is_cycle_edge(edge);
+            let data = json!({
+                "id": format!("e{i}"),
+                "source": format!("n{from}"),
+                "target": format!("n{to}"),
+                "kinds": edge.label(),
+                "cycle": cycle,
+            });
+            Some(element(data, cycle))
+        })
+        .collect();
+
+    Ok(serde_json::to_string_pretty(
+        &json!({ "elements": { "nodes": nodes, "edges": edges } }),
+    )?)
+}
+
+fn element(data: Value, cycle: bool) -> Value {
+    let mut element = Map::new();
+    element.insert("data".into(), data);
+    if cycle {
+        element.insert("classes".into(), json!("cycle"));
+    }
+    Value::Object(element)
+}
@@ -0,0 +1,60 @@
+//! D2 diagram output.
+
+use core::fmt::Write;
+
+use cargo_metadata::PackageId;
+
+use crate::report::Report;
+
+const CLASSES: &str = "classes: {
+  cycle: {style: {stroke: \"#d00\"; font-color: \"#d00\"; stroke-width: 2}}
+  cycle-dependency: {style: {stroke: \"#d00\"; stroke-width: 2}}
+}
+";
+
+/// Renders the workspace dependency graph as a D2 diagram, with the packages in containers for the
+/// directories they are in and the `cycle` and `cycle-dependency` classes on the packages and
+/// edges that participate in cycles.
+pub fn render(report: &Report) -> String {
+    let mut out = String::new();
+    if !report.cycles.is_empty() {
+        out.push_str(CLASSES);
+    }
+
+    for member in &report.members {
+        let class = if report.is_in_cycle(&member.id) { ": {class: cycle}" } else { "" };
+        let _ = writeln!(out, "{}{class}", key(report, &member.id));
+    }
+
+    for edge in &report.dependencies {
+        if report.member(&edge.from.id).is_none() || report.member(&edge.to.id).is_none() {
+            continue;
+        }
+        let class = if report.is_cycle_edge(edge) {
+            ": {class: cycle-dependency}"
+        } else {
+            ""
+        };
+        let _ = writeln!(out, "{} -> {}{class}", key(report, &edge.from.id), key(report, &edge.to.id));
+    }
+
+    for cycle in &report.cycles {
+        let _ = writeln!(out, "# cycle {}: {}", cycle.fingerprint, cycle.path());
+    }
+    out
+}
+
+/// Returns the key of the package, its name in the containers of the directories above its own,
+/// relative to the workspace root, e.g. `"crates"."core"` for `crates/core/Cargo.toml`.
+fn key(report: &Report, id: &PackageId) -> String {
+    let name = report.member(id).map(|m| m.name.as_str()).unwrap_or_default();
+    let path = report.relative_manifest_path(id).unwrap_or_default();
+    let mut directories: Vec<&str> = path.split('/').filter(|c| *c != "..").collect();
+    // The manifest itself and the directory of the package
+    directories.truncate(directories.len().saturating_sub(2));
+    directories.into_iter().chain([name]).map(quote).collect::<Vec<_>>().join(".")
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
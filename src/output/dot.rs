@@ -0,0 +1,76 @@
+//! Graphviz DOT output.
+
+use core::fmt::Write;
+
+use crate::condensation::Condensation;
+use crate::report::Report;
+
+const CYCLE_COLOR: &str = "red";
+
+/// Renders the workspace dependency graph in DOT, highlighting packages and edges that
+/// participate in cycles.
+pub fn render(report: &Report) -> String {
+    if let Some(condensation) = &report.condensation {
+        return render_condensation(condensation);
+    }
+
+    let mut out = String::from("digraph workspace {\n    node [shape=box];\n");
+
+    for (i, member) in report.members.iter().enumerate() {
+        let style = if report.is_in_cycle(&member.id) {
+            format!(", color={CYCLE_COLOR}, fontcolor={CYCLE_COLOR}")
+        } else {
+            String::new()
+        };
+        let _ = writeln!(out, "    n{i} [label={}{style}];", quote(&member.name));
+    }
+
+    for edge in &report.dependencies {
+        let (Some(from), Some(to)) = (report.member_index(&edge.from.id), report.member_index(&edge.to.id)) else {
+            continue;
+        };
+        let style = if report.is_cycle_edge(edge) {
+            format!(" [color={CYCLE_COLOR}, penwidth=2]")
+        } else {
+            String::new()
+        };
+        let _ = writeln!(out, "    n{from} -> n{to}{style};");
+    }
+
+    for cycle in &report.cycles {
+        let _ = writeln!(out, "    // cycle {}: {}", cycle.fingerprint, cycle.path());
+    }
+
+    out.push('}');
+    out
+}
+
+/// Renders the condensation of the workspace graph, with each cycle as a single node.
+fn render_condensation(condensation: &Condensation) -> String {
+    let mut out = String::from("digraph workspace {\n    node [shape=box];\n");
+
+    for (i, component) in condensation.components.iter().enumerate() {
+        let style = if component.cycle.is_some() {
+            format!(", color={CYCLE_COLOR}, fontcolor={CYCLE_COLOR}")
+        } else {
+            String::new()
+        };
+        let _ = writeln!(out, "    c{i} [label={}{style}];", quote(&component.packages.join(", ")));
+    }
+
+    for dependency in &condensation.dependencies {
+        let label = if dependency.count > 1 {
+            format!(" [label=\"{}\"]", dependency.count)
+        } else {
+            String::new()
+        };
+        let _ = writeln!(out, "    c{} -> c{}{label};", dependency.from, dependency.to);
+    }
+
+    out.push('}');
+    out
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
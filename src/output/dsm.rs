@@ -0,0 +1,194 @@
+//! Design Structure Matrix of the workspace crates, as CSV and as an HTML table.
+//!
+//! Rows depend on columns. The crates are partitioned so that dependencies come before their
+//! dependents with the crates of each cycle next to each other, leaving only dependencies within
+//! cycles above the diagonal.
+
+use core::cmp::Reverse;
+use core::fmt::Write;
+use std::collections::BinaryHeap;
+
+use super::escape_xml;
+use crate::condensation;
+use crate::report::{Edge, Report};
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em;color:#222}\
+table{border-collapse:collapse}td,th{border:1px solid #ccc;padding:.2em .4em;text-align:center;min-width:1.2em}\
+th.name{text-align:left}td.diagonal{background:#ddd}td.scc{background:#fdd}td.above{color:#c00;font-weight:bold}\
+code{background:#f4f4f4;padding:0 .2em}";
+
+/// The workspace crates in partitioned order with the dependencies between them.
+struct Matrix<'a> {
+    /// Positions of the crates in [`Report::members`], in the order of the rows and columns.
+    order: Vec<usize>,
+    /// Dependency of the crate of each row on the crate of each column, if any.
+    cells: Vec<Vec<Option<&'a Edge>>>,
+    /// Fingerprint of the cycle of the crate of each row, if any.
+    cycles: Vec<Option<&'a str>>,
+}
+
+/// Renders the matrix as CSV, with a header row of the crate names and the declarations of each
+/// dependency, e.g. `normal` or `normal; dev`, in its cell.
+pub fn render_csv(report: &Report) -> String {
+    let matrix = Matrix::new(report);
+    let names: Vec<&str> = matrix.order.iter().map(|&i| report.members[i].name.as_str()).collect();
+
+    let mut out = String::from("crate");
+    for name in &names {
+        let _ = write!(out, ",{}", field(name));
+    }
+    for (name, row) in names.iter().zip(&matrix.cells) {
+        let _ = write!(out, "\n{}", field(name));
+        for cell in row {
+            let _ = write!(out, ",{}", cell.map(|edge| field(&edge.label())).unwrap_or_default());
+        }
+    }
+    out
+}
+
+/// Renders the matrix as a standalone HTML page, shading the blocks of the cycles and highlighting
+/// the dependencies above the diagonal.
+pub fn render_html(report: &Report) -> String {
+    let matrix = Matrix::new(report);
+    let root = escape_xml(report.workspace_root.as_str());
+
+    let mut out = String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    let _ = writeln!(
+        out,
+        "<title>Design Structure Matrix of {root}</title>\n<style>{STYLE}</style>\n</head>\n<body>"
+    );
+    let _ = writeln!(out, "<h1>Design Structure Matrix</h1>\n<p>Workspace: <code>{root}</code></p>");
+    let _ = writeln!(
+        out,
+        "<p>Rows depend on columns. {} of {} dependencies are above the diagonal, in {} cycle(s).</p>",
+        matrix.above_diagonal(),
+        matrix.cells.iter().flatten().flatten().count(),
+        report.cycles.len()
+    );
+
+    out.push_str("<table>\n<tr><th></th>");
+    for (column, &member) in matrix.order.iter().enumerate() {
+        let _ = write!(
+            out,
+            "<th title=\"{}\">{}</th>",
+            escape_xml(&report.members[member].name),
+            column + 1
+        );
+    }
+    out.push_str("</tr>\n");
+
+    for (row, &member) in matrix.order.iter().enumerate() {
+        let name = escape_xml(&report.members[member].name);
+        let _ = write!(out, "<tr><th class=\"name\">{} {name}</th>", row + 1);
+        for (column, cell) in matrix.cells[row].iter().enumerate() {
+            let mut classes = Vec::new();
+            if row == column {
+                classes.push("diagonal");
+            } else if matrix.cycles[row].is_some() && matrix.cycles[row] == matrix.cycles[column] {
+                classes.push("scc");
+            }
+            if cell.is_some() && column > row {
+                classes.push("above");
+            }
+            let class = if classes.is_empty() {
+                String::new()
+            } else {
+                format!(" class=\"{}\"", classes.join(" "))
+            };
+            match cell {
+                Some(edge) => {
+                    let dependency = escape_xml(&report.members[matrix.order[column]].name);
+                    let label = escape_xml(&edge.label());
+                    let _ = write!(out, "<td{class} title=\"{name} depends on {dependency} ({label})\">x</td>");
+                }
+                None => {
+                    let _ = write!(out, "<td{class}></td>");
+                }
+            }
+        }
+        out.push_str("</tr>\n");
+    }
+
+    out.push_str("</table>\n</body>\n</html>");
+    out
+}
+
+impl<'a> Matrix<'a> {
+    fn new(report: &'a Report) -> Self {
+        let order = partition(report);
+        let cells = order
+            .iter()
+            .map(|&row| {
+                let from = &report.members[row].id;
+                order
+                    .iter()
+                    .map(|&column| {
+                        let to = &report.members[column].id;
+                        report.dependencies.iter().find(|e| &e.from.id == from && &e.to.id == to)
+                    })
+                    .collect()
+            })
+            .collect();
+        let cycles = order
+            .iter()
+            .map(|&i| report.cycle_containing(&report.members[i].id).map(|c| c.fingerprint.as_str()))
+            .collect();
+        Self { order, cells, cycles }
+    }
+
+    /// Returns the number of dependencies on crates later in the order.
+    fn above_diagonal(&self) -> usize {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(row, cells)| cells.iter().skip(row + 1).flatten().count())
+            .sum()
+    }
+}
+
+/// Orders the members topologically by the condensation of the graph, dependencies first, with the
+/// members of each cycle sorted by name next to each other and ties broken by the order of the members.
+fn partition(report: &Report) -> Vec<usize> {
+    let condensation = condensation::condense(report);
+    let components: Vec<usize> = report
+        .members
+        .iter()
+        .map(|member| {
+            let cycle = report.cycle_containing(&member.id).map(|c| &c.fingerprint);
+            condensation
+                .components
+                .iter()
+                .position(|c| c.cycle.as_ref() == cycle && c.packages.contains(&member.name))
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let mut pending = vec![0; condensation.components.len()];
+    for dependency in &condensation.dependencies {
+        pending[dependency.from] += 1;
+    }
+    let mut ready: BinaryHeap<Reverse<usize>> = (0..pending.len()).filter(|&c| pending[c] == 0).map(Reverse).collect();
+
+    let mut order = Vec::with_capacity(report.members.len());
+    while let Some(Reverse(component)) = ready.pop() {
+        let mut members: Vec<usize> = (0..components.len()).filter(|&i| components[i] == component).collect();
+        members.sort_by(|&a, &b| report.members[a].name.cmp(&report.members[b].name));
+        order.extend(members);
+        for dependency in condensation.dependencies.iter().filter(|d| d.to == component) {
+            pending[dependency.from] -= 1;
+            if pending[dependency.from] == 0 {
+                ready.push(Reverse(dependency.from));
+            }
+        }
+    }
+    order
+}
+
+/// Quotes a CSV field if it contains a separator, quote or line break.
+fn field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_owned()
+    }
+}
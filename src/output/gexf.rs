@@ -0,0 +1,70 @@
+//! GEXF output, the native format of Gephi.
+
+use core::fmt::Write;
+
+use super::escape_xml;
+use crate::report::Report;
+
+const ATTRIBUTES: &str = r#"    <attributes class="node">
+      <attribute id="version" title="version" type="string"/>
+      <attribute id="path" title="path" type="string"/>
+      <attribute id="scc" title="scc" type="string"/>
+      <attribute id="layer" title="layer" type="string"/>
+    </attributes>
+    <attributes class="edge">
+      <attribute id="kinds" title="kinds" type="string"/>
+      <attribute id="cycle" title="cycle" type="boolean"/>
+    </attributes>
+"#;
+
+/// Renders the workspace dependency graph as GEXF 1.3, with the packages labeled by their names and
+/// their version, manifest path, cycle and layer and the kinds of each dependency as attributes.
+///
+/// The `scc` attribute is the fingerprint of the cycle the package participates in.
+pub fn render(report: &Report) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<gexf xmlns=\"http://gexf.net/1.3\" version=\"1.3\">\n  \
+         <graph defaultedgetype=\"directed\">\n",
+    );
+    out.push_str(ATTRIBUTES);
+
+    out.push_str("    <nodes>\n");
+    for (i, member) in report.members.iter().enumerate() {
+        let _ = writeln!(
+            out,
+            "      <node id=\"n{i}\" label=\"{}\">\n        <attvalues>",
+            escape_xml(&member.name)
+        );
+        let attributes = [
+            ("version", Some(member.version.clone())),
+            ("path", report.relative_manifest_path(&member.id)),
+            ("scc", report.cycle_containing(&member.id).map(|c| c.fingerprint.clone())),
+            ("layer", member.layer.clone()),
+        ];
+        for (id, value) in attributes {
+            if let Some(value) = value {
+                let _ = writeln!(out, "          <attvalue for=\"{id}\" value=\"{}\"/>", escape_xml(&value));
+            }
+        }
+        out.push_str("        </attvalues>\n      </node>\n");
+    }
+    out.push_str("    </nodes>\n    <edges>\n");
+
+    for (i, edge) in report.dependencies.iter().enumerate() {
+        let (Some(from), Some(to)) = (report.member_index(&edge.from.id), report.member_index(&edge.to.id)) else {
+            continue;
+        };
+        let _ = writeln!(
+            out,
+            "      <edge id=\"e{i}\" source=\"n{from}\" target=\"n{to}\">\n        <attvalues>\n          \
+             <attvalue for=\"kinds\" value=\"{}\"/>\n          \
+             <attvalue for=\"cycle\" value=\"{}\"/>\n        \
+             </attvalues>\n      </edge>",
+            escape_xml(&edge.label()),
+            report.is_cycle_edge(edge)
+        );
+    }
+
+    out.push_str("    </edges>\n  </graph>\n</gexf>");
+    out
+}
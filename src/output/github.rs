@@ -0,0 +1,89 @@
+//! GitHub Actions workflow commands, shown as inline annotations on pull requests.
+
+use core::fmt::Write;
+use std::env;
+
+use cargo_metadata::camino::Utf8Path;
+
+use crate::manifest;
+use crate::report::{Edge, Report};
+
+/// Returns whether the tool runs inside a GitHub Actions workflow.
+pub fn is_running_in_actions() -> bool {
+    env::var("GITHUB_ACTIONS").is_ok_and(|v| v == "true")
+}
+
+/// Renders one `::error` (or `::warning`) command per dependency declaration that forms a cycle or
+/// violates a rule, and per cycle of feature definitions. Stale suppressions are always errors.
+pub fn render(report: &Report) -> String {
+    let checkout = env::var("GITHUB_WORKSPACE").ok();
+    let base = checkout.as_deref().map_or(report.workspace_root.as_path(), Utf8Path::new);
+
+    let mut out = String::new();
+    for cycle in report.failing_cycles() {
+        let message = format!("Cyclic dependency detected: {} (cycle {})", cycle.path(), cycle.fingerprint);
+        for edge in &cycle.edges {
+            let Some(file) = file(report, base, edge) else {
+                continue;
+            };
+            command(
+                &mut out,
+                report.severity.as_str(),
+                Some(&file),
+                "Cyclic dependency",
+                &format!("{message} ({} depends on {})", edge.from.name, edge.to.name),
+            );
+        }
+    }
+
+    for cycle in &report.feature_cycles {
+        let file = report
+            .feature_cycle_member(cycle)
+            .map(|member| format!("file={}", escape_property(&manifest::relative_path(&member.manifest_path, base))));
+        command(
+            &mut out,
+            report.severity.as_str(),
+            file.as_deref(),
+            "Cyclic feature definition",
+            &cycle.describe(),
+        );
+    }
+
+    for violation in &report.violations {
+        let title = format!("Rule violation ({})", violation.rule);
+        if violation.edges.is_empty() {
+            command(&mut out, report.severity.as_str(), None, &title, &violation.message);
+        }
+        for edge in &violation.edges {
+            let file = file(report, base, edge);
+            command(&mut out, report.severity.as_str(), file.as_deref(), &title, &violation.message);
+        }
+    }
+
+    for stale in &report.stale_suppressions {
+        let file = stale.baseline_path().map(|path| format!("file={}", escape_property(path)));
+        command(&mut out, "error", file.as_deref(), "Stale suppression", &stale.message());
+    }
+    out
+}
+
+/// Returns the `file` and `line` properties pointing at the declaration of the dependency.
+fn file(report: &Report, base: &Utf8Path, edge: &Edge) -> Option<String> {
+    let member = report.member(&edge.from.id)?;
+    let file = manifest::relative_path(&member.manifest_path, base);
+    let line = edge.line.map(|l| format!(",line={l}")).unwrap_or_default();
+    Some(format!("file={}{line}", escape_property(&file)))
+}
+
+fn command(out: &mut String, level: &str, file: Option<&str>, title: &str, message: &str) {
+    let file = file.map(|f| format!("{f},")).unwrap_or_default();
+    let _ = writeln!(out, "::{level} {file}title={}::{}", escape_property(title), escape_data(message));
+}
+
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
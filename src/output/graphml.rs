@@ -0,0 +1,60 @@
+//! GraphML output, for graph tools such as yEd or Gephi.
+
+use core::fmt::Write;
+
+use super::escape_xml;
+use crate::report::Report;
+
+const KEYS: &str = r#"  <key id="name" for="node" attr.name="name" attr.type="string"/>
+  <key id="version" for="node" attr.name="version" attr.type="string"/>
+  <key id="path" for="node" attr.name="path" attr.type="string"/>
+  <key id="scc" for="node" attr.name="scc" attr.type="string"/>
+  <key id="layer" for="node" attr.name="layer" attr.type="string"/>
+  <key id="kinds" for="edge" attr.name="kinds" attr.type="string"/>
+  <key id="cycle" for="edge" attr.name="cycle" attr.type="boolean"/>
+"#;
+
+/// Renders the workspace dependency graph as GraphML, with the name, version, manifest path, cycle
+/// and layer of each package and the kinds of each dependency as attributes.
+///
+/// The `scc` attribute is the fingerprint of the cycle the package participates in.
+pub fn render(report: &Report) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+    out.push_str(KEYS);
+    out.push_str("  <graph id=\"workspace\" edgedefault=\"directed\">\n");
+
+    for (i, member) in report.members.iter().enumerate() {
+        let _ = writeln!(out, "    <node id=\"n{i}\">");
+        let attributes = [
+            ("name", Some(member.name.clone())),
+            ("version", Some(member.version.clone())),
+            ("path", report.relative_manifest_path(&member.id)),
+            ("scc", report.cycle_containing(&member.id).map(|c| c.fingerprint.clone())),
+            ("layer", member.layer.clone()),
+        ];
+        for (key, value) in attributes {
+            if let Some(value) = value {
+                let _ = writeln!(out, "      <data key=\"{key}\">{}</data>", escape_xml(&value));
+            }
+        }
+        out.push_str("    </node>\n");
+    }
+
+    for (i, edge) in report.dependencies.iter().enumerate() {
+        let (Some(from), Some(to)) = (report.member_index(&edge.from.id), report.member_index(&edge.to.id)) else {
+            continue;
+        };
+        let _ = writeln!(
+            out,
+            "    <edge id=\"e{i}\" source=\"n{from}\" target=\"n{to}\">\n      \
+             <data key=\"kinds\">{}</data>\n      \
+             <data key=\"cycle\">{}</data>\n    \
+             </edge>",
+            escape_xml(&edge.label()),
+            report.is_cycle_edge(edge)
+        );
+    }
+
+    out.push_str("  </graph>\n</graphml>");
+    out
+}
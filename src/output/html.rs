@@ -0,0 +1,172 @@
+//! Self-contained HTML report for sharing with non-CLI users.
+
+use core::fmt::Write;
+
+use super::{escape_xml, svg};
+use crate::report::Report;
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em;color:#222}\
+table{border-collapse:collapse}td,th{border:1px solid #ccc;padding:.3em .6em;text-align:left}\
+.cycle{color:#c00}.ok{color:#080}code{background:#f4f4f4;padding:0 .2em}";
+
+/// Renders the report as a standalone HTML page, including an inline SVG rendering of the
+/// packages participating in cycles.
+pub fn render(report: &Report) -> String {
+    let mut out = String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    let _ = writeln!(
+        out,
+        "<title>Cyclic dependency report for {}</title>",
+        escape_xml(report.workspace_root.as_str())
+    );
+    let _ = writeln!(out, "<style>{STYLE}</style>\n</head>\n<body>");
+    let _ = writeln!(
+        out,
+        "<h1>Cyclic dependency report</h1>\n<p>Workspace: <code>{}</code></p>",
+        escape_xml(report.workspace_root.as_str())
+    );
+
+    if report.has_failures() {
+        let _ = writeln!(
+            out,
+            "<p class=\"cycle\">{} cycle(s) detected among {} workspace crates.</p>",
+            report.failing_cycles().count(),
+            report.members.len()
+        );
+    } else if report.has_cycles() {
+        let _ = writeln!(
+            out,
+            "<p class=\"ok\">No new cyclic dependencies found among {} workspace crates.</p>",
+            report.members.len()
+        );
+    } else {
+        let _ = writeln!(
+            out,
+            "<p class=\"ok\">No cyclic dependencies found among {} workspace crates.</p>",
+            report.members.len()
+        );
+    }
+
+    write_cycles(&mut out, report);
+    write_feature_cycles(&mut out, report);
+    write_violations(&mut out, report);
+    write_stale_suppressions(&mut out, report);
+    write_members(&mut out, report);
+    if report.has_cycles() {
+        out.push_str("<h2>Affected subgraph</h2>\n");
+        out.push_str(&svg::render(report));
+    }
+
+    out.push_str("</body>\n</html>");
+    out
+}
+
+fn write_cycles(out: &mut String, report: &Report) {
+    if !report.has_cycles() {
+        return;
+    }
+    out.push_str("<h2>Cycles</h2>\n<ol>\n");
+    for cycle in report.reported_cycles() {
+        let note = if cycle.suppressed { " (suppressed)" } else { "" };
+        let _ = writeln!(
+            out,
+            "<li><p><code>{}</code>{note}</p>\n<p>Fingerprint: <code>{}</code></p>",
+            escape_xml(&cycle.path()),
+            cycle.fingerprint
+        );
+        let _ = writeln!(
+            out,
+            "<p>Strongly connected component of {} crate(s), edges:</p>\n<ul>",
+            cycle.packages.len()
+        );
+        for edge in &cycle.edges {
+            let _ = writeln!(
+                out,
+                "<li><code>{}</code> &rarr; <code>{}</code></li>",
+                escape_xml(&edge.from.name),
+                escape_xml(&edge.to.name)
+            );
+        }
+        out.push_str("</ul></li>\n");
+    }
+    out.push_str("</ol>\n");
+}
+
+fn write_feature_cycles(out: &mut String, report: &Report) {
+    if report.feature_cycles.is_empty() {
+        return;
+    }
+    out.push_str("<h2>Cyclic feature definitions</h2>\n<ul>\n");
+    for cycle in &report.feature_cycles {
+        let note = if cycle.is_cross_package() { " (across crates)" } else { "" };
+        let _ = writeln!(out, "<li class=\"cycle\"><code>{}</code>{note}</li>", escape_xml(&cycle.path()));
+    }
+    out.push_str("</ul>\n");
+}
+
+fn write_violations(out: &mut String, report: &Report) {
+    if report.violations.is_empty() {
+        return;
+    }
+    out.push_str("<h2>Rule violations</h2>\n<ul>\n");
+    for violation in &report.violations {
+        let _ = writeln!(
+            out,
+            "<li class=\"cycle\"><p><code>{}</code> {}</p>",
+            escape_xml(&violation.rule),
+            escape_xml(&violation.message)
+        );
+        if !violation.edges.is_empty() {
+            out.push_str("<ul>\n");
+            for edge in &violation.edges {
+                let _ = writeln!(
+                    out,
+                    "<li><code>{}</code> &rarr; <code>{}</code> ({}) at <code>{}</code></li>",
+                    escape_xml(&edge.from.name),
+                    escape_xml(&edge.to.name),
+                    escape_xml(&edge.label()),
+                    escape_xml(&report.declaration_location(edge))
+                );
+            }
+            out.push_str("</ul>\n");
+        }
+        out.push_str("</li>\n");
+    }
+    out.push_str("</ul>\n");
+}
+
+fn write_stale_suppressions(out: &mut String, report: &Report) {
+    if report.stale_suppressions.is_empty() {
+        return;
+    }
+    out.push_str("<h2>Stale suppressions</h2>\n<p>Their cycles no longer exist, remove them:</p>\n<ul>\n");
+    for stale in &report.stale_suppressions {
+        let _ = writeln!(
+            out,
+            "<li class=\"cycle\">{} (<code>{}</code>)</li>",
+            escape_xml(&stale.describe()),
+            escape_xml(&stale.source)
+        );
+    }
+    out.push_str("</ul>\n");
+}
+
+fn write_members(out: &mut String, report: &Report) {
+    out.push_str("<h2>Workspace crates</h2>\n<table>\n<tr><th>Crate</th><th>Manifest</th><th>Cycles</th></tr>\n");
+    for member in &report.members {
+        let cycles: Vec<String> = report
+            .reported_cycles()
+            .enumerate()
+            .filter(|(_, c)| c.contains(&member.id))
+            .map(|(i, _)| (i + 1).to_string())
+            .collect();
+        let class = if cycles.is_empty() { "" } else { " class=\"cycle\"" };
+        let _ = writeln!(
+            out,
+            "<tr{class}><td>{}</td><td><code>{}</code></td><td>{}</td></tr>",
+            escape_xml(&member.name),
+            escape_xml(&report.relative_manifest_path(&member.id).unwrap_or_default()),
+            cycles.join(", ")
+        );
+    }
+    out.push_str("</table>\n");
+}
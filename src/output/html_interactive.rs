@@ -0,0 +1,176 @@
+//! Self-contained HTML page with an interactive force-directed rendering of the workspace graph.
+
+use core::fmt::Write;
+
+use anyhow::Result;
+use serde_json::json;
+
+use super::escape_xml;
+use crate::report::Report;
+
+const STYLE: &str = "body{font-family:sans-serif;margin:0;display:flex;height:100vh;color:#222}\
+#side{width:22em;padding:1em;overflow:auto;border-right:1px solid #ccc}#side input{width:100%;box-sizing:border-box}\
+svg{flex:1}line{stroke:#999}line.cycle{stroke:#c00;stroke-width:2}circle{fill:#8ab;stroke:#456}\
+circle.cycle{fill:#f99;stroke:#c00}text{font-size:12px;pointer-events:none}.faded{opacity:.15}\
+.cycle-name{color:#c00;cursor:pointer}code{background:#f4f4f4;padding:0 .2em}";
+
+/// Lays the graph out with a simple force simulation, there is no layout library to rely on.
+const SCRIPT: &str = r#"
+const svg = document.getElementById("graph");
+const ns = "http://www.w3.org/2000/svg";
+const width = svg.clientWidth || 800, height = svg.clientHeight || 600;
+const nodes = GRAPH.nodes.map((n, i) => {
+  const angle = 2 * Math.PI * i / GRAPH.nodes.length;
+  return { ...n, x: width / 2 + 200 * Math.cos(angle), y: height / 2 + 200 * Math.sin(angle), vx: 0, vy: 0 };
+});
+const lines = GRAPH.edges.map(e => {
+  const line = document.createElementNS(ns, "line");
+  line.setAttribute("marker-end", "url(#arrow)");
+  if (e.cycle) line.classList.add("cycle");
+  svg.appendChild(line);
+  return line;
+});
+const groups = nodes.map((n, i) => {
+  const g = document.createElementNS(ns, "g");
+  const circle = document.createElementNS(ns, "circle");
+  circle.setAttribute("r", 8);
+  if (n.scc) circle.classList.add("cycle");
+  const text = document.createElementNS(ns, "text");
+  text.setAttribute("x", 11);
+  text.setAttribute("y", 4);
+  text.textContent = n.name;
+  g.append(circle, text);
+  g.addEventListener("click", () => show(i));
+  svg.appendChild(g);
+  return g;
+});
+
+function tick() {
+  for (const a of nodes) {
+    for (const b of nodes) {
+      if (a === b) continue;
+      const dx = a.x - b.x, dy = a.y - b.y, d2 = Math.max(dx * dx + dy * dy, 1);
+      a.vx += 2000 * dx / d2 / Math.sqrt(d2);
+      a.vy += 2000 * dy / d2 / Math.sqrt(d2);
+    }
+    a.vx += (width / 2 - a.x) * 0.002;
+    a.vy += (height / 2 - a.y) * 0.002;
+  }
+  for (const e of GRAPH.edges) {
+    const a = nodes[e.source], b = nodes[e.target];
+    const dx = b.x - a.x, dy = b.y - a.y, d = Math.max(Math.hypot(dx, dy), 1), f = (d - 120) * 0.01;
+    a.vx += f * dx / d; a.vy += f * dy / d;
+    b.vx -= f * dx / d; b.vy -= f * dy / d;
+  }
+  for (const n of nodes) {
+    n.vx *= 0.8; n.vy *= 0.8;
+    n.x += n.vx; n.y += n.vy;
+  }
+}
+
+function draw() {
+  GRAPH.edges.forEach((e, i) => {
+    const a = nodes[e.source], b = nodes[e.target];
+    const dx = b.x - a.x, dy = b.y - a.y, d = Math.max(Math.hypot(dx, dy), 1);
+    lines[i].setAttribute("x1", a.x);
+    lines[i].setAttribute("y1", a.y);
+    lines[i].setAttribute("x2", b.x - 10 * dx / d);
+    lines[i].setAttribute("y2", b.y - 10 * dy / d);
+  });
+  nodes.forEach((n, i) => groups[i].setAttribute("transform", `translate(${n.x},${n.y})`));
+}
+
+let ticks = 0;
+(function animate() {
+  tick();
+  draw();
+  if (++ticks < 300) requestAnimationFrame(animate);
+})();
+
+function highlight(visible) {
+  groups.forEach((g, i) => g.classList.toggle("faded", !visible(i)));
+  lines.forEach((l, i) => l.classList.toggle("faded", !(visible(GRAPH.edges[i].source) && visible(GRAPH.edges[i].target))));
+}
+
+const esc = s => String(s).replace(/[&<>"]/g, c => ({ "&": "&amp;", "<": "&lt;", ">": "&gt;", '"': "&quot;" })[c]);
+
+function show(i) {
+  const node = nodes[i];
+  const item = (e, other) => `<li>${esc(nodes[other].name)} (${esc(e.kinds)})${e.cycle ? " <span class=cycle-name>cycle</span>" : ""}<br><code>${esc(e.location)}</code></li>`;
+  const dependencies = GRAPH.edges.filter(e => e.source === i).map(e => item(e, e.target)).join("");
+  const dependents = GRAPH.edges.filter(e => e.target === i).map(e => item(e, e.source)).join("");
+  document.getElementById("details").innerHTML = `<h2>${esc(node.name)}</h2><p><code>${esc(node.path)}</code></p>`
+    + (node.scc ? `<p class=cycle-name>In cycle ${node.scc}</p>` : "")
+    + `<h3>Dependencies</h3><ul>${dependencies}</ul><h3>Dependents</h3><ul>${dependents}</ul>`;
+  highlight(j => j === i || GRAPH.edges.some(e => (e.source === i && e.target === j) || (e.target === i && e.source === j)));
+}
+
+document.getElementById("search").addEventListener("input", event => {
+  const text = event.target.value;
+  highlight(i => !text || nodes[i].name.includes(text));
+});
+document.querySelectorAll("[data-scc]").forEach(element => element.addEventListener("click", () => {
+  highlight(i => nodes[i].scc === element.dataset.scc);
+}));
+"#;
+
+/// Renders the workspace graph as a standalone HTML page, with a force-directed layout of the
+/// packages, the cycles highlighted, a search box and the dependencies of a package on click.
+///
+/// # Errors
+///
+/// Fails if the graph can't be serialized.
+pub fn render(report: &Report) -> Result<String> {
+    let nodes: Vec<_> = report
+        .members
+        .iter()
+        .map(|member| {
+            json!({
+                "name": member.name,
+                "path": report.relative_manifest_path(&member.id),
+                "scc": report.cycle_containing(&member.id).map(|c| &c.fingerprint),
+            })
+        })
+        .collect();
+    let edges: Vec<_> = report
+        .dependencies
+        .iter()
+        .filter_map(|edge| {
+            Some(json!({
+                "source": report.member_index(&edge.from.id)?,
+                "target": report.member_index(&edge.to.id)?,
+                "kinds": edge.label(),
+                "cycle": report.is_cycle_edge(edge),
+                "location": report.declaration_location(edge),
+            }))
+        })
+        .collect();
+    // The graph is embedded in a script, which must not be closed by any of its strings
+    let graph = serde_json::to_string(&json!({ "nodes": nodes, "edges": edges }))?.replace("</", "<\\/");
+
+    let mut cycles = String::new();
+    for cycle in &report.cycles {
+        let _ = write!(
+            cycles,
+            "<li class=\"cycle-name\" data-scc=\"{}\">{}</li>",
+            escape_xml(&cycle.fingerprint),
+            escape_xml(&cycle.path())
+        );
+    }
+    let summary = if report.cycles.is_empty() {
+        format!("No cyclic dependencies among {} workspace crates", report.members.len())
+    } else {
+        format!("{} cycle(s) among {} workspace crates", report.cycles.len(), report.members.len())
+    };
+
+    Ok(format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Dependency graph of {root}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n\
+         <div id=\"side\">\n<h1>Dependency graph</h1>\n<p><code>{root}</code></p>\n<p>{summary}</p>\n\
+         <input id=\"search\" type=\"search\" placeholder=\"Search crates\">\n<ul>{cycles}</ul>\n<div id=\"details\"></div>\n</div>\n\
+         <svg id=\"graph\" xmlns=\"http://www.w3.org/2000/svg\"><defs><marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" \
+         markerWidth=\"6\" markerHeight=\"6\" orient=\"auto\"><path d=\"M0,0 L10,5 L0,10 z\" fill=\"#999\"/></marker></defs></svg>\n\
+         <script>\nconst GRAPH = {graph};\n{SCRIPT}</script>\n</body>\n</html>",
+        root = escape_xml(report.workspace_root.as_str()),
+    ))
+}
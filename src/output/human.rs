@@ -0,0 +1,247 @@
+//! Human readable text output.
+
+use core::fmt::Write;
+
+use crate::elementary::COUNT_LIMIT;
+use crate::report::{Cycle, Report, Severity};
+
+/// Number of dependencies listed in the ranking of dependencies by the cycles they appear in.
+const CULPRIT_COUNT: usize = 10;
+
+/// Renders the report as readable text.
+pub fn render(report: &Report) -> String {
+    let mut out = if !report.has_cycles() {
+        String::from("No cyclic dependencies found.\n")
+    } else if !report.has_failures() {
+        String::from("No new cyclic dependencies found.\n")
+    } else {
+        render_failures(report)
+    };
+
+    let suppressed: Vec<String> = report
+        .suppressed_cycles()
+        .map(|c| {
+            let note = if c.dev_only { ", dev-dependencies only" } else { "" };
+            format!("  {} ({}){note}\n", c.path(), c.fingerprint)
+        })
+        .collect();
+    if !suppressed.is_empty() {
+        let _ = write!(out, "Suppressed {} known cycle(s):\n{}", suppressed.len(), suppressed.concat());
+    }
+
+    if !report.feature_cycles.is_empty() {
+        if !out.ends_with("\n\n") {
+            out.push('\n');
+        }
+        let _ = writeln!(
+            out,
+            "{}: Cyclic feature definitions detected!",
+            if report.severity == Severity::Error { "Error" } else { "Warning" }
+        );
+        for cycle in &report.feature_cycles {
+            let note = if cycle.is_cross_package() { " (across crates)" } else { "" };
+            let _ = writeln!(out, "  {}{note}", cycle.path());
+        }
+    }
+
+    if !report.violations.is_empty() {
+        if !out.ends_with("\n\n") {
+            out.push('\n');
+        }
+        let _ = writeln!(
+            out,
+            "{}: Rule violations detected!",
+            if report.severity == Severity::Error { "Error" } else { "Warning" }
+        );
+        for violation in &report.violations {
+            let _ = writeln!(out, "  [{}] {}", violation.rule, violation.message);
+            for edge in &violation.edges {
+                let _ = writeln!(
+                    out,
+                    "    {} -> {} ({}) at {}",
+                    edge.from.name,
+                    edge.to.name,
+                    edge.label(),
+                    report.declaration_location(edge)
+                );
+            }
+        }
+    }
+
+    if !report.near_cycles.is_empty() {
+        if !out.ends_with("\n\n") {
+            out.push('\n');
+        }
+        out.push_str("Adding any of these dependencies would create a cycle:\n");
+        for near in &report.near_cycles {
+            let _ = writeln!(out, "  {} -> {} (existing path: {})", near.from, near.to, near.path.join(" -> "));
+        }
+    }
+
+    if let Some(err) = &report.resolve_error {
+        if !out.ends_with("\n\n") {
+            out.push('\n');
+        }
+        let reason = err.lines().next().unwrap_or_default();
+        let _ = writeln!(
+            out,
+            "Note: The declared dependencies were checked, they couldn't be resolved: {reason}"
+        );
+    }
+
+    if !report.stale_suppressions.is_empty() {
+        out.push_str("\nError: Stale suppressions found, their cycles no longer exist:\n");
+        for stale in &report.stale_suppressions {
+            let _ = writeln!(out, "  {} ({})", stale.describe(), stale.source);
+        }
+    }
+    out
+}
+
+/// Renders the cycles that aren't suppressed, with the summaries of their analyses.
+fn render_failures(report: &Report) -> String {
+    let mut out = if report.severity == Severity::Error && report.exceeds_budget() {
+        String::from("Error: Cyclic dependencies detected!\n\n")
+    } else {
+        String::from("Warning: Cyclic dependencies detected!\n\n")
+    };
+    for (i, cycle) in report.failing_cycles().enumerate() {
+        let _ = writeln!(out, "Cycle {}:", i + 1);
+        render_cycle(&mut out, report, cycle);
+        out.push('\n');
+    }
+    let listed: Vec<&Cycle> = report.failing_cycles().collect();
+    if listed.iter().any(|c| c.elementary_cycle_count.is_some()) {
+        let total: usize = listed.iter().filter_map(|c| c.elementary_cycle_count).sum();
+        let _ = writeln!(
+            out,
+            "Found {} elementary cycle(s) in {} cycle(s).\n",
+            elementary_count(total),
+            listed.len()
+        );
+    }
+    if !report.culprits.is_empty() {
+        out.push_str("Dependencies in the most cycles:\n");
+        for culprit in report.culprits.iter().take(CULPRIT_COUNT) {
+            let _ = writeln!(out, "  {} -> {}: {} cycle(s)", culprit.from.name, culprit.to.name, culprit.cycles);
+        }
+        out.push('\n');
+    }
+    if let Some(max) = report.max_cycles {
+        let _ = writeln!(
+            out,
+            "Found {} cycle(s), the budget allows {max}.\n",
+            report.failing_cycles().count()
+        );
+    }
+    out
+}
+
+/// Renders the details of a cycle that isn't suppressed.
+fn render_cycle(out: &mut String, report: &Report, cycle: &Cycle) {
+    let _ = writeln!(out, "  {}", cycle.path());
+    let _ = writeln!(out, "  dependencies: {}", cycle.annotated_path());
+    if cycle.patch_only {
+        out.push_str("  only exists because of [patch] or [replace] in the workspace manifest\n");
+    }
+    if cycle.shortest_path.len() < cycle.packages.len() {
+        let _ = writeln!(out, "  shortest cycle: {}", chain(&cycle.shortest_path));
+    }
+    // Only worth pointing out when some packages or edges aren't critical
+    if cycle.critical_packages.len() < cycle.packages.len() && !cycle.critical_packages.is_empty() {
+        let _ = writeln!(out, "  critical crates: {}", cycle.critical_packages.join(", "));
+    }
+    if cycle.critical_edges.len() < cycle.edges.len() && !cycle.critical_edges.is_empty() {
+        let edges: Vec<String> = cycle
+            .critical_edges
+            .iter()
+            .map(|e| format!("{} -> {}", e.from.name, e.to.name))
+            .collect();
+        let _ = writeln!(out, "  critical dependencies: {}", edges.join(", "));
+    }
+    for edge in &cycle.edges {
+        for declaration in edge.declarations.iter().filter(|d| !d.enabled_by.is_empty()) {
+            let _ = writeln!(
+                out,
+                "  {} -> {} is enabled by any of the features: {}",
+                edge.from.name,
+                edge.to.name,
+                declaration.enabled_by.join(", ")
+            );
+        }
+    }
+    if let Some(cycles) = &cycle.elementary_cycles {
+        let count = cycle.elementary_cycle_count.unwrap_or(cycles.len());
+        let _ = writeln!(out, "  elementary cycles ({}):", elementary_count(count));
+        for names in cycles {
+            let _ = writeln!(out, "    {}", chain(names));
+        }
+        if count > cycles.len() {
+            let _ = writeln!(out, "    ... and {} more", elementary_count(count - cycles.len()));
+        }
+    } else if let Some(count) = cycle.elementary_cycle_count
+        && count > 1
+    {
+        let _ = writeln!(out, "  elementary cycles: {}", elementary_count(count));
+    }
+    if let Some(cuts) = &cycle.suggested_cuts {
+        out.push_str("  to break the cycle:\n");
+        for edge in cuts {
+            let _ = writeln!(
+                out,
+                "    remove dependency `{}` ({}) from `{}` ({})",
+                edge.to.name,
+                edge.label(),
+                edge.from.name,
+                report.declaration_location(edge)
+            );
+        }
+    }
+    if let Some(extraction) = &cycle.suggested_extraction {
+        let _ = writeln!(
+            out,
+            "  or extract a shared crate:\n    move the parts of {} used by {} into a new crate",
+            quoted(&extraction.crates),
+            quoted(&extraction.dependents)
+        );
+    }
+    if let Some(depth) = report.feature_powerset_depth {
+        match &cycle.required_features {
+            Some(features) if features.is_empty() => out.push_str("  exists without any features\n"),
+            Some(features) => {
+                let _ = writeln!(out, "  requires features: {}", features.join(", "));
+            }
+            None => {
+                let _ = writeln!(out, "  requires more than {depth} feature(s)");
+            }
+        }
+    }
+    let _ = writeln!(out, "  fingerprint: {}", cycle.fingerprint);
+    if let Some(date) = cycle.suppression_expired {
+        let _ = writeln!(out, "  suppression expired on {date}");
+    }
+}
+
+/// Formats a number of elementary cycles, which may have been counted up to the limit only.
+fn elementary_count(count: usize) -> String {
+    if count >= COUNT_LIMIT {
+        format!("at least {count}")
+    } else {
+        count.to_string()
+    }
+}
+
+/// Formats package names as a list of code spans, e.g. `` `a`, `b` ``.
+fn quoted(names: &[String]) -> String {
+    names.iter().map(|name| format!("`{name}`")).collect::<Vec<_>>().join(", ")
+}
+
+/// Formats package names as a chain returning to the first one, e.g. `a -> b -> a`.
+fn chain(names: &[String]) -> String {
+    names
+        .iter()
+        .chain(names.first())
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
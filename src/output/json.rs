@@ -0,0 +1,10 @@
+//! Machine-readable JSON output.
+
+use anyhow::Result;
+
+use crate::report::Report;
+
+/// Renders the report as pretty-printed JSON.
+pub fn render(report: &Report) -> Result<String> {
+    Ok(serde_json::to_string_pretty(report)?)
+}
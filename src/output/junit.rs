@@ -0,0 +1,145 @@
+//! JUnit XML output for CI test report aggregation.
+
+use core::fmt::Write;
+
+use super::escape_xml;
+use crate::report::Report;
+
+const SUITE_NAME: &str = "cyclic-dependencies";
+const RULES_SUITE_NAME: &str = "dependency-rules";
+const FEATURES_SUITE_NAME: &str = "cyclic-features";
+const STALE_SUITE_NAME: &str = "stale-suppressions";
+
+/// Renders the report as a JUnit XML document with one test case per workspace member.
+///
+/// Members participating in a cycle are reported as failures listing their cycles. Rule violations,
+/// cycles of feature definitions and stale suppressions are reported as failing test cases of
+/// separate test suites.
+pub fn render(report: &Report) -> String {
+    let tests = report.members.len();
+    let failures = report
+        .members
+        .iter()
+        .filter(|m| report.failing_cycles().any(|c| c.contains(&m.id)))
+        .count();
+    let violations = report.violations.len() + report.feature_cycles.len() + report.stale_suppressions.len();
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        out,
+        "<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\">",
+        env!("CARGO_PKG_NAME"),
+        tests + violations,
+        failures + violations
+    );
+    let _ = writeln!(out, "  <testsuite name=\"{SUITE_NAME}\" tests=\"{tests}\" failures=\"{failures}\">");
+
+    for member in &report.members {
+        let name = escape_xml(&member.name);
+        let cycles: Vec<String> = report
+            .failing_cycles()
+            .filter(|c| c.contains(&member.id))
+            .map(|c| escape_xml(&format!("{} (cycle {})", c.path(), c.fingerprint)))
+            .collect();
+
+        if cycles.is_empty() {
+            let _ = writeln!(out, "    <testcase name=\"{name}\" classname=\"{SUITE_NAME}\"/>");
+        } else {
+            let _ = writeln!(out, "    <testcase name=\"{name}\" classname=\"{SUITE_NAME}\">");
+            let _ = writeln!(
+                out,
+                "      <failure message=\"{name} is part of a dependency cycle\" type=\"cyclic-dependency\">{}</failure>",
+                cycles.join("\n")
+            );
+            out.push_str("    </testcase>\n");
+        }
+    }
+
+    out.push_str("  </testsuite>\n");
+
+    if !report.violations.is_empty() {
+        write_violations(&mut out, report);
+    }
+    if !report.feature_cycles.is_empty() {
+        write_feature_cycles(&mut out, report);
+    }
+    if !report.stale_suppressions.is_empty() {
+        write_stale_suppressions(&mut out, report);
+    }
+    out.push_str("</testsuites>");
+    out
+}
+
+/// Writes a test suite with one failing test case per rule violation.
+fn write_violations(out: &mut String, report: &Report) {
+    let count = report.violations.len();
+    let _ = writeln!(
+        out,
+        "  <testsuite name=\"{RULES_SUITE_NAME}\" tests=\"{count}\" failures=\"{count}\">"
+    );
+    for violation in &report.violations {
+        let locations: Vec<String> = violation
+            .edges
+            .iter()
+            .map(|e| {
+                escape_xml(&format!(
+                    "{} -> {} ({}) at {}",
+                    e.from.name,
+                    e.to.name,
+                    e.label(),
+                    report.declaration_location(e)
+                ))
+            })
+            .collect();
+        let _ = writeln!(
+            out,
+            "    <testcase name=\"{}: {}\" classname=\"{RULES_SUITE_NAME}\">\n      \
+             <failure message=\"{}\" type=\"{}\">{}</failure>\n    </testcase>",
+            escape_xml(&violation.rule),
+            escape_xml(&violation.packages.join(", ")),
+            escape_xml(&violation.message),
+            escape_xml(&violation.rule),
+            locations.join("\n")
+        );
+    }
+    out.push_str("  </testsuite>\n");
+}
+
+/// Writes a test suite with one failing test case per cycle of feature definitions.
+fn write_feature_cycles(out: &mut String, report: &Report) {
+    let count = report.feature_cycles.len();
+    let _ = writeln!(
+        out,
+        "  <testsuite name=\"{FEATURES_SUITE_NAME}\" tests=\"{count}\" failures=\"{count}\">"
+    );
+    for cycle in &report.feature_cycles {
+        let _ = writeln!(
+            out,
+            "    <testcase name=\"{}\" classname=\"{FEATURES_SUITE_NAME}\">\n      \
+             <failure message=\"{}\" type=\"cyclic-feature\">{}</failure>\n    </testcase>",
+            escape_xml(&cycle.packages.join(", ")),
+            escape_xml(&cycle.describe()),
+            escape_xml(&cycle.path())
+        );
+    }
+    out.push_str("  </testsuite>\n");
+}
+
+/// Writes a test suite with one failing test case per stale suppression.
+fn write_stale_suppressions(out: &mut String, report: &Report) {
+    let count = report.stale_suppressions.len();
+    let _ = writeln!(
+        out,
+        "  <testsuite name=\"{STALE_SUITE_NAME}\" tests=\"{count}\" failures=\"{count}\">"
+    );
+    for stale in &report.stale_suppressions {
+        let _ = writeln!(
+            out,
+            "    <testcase name=\"{}\" classname=\"{STALE_SUITE_NAME}\">\n      \
+             <failure message=\"{}\" type=\"stale-suppression\"/>\n    </testcase>",
+            escape_xml(&format!("{} ({})", stale.describe(), stale.source)),
+            escape_xml(&stale.message())
+        );
+    }
+    out.push_str("  </testsuite>\n");
+}
@@ -0,0 +1,116 @@
+//! Markdown report for pull request comments.
+
+use core::fmt::Write;
+use std::env;
+
+use cargo_metadata::PackageId;
+use cargo_metadata::camino::Utf8Path;
+
+use crate::manifest;
+use crate::report::{Cycle, Report};
+
+/// Marker identifying comments created from this report, so bots can update them in place.
+const MARKER: &str = "<!-- cargo-ensure-no-cyclic-deps -->";
+
+/// Renders a concise Markdown report, starting with a stable HTML comment marker.
+///
+/// Manifest links point at the checked out commit when running in GitHub Actions and are
+/// relative to the workspace root otherwise.
+pub fn render(report: &Report) -> String {
+    let mut out = format!("{MARKER}\n## Cyclic dependency check\n\n");
+
+    let status = if report.is_failure() {
+        ":x: Failed"
+    } else if report.has_failures() {
+        ":warning: Warning"
+    } else {
+        ":white_check_mark: Passed"
+    };
+    out.push_str("| Status | Crates analyzed | Cycles | Suppressed |\n|---|---|---|---|\n");
+    let _ = writeln!(
+        out,
+        "| {status} | {} | {} | {} |",
+        report.members.len(),
+        report.failing_cycles().count(),
+        report.suppressed_cycles().count()
+    );
+
+    if !report.has_cycles() {
+        out.push_str("\nNo cyclic dependencies found.\n");
+    }
+
+    for (i, cycle) in report.failing_cycles().enumerate() {
+        let _ = writeln!(out, "\n### Cycle {} (`{}`)\n", i + 1, cycle.fingerprint);
+        let _ = writeln!(out, "- {}", chain(report, cycle));
+    }
+
+    if report.suppressed_cycles().next().is_some() {
+        out.push_str("\n### Suppressed cycles\n\n");
+        for cycle in report.suppressed_cycles() {
+            let _ = writeln!(out, "- {} (`{}`)", chain(report, cycle), cycle.fingerprint);
+        }
+    }
+
+    if !report.feature_cycles.is_empty() {
+        out.push_str("\n### Cyclic feature definitions\n\n");
+        for cycle in &report.feature_cycles {
+            let note = if cycle.is_cross_package() { " (across crates)" } else { "" };
+            let _ = writeln!(out, "- `{}`{note}", cycle.path());
+        }
+    }
+
+    if !report.stale_suppressions.is_empty() {
+        out.push_str("\n### Stale suppressions\n\nTheir cycles no longer exist, remove them:\n\n");
+        for stale in &report.stale_suppressions {
+            let _ = writeln!(out, "- {} (`{}`)", stale.describe(), stale.source);
+        }
+    }
+
+    if !report.violations.is_empty() {
+        out.push_str("\n### Rule violations\n\n");
+        for violation in &report.violations {
+            let _ = writeln!(out, "- **{}**: {}", violation.rule, violation.message);
+            for edge in &violation.edges {
+                let _ = writeln!(
+                    out,
+                    "  - {} &rarr; {} ({}) at `{}`",
+                    link(report, &edge.from.id, &edge.from.name),
+                    link(report, &edge.to.id, &edge.to.name),
+                    edge.label(),
+                    report.declaration_location(edge)
+                );
+            }
+        }
+    }
+    out
+}
+
+fn chain(report: &Report, cycle: &Cycle) -> String {
+    let links: Vec<String> = cycle
+        .packages
+        .iter()
+        .chain(cycle.packages.first())
+        .map(|p| link(report, &p.id, &p.name))
+        .collect();
+    links.join(" &rarr; ")
+}
+
+fn link(report: &Report, id: &PackageId, name: &str) -> String {
+    let Some(member) = report.member(id) else {
+        return format!("`{name}`");
+    };
+    format!("[`{name}`]({})", manifest_url(&member.manifest_path, &report.workspace_root))
+}
+
+fn manifest_url(manifest_path: &Utf8Path, workspace_root: &Utf8Path) -> String {
+    if let (Ok(server), Ok(repository), Ok(sha), Ok(checkout)) = (
+        env::var("GITHUB_SERVER_URL"),
+        env::var("GITHUB_REPOSITORY"),
+        env::var("GITHUB_SHA"),
+        env::var("GITHUB_WORKSPACE"),
+    ) {
+        let path = manifest::relative_path(manifest_path, Utf8Path::new(&checkout));
+        return format!("{server}/{repository}/blob/{sha}/{path}");
+    }
+    manifest::relative_path(manifest_path, workspace_root)
+}
@@ -0,0 +1,51 @@
+//! Mermaid diagram output, rendered natively by GitHub.
+
+use core::fmt::Write;
+
+use crate::report::Report;
+
+/// Renders the workspace dependency graph as a fenced Mermaid `graph TD` block, highlighting
+/// packages and edges that participate in cycles.
+pub fn render(report: &Report) -> String {
+    let mut out = String::from("```mermaid\ngraph TD\n");
+
+    let mut cycle_nodes = Vec::new();
+    for (i, member) in report.members.iter().enumerate() {
+        let _ = writeln!(out, "    n{i}[\"{}\"]", escape(&member.name));
+        if report.is_in_cycle(&member.id) {
+            cycle_nodes.push(format!("n{i}"));
+        }
+    }
+
+    let mut cycle_links = Vec::new();
+    let mut link = 0;
+    for edge in &report.dependencies {
+        let (Some(from), Some(to)) = (report.member_index(&edge.from.id), report.member_index(&edge.to.id)) else {
+            continue;
+        };
+        let _ = writeln!(out, "    n{from} --> n{to}");
+        if report.is_cycle_edge(edge) {
+            cycle_links.push(link.to_string());
+        }
+        link += 1;
+    }
+
+    if !cycle_nodes.is_empty() {
+        out.push_str("    classDef cycle stroke:#d00,stroke-width:2px,color:#d00\n");
+        let _ = writeln!(out, "    class {} cycle", cycle_nodes.join(","));
+    }
+    if !cycle_links.is_empty() {
+        let _ = writeln!(out, "    linkStyle {} stroke:#d00,stroke-width:2px", cycle_links.join(","));
+    }
+
+    for cycle in &report.cycles {
+        let _ = writeln!(out, "    %% cycle {}: {}", cycle.fingerprint, cycle.path());
+    }
+
+    out.push_str("```");
+    out
+}
+
+fn escape(s: &str) -> String {
+    s.replace('"', "#quot;")
+}
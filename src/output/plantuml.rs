@@ -0,0 +1,46 @@
+//! PlantUML component diagram output.
+
+use core::fmt::Write;
+
+use crate::report::Report;
+
+const CYCLE_COLOR: &str = "red";
+
+/// Renders the workspace dependency graph as a PlantUML component diagram, highlighting packages
+/// and edges that participate in cycles.
+pub fn render(report: &Report) -> String {
+    let mut out = String::from("@startuml\n");
+
+    for (i, member) in report.members.iter().enumerate() {
+        let style = if report.is_in_cycle(&member.id) {
+            format!(" #line:{CYCLE_COLOR};text:{CYCLE_COLOR}")
+        } else {
+            String::new()
+        };
+        let _ = writeln!(out, "component \"{}\" as n{i}{style}", escape(&member.name));
+    }
+
+    for edge in &report.dependencies {
+        let (Some(from), Some(to)) = (report.member_index(&edge.from.id), report.member_index(&edge.to.id)) else {
+            continue;
+        };
+        let arrow = if report.is_cycle_edge(edge) {
+            format!("-[#{CYCLE_COLOR},bold]->")
+        } else {
+            String::from("-->")
+        };
+        let _ = writeln!(out, "n{from} {arrow} n{to}");
+    }
+
+    for cycle in &report.cycles {
+        let _ = writeln!(out, "' cycle {}: {}", cycle.fingerprint, cycle.path());
+    }
+
+    out.push_str("@enduml");
+    out
+}
+
+/// Replaces the double quotes ending the name of a component, PlantUML has no escape for them.
+fn escape(s: &str) -> String {
+    s.replace('"', "'")
+}
@@ -0,0 +1,146 @@
+//! SARIF output for GitHub code scanning.
+
+use anyhow::Result;
+use serde_json::{Value, json};
+
+use crate::report::{Cycle, FeatureCycle, Report, StaleSuppression, Violation};
+
+const RULE_ID: &str = "cyclic-dependency";
+const FEATURE_RULE_ID: &str = "cyclic-feature";
+const STALE_RULE_ID: &str = "stale-suppression";
+
+/// Renders the report as a SARIF 2.1.0 log with one result per cycle, cycle of feature definitions,
+/// rule violation and stale suppression.
+pub fn render(report: &Report) -> Result<String> {
+    let mut results: Vec<Value> = report.failing_cycles().map(|cycle| result(report, cycle)).collect();
+    results.extend(report.feature_cycles.iter().map(|cycle| feature_result(report, cycle)));
+    results.extend(report.violations.iter().map(|violation| violation_result(report, violation)));
+    results.extend(report.stale_suppressions.iter().map(stale_result));
+
+    let mut rules = vec![json!({
+        "id": RULE_ID,
+        "name": "CyclicDependency",
+        "shortDescription": { "text": "Cyclic dependency between workspace crates" },
+        "defaultConfiguration": { "level": "error" },
+    })];
+    if !report.feature_cycles.is_empty() {
+        rules.push(json!({
+            "id": FEATURE_RULE_ID,
+            "name": "CyclicFeature",
+            "shortDescription": { "text": "Features that cyclically enable each other" },
+            "defaultConfiguration": { "level": "error" },
+        }));
+    }
+    if !report.stale_suppressions.is_empty() {
+        rules.push(json!({
+            "id": STALE_RULE_ID,
+            "name": "StaleSuppression",
+            "shortDescription": { "text": "Suppression of a cycle that no longer exists" },
+            "defaultConfiguration": { "level": "error" },
+        }));
+    }
+    let mut violated: Vec<&str> = report.violations.iter().map(|v| v.rule.as_str()).collect();
+    violated.sort_unstable();
+    violated.dedup();
+    rules.extend(violated.into_iter().map(|rule| {
+        json!({
+            "id": rule,
+            "shortDescription": { "text": format!("Dependency rule `{rule}`") },
+            "defaultConfiguration": { "level": "error" },
+        })
+    }));
+
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    });
+
+    Ok(serde_json::to_string_pretty(&log)?)
+}
+
+fn result(report: &Report, cycle: &Cycle) -> Value {
+    let locations: Vec<Value> = cycle
+        .packages
+        .iter()
+        .filter_map(|p| report.relative_manifest_path(&p.id))
+        .map(|uri| {
+            json!({
+                "physicalLocation": {
+                    "artifactLocation": { "uri": uri, "uriBaseId": "%SRCROOT%" },
+                },
+            })
+        })
+        .collect();
+
+    json!({
+        "ruleId": RULE_ID,
+        "level": report.severity.as_str(),
+        "message": { "text": format!("Cyclic dependency detected: {} (cycle {})", cycle.path(), cycle.fingerprint) },
+        "partialFingerprints": { "cycleFingerprint/v1": cycle.fingerprint },
+        "locations": locations,
+    })
+}
+
+fn feature_result(report: &Report, cycle: &FeatureCycle) -> Value {
+    let locations: Vec<Value> = report
+        .feature_cycle_member(cycle)
+        .and_then(|member| report.relative_manifest_path(&member.id))
+        .map(|uri| json!({ "physicalLocation": { "artifactLocation": { "uri": uri, "uriBaseId": "%SRCROOT%" } } }))
+        .into_iter()
+        .collect();
+
+    json!({
+        "ruleId": FEATURE_RULE_ID,
+        "level": report.severity.as_str(),
+        "message": { "text": cycle.describe() },
+        "locations": locations,
+    })
+}
+
+fn stale_result(stale: &StaleSuppression) -> Value {
+    let locations: Vec<Value> = stale
+        .baseline_path()
+        .map(|uri| json!({ "physicalLocation": { "artifactLocation": { "uri": uri } } }))
+        .into_iter()
+        .collect();
+
+    json!({
+        "ruleId": STALE_RULE_ID,
+        "level": "error",
+        "message": { "text": stale.message() },
+        "locations": locations,
+    })
+}
+
+fn violation_result(report: &Report, violation: &Violation) -> Value {
+    let locations: Vec<Value> = violation
+        .edges
+        .iter()
+        .filter_map(|edge| {
+            let uri = report.relative_manifest_path(&edge.from.id)?;
+            let mut location = json!({ "artifactLocation": { "uri": uri, "uriBaseId": "%SRCROOT%" } });
+            if let Some(line) = edge.line {
+                location["region"] = json!({ "startLine": line });
+            }
+            Some(json!({ "physicalLocation": location }))
+        })
+        .collect();
+
+    json!({
+        "ruleId": violation.rule,
+        "level": report.severity.as_str(),
+        "message": { "text": violation.message },
+        "locations": locations,
+    })
+}
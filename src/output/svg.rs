@@ -0,0 +1,89 @@
+//! SVG rendering of the cycles, without depending on Graphviz.
+
+use core::f64::consts::PI;
+use core::fmt::Write;
+
+use super::escape_xml;
+use crate::report::Report;
+
+const NODE_WIDTH: f64 = 140.0;
+const NODE_HEIGHT: f64 = 30.0;
+
+/// Renders the packages participating in cycles as an SVG image, laid out on a circle with their
+/// edges.
+#[expect(clippy::cast_precision_loss, reason = "node counts are far below the precision limit")]
+pub fn render(report: &Report) -> String {
+    let mut out = String::new();
+    let nodes: Vec<_> = report.members.iter().filter(|m| report.is_in_cycle(&m.id)).collect();
+    let radius = f64::max(120.0, nodes.len() as f64 * 35.0);
+    let (cx, cy) = (radius + NODE_WIDTH, NODE_HEIGHT.mul_add(2.0, radius));
+    let position = |i: usize| {
+        let angle = 2.0 * PI * i as f64 / nodes.len() as f64 - PI / 2.0;
+        (cx + radius * angle.cos(), cy + radius * angle.sin())
+    };
+
+    let _ = writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.0}\" height=\"{:.0}\">",
+        2.0 * cx,
+        2.0 * cy
+    );
+    out.push_str(
+        "<defs><marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" markerWidth=\"8\" markerHeight=\"8\" \
+         orient=\"auto-start-reverse\"><path d=\"M0,0 L10,5 L0,10 z\" fill=\"#c00\"/></marker></defs>\n",
+    );
+
+    for edge in report.dependencies.iter().filter(|e| report.is_cycle_edge(e)) {
+        let (Some(from), Some(to)) = (
+            nodes.iter().position(|m| m.id == edge.from.id),
+            nodes.iter().position(|m| m.id == edge.to.id),
+        ) else {
+            continue;
+        };
+        let (x1, y1) = position(from);
+        if from == to {
+            let top = y1 - NODE_HEIGHT / 2.0;
+            let _ = writeln!(
+                out,
+                "<path d=\"M{:.1},{top:.1} C{:.1},{:.1} {:.1},{:.1} {:.1},{top:.1}\" fill=\"none\" stroke=\"#c00\" marker-end=\"url(#arrow)\"/>",
+                x1 - 20.0,
+                x1 - 30.0,
+                top - 40.0,
+                x1 + 30.0,
+                top - 40.0,
+                x1 + 20.0
+            );
+            continue;
+        }
+        let (x2, y2) = position(to);
+        let (dx, dy) = (x2 - x1, y2 - y1);
+        let length = dx.hypot(dy);
+        // Stop the arrow at the border of the target box rather than its center
+        let scale = f64::min(
+            (NODE_WIDTH / 2.0) / dx.abs().max(f64::EPSILON),
+            (NODE_HEIGHT / 2.0) / dy.abs().max(f64::EPSILON),
+        );
+        let cut = scale.min(0.5) * length;
+        let _ = writeln!(
+            out,
+            "<line x1=\"{x1:.1}\" y1=\"{y1:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"#c00\" marker-end=\"url(#arrow)\"/>",
+            (dx / length).mul_add(-cut, x2),
+            (dy / length).mul_add(-cut, y2)
+        );
+    }
+
+    for (i, node) in nodes.iter().enumerate() {
+        let (x, y) = position(i);
+        let _ = writeln!(
+            out,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{NODE_WIDTH}\" height=\"{NODE_HEIGHT}\" rx=\"4\" fill=\"#fee\" stroke=\"#c00\"/>\
+             <text x=\"{x:.1}\" y=\"{:.1}\" text-anchor=\"middle\" font-size=\"13\">{}</text>",
+            x - NODE_WIDTH / 2.0,
+            y - NODE_HEIGHT / 2.0,
+            y + 4.0,
+            escape_xml(&node.name)
+        );
+    }
+    out.push_str("</svg>\n");
+    out
+}
@@ -0,0 +1,12 @@
+//! Machine-readable TOML output.
+
+use anyhow::Result;
+
+use crate::report::Report;
+
+/// Renders the report as TOML, with the same structure as the JSON output.
+///
+/// TOML has no null value, so fields without a value are omitted.
+pub fn render(report: &Report) -> Result<String> {
+    Ok(::toml::to_string_pretty(report)?)
+}
@@ -0,0 +1,72 @@
+//! Tree rendering of the cycles for the terminal, similar to `cargo tree`.
+
+use core::fmt::Write;
+
+use cargo_metadata::PackageId;
+
+use crate::report::{Cycle, Edge, Report};
+
+/// Marks a dependency back on a package of the path leading to it, closing a cycle.
+const BACK_EDGE: &str = "(cycle)";
+/// Marks a package whose dependencies are already shown further up.
+const SHOWN: &str = "(*)";
+
+/// Renders the dependencies within each reported cycle as a tree from its first package.
+pub fn render(report: &Report) -> String {
+    if !report.has_cycles() {
+        return String::from("No cyclic dependencies found.");
+    }
+    let mut out = String::new();
+    for (i, cycle) in report.reported_cycles().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        let _ = writeln!(out, "Cycle {}: {}", cycle.fingerprint, cycle.path());
+        let Some(root) = cycle.packages.first() else {
+            continue;
+        };
+        let _ = writeln!(out, "{}", root.name);
+        let mut tree = Tree {
+            cycle,
+            path: vec![&root.id],
+            shown: vec![&root.id],
+            out: &mut out,
+        };
+        tree.write_dependencies(&root.id, "");
+    }
+    out
+}
+
+struct Tree<'a> {
+    cycle: &'a Cycle,
+    /// Packages from the root to the one whose dependencies are written.
+    path: Vec<&'a PackageId>,
+    /// Packages whose dependencies are written already.
+    shown: Vec<&'a PackageId>,
+    out: &'a mut String,
+}
+
+impl<'a> Tree<'a> {
+    fn write_dependencies(&mut self, id: &PackageId, prefix: &str) {
+        let mut edges: Vec<&'a Edge> = self.cycle.edges.iter().filter(|e| &e.from.id == id).collect();
+        edges.sort_by(|a, b| a.to.name.cmp(&b.to.name));
+        for (i, edge) in edges.iter().enumerate() {
+            let last = i + 1 == edges.len();
+            let (branch, indent) = if last { ("└── ", "    ") } else { ("├── ", "│   ") };
+            let marker = if self.path.contains(&&edge.to.id) {
+                format!(" {BACK_EDGE}")
+            } else if self.shown.contains(&&edge.to.id) {
+                format!(" {SHOWN}")
+            } else {
+                String::new()
+            };
+            let _ = writeln!(self.out, "{prefix}{branch}{} ({}){marker}", edge.to.name, edge.label());
+            if marker.is_empty() {
+                self.path.push(&edge.to.id);
+                self.shown.push(&edge.to.id);
+                self.write_dependencies(&edge.to.id, &format!("{prefix}{indent}"));
+                self.path.pop();
+            }
+        }
+    }
+}
@@ -0,0 +1,10 @@
+//! Machine-readable YAML output.
+
+use anyhow::Result;
+
+use crate::report::Report;
+
+/// Renders the report as YAML, with the same structure as the JSON output.
+pub fn render(report: &Report) -> Result<String> {
+    Ok(serde_yaml_ng::to_string(report)?)
+}
@@ -0,0 +1,89 @@
+//! Evaluation of the dependency graph under combinations of features, like `cargo hack
+//! --feature-powerset`.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use cargo_metadata::{Metadata, PackageId};
+
+use crate::features::{EnabledFeatures, FeatureSelection};
+use crate::graph::{GraphOptions, WorkspaceGraph};
+use crate::report::Report;
+
+/// Records on each cycle of the report the smallest combination of features under which any cycle
+/// between its packages exists, trying combinations of up to `depth` features.
+///
+/// The combinations are evaluated without the `default` features. Cycles that don't exist with up to
+/// `depth` features are left without a combination. The graph is built once with all dependencies,
+/// each combination only selects the optional dependencies it enables.
+///
+/// # Errors
+///
+/// Fails if the features of a combination can't be resolved.
+pub fn apply(metadata: &Metadata, options: &GraphOptions, depth: usize, report: &mut Report) -> Result<()> {
+    report.feature_powerset_depth = Some(depth);
+
+    let features: Vec<String> = metadata
+        .workspace_packages()
+        .into_iter()
+        .flat_map(|p| p.features.keys().map(move |f| format!("{}/{f}", p.name)))
+        .collect();
+    let names: HashMap<&PackageId, &str> = metadata.packages.iter().map(|p| (&p.id, p.name.as_str())).collect();
+    let graph = WorkspaceGraph::new(
+        metadata,
+        &GraphOptions {
+            features: None,
+            ..options.clone()
+        },
+    );
+
+    for size in 0..=depth.min(features.len()) {
+        for combination in combinations(&features, size) {
+            if report.cycles.iter().all(|c| c.required_features.is_some()) {
+                return Ok(());
+            }
+            let selection = FeatureSelection {
+                features: combination.clone(),
+                all_features: false,
+                no_default_features: true,
+            };
+            let enabled = EnabledFeatures::resolve(metadata, &selection)?;
+
+            let cycles = graph.cycles_with(|package, dependency, declaration| {
+                !declaration.optional || {
+                    let key = declaration.rename.as_deref().or_else(|| names.get(dependency).copied());
+                    key.is_some_and(|key| enabled.is_dependency_enabled(package, key))
+                }
+            });
+            for ids in cycles {
+                // Cycles with fewer features are contained in the cycles with all features
+                for cycle in report.cycles.iter_mut().filter(|c| c.required_features.is_none()) {
+                    if ids.iter().all(|id| cycle.contains(id)) {
+                        cycle.required_features = Some(combination.clone());
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns all combinations of `size` items, in lexicographic order of their positions.
+fn combinations(items: &[String], size: usize) -> impl Iterator<Item = Vec<String>> + '_ {
+    let mut indices = Some((0..size).collect::<Vec<usize>>());
+    core::iter::from_fn(move || {
+        let current = indices.take()?;
+        let combination = current.iter().map(|&i| items[i].clone()).collect();
+
+        // Advance the rightmost index that can still move
+        if let Some(pos) = (0..size).rev().find(|&i| current[i] < items.len() - size + i) {
+            let mut next = current;
+            next[pos] += 1;
+            for i in pos + 1..size {
+                next[i] = next[i - 1] + 1;
+            }
+            indices = Some(next);
+        }
+        Some(combination)
+    })
+}
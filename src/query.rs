@@ -0,0 +1,206 @@
+//! Queries of the dependency graph between the workspace members of a report.
+
+use std::collections::VecDeque;
+
+use anyhow::{Context, Result};
+use cargo_metadata::PackageId;
+
+use crate::condensation;
+use crate::report::{Edge, Member, Report};
+
+/// Looks up the analyzed workspace member with the given name.
+///
+/// # Errors
+///
+/// Fails if no analyzed member has the name.
+pub fn member<'a>(report: &'a Report, name: &str) -> Result<&'a Member> {
+    report
+        .members
+        .iter()
+        .find(|m| m.name == name)
+        .with_context(|| format!("`{name}` isn't a member of the workspace at {}", report.workspace_root))
+}
+
+/// Returns a shortest path of dependencies from one member to another, none if the first one
+/// doesn't depend on the second one, even transitively.
+///
+/// For a member and itself, the path is the shortest cycle through the member.
+#[must_use]
+pub fn shortest_path<'a>(report: &'a Report, from: &PackageId, to: &PackageId) -> Option<Vec<&'a Edge>> {
+    shortest_path_avoiding(report, from, to, &[], &[])
+}
+
+/// Returns up to `count` shortest paths of dependencies from one member to another, shortest first.
+///
+/// The paths are found with Yen's algorithm. They differ in at least one dependency and don't visit
+/// a member twice, except for a path from a member back to itself.
+#[must_use]
+pub fn shortest_paths<'a>(report: &'a Report, from: &PackageId, to: &PackageId, count: usize) -> Vec<Vec<&'a Edge>> {
+    let mut paths: Vec<Vec<&Edge>> = shortest_path(report, from, to).into_iter().collect();
+    let mut candidates: Vec<Vec<&Edge>> = Vec::new();
+    while paths.len() < count
+        && let Some(last) = paths.last()
+    {
+        for spur in 0..last.len() {
+            let root = &last[..spur];
+            // Deviate from the paths found so far right after the shared root
+            let edges: Vec<&Edge> = paths
+                .iter()
+                .filter(|p| same_path(&p[..spur.min(p.len())], root))
+                .filter_map(|p| p.get(spur).copied())
+                .collect();
+            let nodes: Vec<&PackageId> = root.iter().map(|e| &e.from.id).filter(|id| *id != to).collect();
+            let Some(rest) = shortest_path_avoiding(report, &last[spur].from.id, to, &edges, &nodes) else {
+                continue;
+            };
+            let path: Vec<&Edge> = root.iter().copied().chain(rest).collect();
+            if !paths.iter().chain(&candidates).any(|p| same_path(p, &path)) {
+                candidates.push(path);
+            }
+        }
+        // The first of the shortest candidates, so equally long paths keep the order they're found in
+        let Some(next) = candidates.iter().enumerate().min_by_key(|(_, p)| p.len()).map(|(idx, _)| idx) else {
+            break;
+        };
+        paths.push(candidates.remove(next));
+    }
+    paths
+}
+
+/// Returns whether the paths consist of the same dependencies.
+fn same_path(a: &[&Edge], b: &[&Edge]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| core::ptr::eq(*a, *b))
+}
+
+/// Returns a shortest path of dependencies that doesn't use the given dependencies or pass
+/// through the given members.
+fn shortest_path_avoiding<'a>(
+    report: &'a Report,
+    from: &PackageId,
+    to: &PackageId,
+    edges: &[&Edge],
+    nodes: &[&PackageId],
+) -> Option<Vec<&'a Edge>> {
+    // Dependency through which each member was first reached
+    let mut reached_by: Vec<&Edge> = Vec::new();
+    let mut queue = VecDeque::from([from]);
+    while let Some(node) = queue.pop_front() {
+        for edge in report.dependencies.iter().filter(|e| &e.from.id == node) {
+            if reached_by.iter().any(|e| e.to.id == edge.to.id)
+                || edges.iter().any(|e| core::ptr::eq(*e, edge))
+                || nodes.contains(&&edge.to.id)
+            {
+                continue;
+            }
+            reached_by.push(edge);
+            if &edge.to.id == to {
+                let mut path = vec![edge];
+                let mut node = &edge.from.id;
+                while node != from
+                    && let Some(previous) = reached_by.iter().find(|e| &e.to.id == node)
+                {
+                    path.push(previous);
+                    node = &previous.from.id;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            queue.push_back(&edge.to.id);
+        }
+    }
+    None
+}
+
+/// Returns the members that depend on a member, even transitively, with the length of the
+/// shortest path of dependencies to it, sorted by that depth and then by name.
+#[must_use]
+pub fn dependents<'a>(report: &'a Report, id: &PackageId) -> Vec<(&'a Member, usize)> {
+    let mut dependents: Vec<(&Member, usize)> = Vec::new();
+    let mut queue = VecDeque::from([(id, 0)]);
+    while let Some((node, depth)) = queue.pop_front() {
+        for edge in report.dependencies.iter().filter(|e| &e.to.id == node) {
+            let dependent = &edge.from.id;
+            if dependent == id || dependents.iter().any(|(m, _)| &m.id == dependent) {
+                continue;
+            }
+            if let Some(member) = report.member(dependent) {
+                dependents.push((member, depth + 1));
+                queue.push_back((dependent, depth + 1));
+            }
+        }
+    }
+    dependents.sort_by(|(a, a_depth), (b, b_depth)| a_depth.cmp(b_depth).then_with(|| a.name.cmp(&b.name)));
+    dependents
+}
+
+/// Returns the members ordered so each one comes after the members it depends on, none if they
+/// cyclically depend on each other.
+///
+/// Among members whose dependencies all come before them, the alphabetically first one comes first.
+/// Dependencies of a member on itself are ignored.
+#[must_use]
+pub fn dependency_order(report: &Report) -> Option<Vec<&Member>> {
+    let edges: Vec<(usize, usize)> = report
+        .dependencies
+        .iter()
+        .filter_map(|e| Some((report.member_index(&e.from.id)?, report.member_index(&e.to.id)?)))
+        .filter(|(from, to)| from != to)
+        .collect();
+    let mut ordered: Vec<&Member> = Vec::with_capacity(report.members.len());
+    let mut done = vec![false; report.members.len()];
+    while ordered.len() < report.members.len() {
+        let next = (0..report.members.len())
+            .filter(|&idx| !done[idx] && edges.iter().all(|&(from, to)| from != idx || done[to]))
+            .min_by(|&a, &b| report.members[a].name.cmp(&report.members[b].name))?;
+        done[next] = true;
+        ordered.push(&report.members[next]);
+    }
+    Some(ordered)
+}
+
+/// Restricts the report to the given members and the members they depend on, even transitively.
+pub fn retain_dependencies_of(report: &mut Report, ids: &[PackageId]) {
+    let mut retained: Vec<PackageId> = Vec::new();
+    let mut queue: VecDeque<&PackageId> = ids.iter().collect();
+    while let Some(node) = queue.pop_front() {
+        if retained.contains(node) {
+            continue;
+        }
+        retained.push(node.clone());
+        queue.extend(report.dependencies.iter().filter(|e| &e.from.id == node).map(|e| &e.to.id));
+    }
+
+    report.members.retain(|m| retained.contains(&m.id));
+    report
+        .dependencies
+        .retain(|e| retained.contains(&e.from.id) && retained.contains(&e.to.id));
+    report.cycles.retain(|c| c.packages.iter().all(|p| retained.contains(&p.id)));
+}
+
+/// Restricts the report to the members and dependencies participating in cycles, including crates
+/// depending on themselves.
+pub fn retain_cycles(report: &mut Report) {
+    let cycles = core::mem::take(&mut report.cycles);
+    report.members.retain(|m| cycles.iter().any(|c| c.contains(&m.id)));
+    report.dependencies.retain(|e| cycles.iter().any(|c| c.contains_edge(e)));
+    report.cycles = cycles;
+    if report.condensation.is_some() {
+        report.condensation = Some(condensation::condense(report));
+    }
+}
+
+/// Formats a path of dependencies with their kinds, e.g. `a --(normal)--> b --(dev)--> c`.
+#[must_use]
+pub fn annotated_path(path: &[&Edge]) -> String {
+    let Some(first) = path.first() else {
+        return String::new();
+    };
+    let mut out = first.from.name.clone();
+    for edge in path {
+        out.push_str(" --(");
+        out.push_str(&edge.label());
+        out.push_str(")--> ");
+        out.push_str(&edge.to.name);
+    }
+    out
+}
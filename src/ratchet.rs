@@ -0,0 +1,152 @@
+//! Ratchet state that lets the number and the sizes of the cycles shrink but never grow.
+//!
+//! The state is a JSON file recording the number of cycles that aren't suppressed and the number of
+//! packages in each of them:
+//!
+//! ```json
+//! {
+//!   "cycles": 2,
+//!   "sizes": [3, 2]
+//! }
+//! ```
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::report::Report;
+
+/// Recorded cycle counts of a previous run.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RatchetState {
+    /// Number of cycles that aren't suppressed.
+    pub cycles: usize,
+    /// Number of packages in each of the cycles, largest first.
+    #[serde(default)]
+    pub sizes: Vec<usize>,
+}
+
+/// Outcome of checking a report against the ratchet state.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// No state was recorded yet, it was created from the report.
+    Created,
+    /// The number of cycles didn't change.
+    Unchanged,
+    /// The number of cycles decreased, the state was updated.
+    Decreased {
+        /// Previously recorded number of cycles.
+        recorded: usize,
+        /// Current number of cycles.
+        current: usize,
+    },
+    /// The number of cycles increased.
+    Increased {
+        /// Previously recorded number of cycles.
+        recorded: usize,
+        /// Current number of cycles.
+        current: usize,
+    },
+    /// The number of cycles didn't increase, but a cycle grew.
+    Grown {
+        /// Previously recorded sizes of the cycles, largest first.
+        recorded: Vec<usize>,
+        /// Current sizes of the cycles, largest first.
+        current: Vec<usize>,
+    },
+    /// The number of cycles didn't change, but a cycle shrank, the state was updated.
+    Shrunk {
+        /// Previously recorded sizes of the cycles, largest first.
+        recorded: Vec<usize>,
+        /// Current sizes of the cycles, largest first.
+        current: Vec<usize>,
+    },
+}
+
+impl RatchetState {
+    /// Records the cycles of the report that aren't suppressed.
+    #[must_use]
+    pub fn from_report(report: &Report) -> Self {
+        let mut sizes: Vec<usize> = report.failing_cycles().map(|c| c.packages.len()).collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        Self {
+            cycles: sizes.len(),
+            sizes,
+        }
+    }
+
+    /// Loads a state file.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be read or isn't a valid state.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path).with_context(|| format!("Failed to read ratchet state {}", path.display()))?;
+        serde_json::from_str(&contents).with_context(|| format!("Failed to parse ratchet state {}", path.display()))
+    }
+
+    /// Writes the state to a file.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be written.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let contents = serde_json::to_string_pretty(self)? + "\n";
+        fs::write(path, contents).with_context(|| format!("Failed to write ratchet state {}", path.display()))
+    }
+}
+
+/// Checks the report against the state in `path`, creating or updating it unless the number of
+/// cycles increased or a cycle grew.
+///
+/// The sizes are compared largest first, a cycle grew if the n-th largest cycle is larger than the
+/// n-th largest recorded one. States that don't record the sizes only compare the number of cycles.
+/// As long as nothing grows, the cycles of the report are marked as suppressed.
+///
+/// # Errors
+///
+/// Fails if the state can't be read or updated.
+pub fn apply(path: &Path, report: &mut Report) -> Result<Outcome> {
+    let current = RatchetState::from_report(report);
+
+    let outcome = if path.exists() {
+        let recorded = RatchetState::load(path)?;
+        if current.cycles > recorded.cycles {
+            return Ok(Outcome::Increased {
+                recorded: recorded.cycles,
+                current: current.cycles,
+            });
+        }
+        if recorded.sizes.len() == recorded.cycles && current.sizes.iter().zip(&recorded.sizes).any(|(c, r)| c > r) {
+            return Ok(Outcome::Grown {
+                recorded: recorded.sizes,
+                current: current.sizes,
+            });
+        }
+        if current.cycles < recorded.cycles {
+            current.save(path)?;
+            Outcome::Decreased {
+                recorded: recorded.cycles,
+                current: current.cycles,
+            }
+        } else if current.sizes == recorded.sizes {
+            Outcome::Unchanged
+        } else {
+            current.save(path)?;
+            Outcome::Shrunk {
+                recorded: recorded.sizes,
+                current: current.sizes,
+            }
+        }
+    } else {
+        current.save(path)?;
+        Outcome::Created
+    };
+
+    for cycle in &mut report.cycles {
+        cycle.suppressed = true;
+    }
+    Ok(outcome)
+}
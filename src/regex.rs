@@ -0,0 +1,275 @@
+//! Regular expressions matching crate names, for configuration that names crates by pattern.
+//!
+//! The supported syntax is the common subset of regular expressions: literals, `.`, character
+//! classes such as `[a-z_]` or `[^-]`, the escapes `\d`, `\w` and `\s` and of special characters,
+//! groups, alternation with `|`, the quantifiers `*`, `+`, `?` and `{n}`, `{n,}` or `{n,m}`, and the
+//! anchors `^` and `$`. Like `regex::Regex::is_match`, an expression matches a name if it matches
+//! any part of it unless it is anchored.
+
+use anyhow::{Result, bail};
+
+/// A parsed regular expression.
+#[derive(Debug, Clone)]
+pub struct Regex {
+    root: Alternation,
+}
+
+/// Alternatives, one of which has to match.
+type Alternation = Vec<Vec<Node>>;
+
+#[derive(Debug, Clone)]
+enum Node {
+    Char(char),
+    Any,
+    Class { ranges: Vec<(char, char)>, negated: bool },
+    Start,
+    End,
+    Group(Alternation),
+    Repeat { node: Box<Self>, min: usize, max: Option<usize> },
+}
+
+impl Regex {
+    /// Parses the regular expression.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the expression isn't valid or uses unsupported syntax, e.g. lookarounds.
+    pub fn new(source: &str) -> Result<Self> {
+        let mut parser = Parser {
+            chars: source.chars().collect(),
+            pos: 0,
+        };
+        let root = parser.alternation()?;
+        if let Some(c) = parser.peek() {
+            bail!("unexpected `{c}` at position {}", parser.pos);
+        }
+        Ok(Self { root })
+    }
+
+    /// Returns whether the expression matches any part of the text.
+    pub fn is_match(&self, text: &str) -> bool {
+        let text: Vec<char> = text.chars().collect();
+        (0..=text.len()).any(|start| alternation(&self.root, &text, start, &|_| true))
+    }
+}
+
+/// Recursive descent parser of the expression.
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    fn alternation(&mut self) -> Result<Alternation> {
+        let mut alternatives = vec![self.sequence()?];
+        while self.peek() == Some('|') {
+            self.pos += 1;
+            alternatives.push(self.sequence()?);
+        }
+        Ok(alternatives)
+    }
+
+    fn sequence(&mut self) -> Result<Vec<Node>> {
+        let mut nodes = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let atom = self.atom()?;
+            nodes.push(self.quantified(atom)?);
+        }
+        Ok(nodes)
+    }
+
+    fn atom(&mut self) -> Result<Node> {
+        let Some(c) = self.next() else {
+            bail!("unexpected end of the expression");
+        };
+        Ok(match c {
+            '.' => Node::Any,
+            '^' => Node::Start,
+            '$' => Node::End,
+            '(' => {
+                if self.peek() == Some('?') {
+                    bail!("groups with `(?` aren't supported");
+                }
+                let group = self.alternation()?;
+                if self.next() != Some(')') {
+                    bail!("unclosed group");
+                }
+                Node::Group(group)
+            }
+            '[' => self.class()?,
+            '\\' => self.escape()?,
+            '*' | '+' | '?' | '{' => bail!("`{c}` at position {} doesn't follow anything to repeat", self.pos - 1),
+            ')' => bail!("unopened group"),
+            c => Node::Char(c),
+        })
+    }
+
+    fn escape(&mut self) -> Result<Node> {
+        let Some(c) = self.next() else {
+            bail!("unfinished escape at the end of the expression");
+        };
+        let (ranges, negated) = match c {
+            'd' => (vec![('0', '9')], false),
+            'D' => (vec![('0', '9')], true),
+            'w' => (WORD.to_vec(), false),
+            'W' => (WORD.to_vec(), true),
+            's' => (SPACE.to_vec(), false),
+            'S' => (SPACE.to_vec(), true),
+            c if c.is_ascii_punctuation() => return Ok(Node::Char(c)),
+            c => bail!("unsupported escape `\\{c}`"),
+        };
+        Ok(Node::Class { ranges, negated })
+    }
+
+    fn class(&mut self) -> Result<Node> {
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.pos += 1;
+        }
+        let mut ranges = Vec::new();
+        let mut first = true;
+        loop {
+            let Some(c) = self.next() else {
+                bail!("unclosed character class");
+            };
+            if c == ']' && !first {
+                break;
+            }
+            first = false;
+            let start = match c {
+                '\\' => match self.escape()? {
+                    Node::Char(c) => c,
+                    Node::Class {
+                        ranges: escaped,
+                        negated: false,
+                    } => {
+                        ranges.extend(escaped);
+                        continue;
+                    }
+                    _ => bail!("negated escapes aren't supported in character classes"),
+                },
+                c => c,
+            };
+            let end = if self.peek() == Some('-') && self.chars.get(self.pos + 1).is_some_and(|&c| c != ']') {
+                self.pos += 1;
+                match self.next() {
+                    Some('\\') => match self.escape()? {
+                        Node::Char(c) => c,
+                        _ => bail!("invalid range end in character class"),
+                    },
+                    Some(c) => c,
+                    None => bail!("unclosed character class"),
+                }
+            } else {
+                start
+            };
+            if end < start {
+                bail!("invalid range `{start}-{end}` in character class");
+            }
+            ranges.push((start, end));
+        }
+        Ok(Node::Class { ranges, negated })
+    }
+
+    fn quantified(&mut self, node: Node) -> Result<Node> {
+        let (min, max) = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => {
+                self.pos += 1;
+                let min = self.number()?;
+                let max = if self.peek() == Some(',') {
+                    self.pos += 1;
+                    if self.peek() == Some('}') { None } else { Some(self.number()?) }
+                } else {
+                    Some(min)
+                };
+                if self.peek() != Some('}') {
+                    bail!("unclosed repetition");
+                }
+                if max.is_some_and(|max| max < min) {
+                    bail!("invalid repetition `{{{min},{}}}`", max.unwrap_or_default());
+                }
+                (min, max)
+            }
+            _ => return Ok(node),
+        };
+        self.pos += 1;
+        if matches!(self.peek(), Some('*' | '+' | '?' | '{')) {
+            bail!("repeated quantifiers and lazy quantifiers aren't supported");
+        }
+        Ok(Node::Repeat {
+            node: Box::new(node),
+            min,
+            max,
+        })
+    }
+
+    fn number(&mut self) -> Result<usize> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        let digits: String = self.chars[start..self.pos].iter().collect();
+        digits
+            .parse()
+            .map_err(|_err| anyhow::anyhow!("expected a number at position {start}"))
+    }
+}
+
+/// Characters matched by `\w`.
+const WORD: [(char, char); 4] = [('a', 'z'), ('A', 'Z'), ('0', '9'), ('_', '_')];
+
+/// Characters matched by `\s`.
+const SPACE: [(char, char); 2] = [('\t', '\r'), (' ', ' ')];
+
+/// Matches one of the alternatives at `pos`, calling `rest` with the end of each match until it
+/// accepts one.
+fn alternation(alternatives: &Alternation, text: &[char], pos: usize, rest: &dyn Fn(usize) -> bool) -> bool {
+    alternatives.iter().any(|nodes| sequence(nodes, text, pos, rest))
+}
+
+fn sequence(nodes: &[Node], text: &[char], pos: usize, rest: &dyn Fn(usize) -> bool) -> bool {
+    match nodes.split_first() {
+        None => rest(pos),
+        Some((node, others)) => matches(node, text, pos, &|end| sequence(others, text, end, rest)),
+    }
+}
+
+fn matches(node: &Node, text: &[char], pos: usize, rest: &dyn Fn(usize) -> bool) -> bool {
+    match node {
+        Node::Char(c) => text.get(pos) == Some(c) && rest(pos + 1),
+        Node::Any => pos < text.len() && rest(pos + 1),
+        Node::Class { ranges, negated } => text
+            .get(pos)
+            .is_some_and(|c| ranges.iter().any(|&(start, end)| (start..=end).contains(c)) != *negated && rest(pos + 1)),
+        Node::Start => pos == 0 && rest(pos),
+        Node::End => pos == text.len() && rest(pos),
+        Node::Group(alternatives) => alternation(alternatives, text, pos, rest),
+        Node::Repeat { node, min, max } => repeat(node, *min, *max, 0, text, pos, rest),
+    }
+}
+
+/// Matches the node greedily, at least `min` and at most `max` times, of which `count` already did.
+fn repeat(node: &Node, min: usize, max: Option<usize>, count: usize, text: &[char], pos: usize, rest: &dyn Fn(usize) -> bool) -> bool {
+    // Another repetition must consume something, or the required ones wouldn't end
+    let more = max.is_none_or(|max| count < max)
+        && matches(node, text, pos, &|end| {
+            (end > pos || count < min) && repeat(node, min, max, count + 1, text, end, rest)
+        });
+    more || (count >= min && rest(pos))
+}
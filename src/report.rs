@@ -0,0 +1,620 @@
+//! Structured results of the cycle check.
+
+use cargo_metadata::camino::Utf8PathBuf;
+use cargo_metadata::{Metadata, PackageId};
+use serde::{Deserialize, Serialize};
+
+use crate::baseline;
+use crate::condensation::Condensation;
+use crate::date::Date;
+use crate::extraction::Extraction;
+use crate::features::FeatureNode;
+use crate::graph::{Declaration, WorkspaceGraph, is_skipped};
+use crate::manifest;
+use crate::near_cycles::NearCycle;
+use crate::rules::{self, Diagnostic, Rule, RuleContext};
+
+/// Version of the data model of [`Report`], incremented on incompatible changes to its serialized
+/// form, e.g. removed or renamed fields. New fields don't change the version.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Result of checking a workspace for cyclic dependencies.
+///
+/// This is also the structure of the `json`, `yaml` and `toml` output, which can be deserialized
+/// back into it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Report {
+    /// Version of the data model, see [`SCHEMA_VERSION`].
+    pub schema_version: u32,
+    /// Root directory of the analyzed workspace.
+    pub workspace_root: Utf8PathBuf,
+    /// Workspace members that were analyzed.
+    pub members: Vec<Member>,
+    /// All dependency edges between the analyzed workspace members.
+    pub dependencies: Vec<Edge>,
+    /// Detected cycles, empty if the workspace is acyclic.
+    pub cycles: Vec<Cycle>,
+    /// Fingerprints of the cycles that involve none of the focused packages or none of the packages
+    /// in scope. They are neither reported nor fail the check, but still shape the analyses of the
+    /// graph.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub out_of_focus: Vec<String>,
+    /// Problems found by rules other than the cycle check.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub violations: Vec<Violation>,
+    /// Severity of cycles that aren't suppressed, only errors fail the check.
+    pub severity: Severity,
+    /// Number of cycles that aren't suppressed tolerated before the check fails, none if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_cycles: Option<usize>,
+    /// Maximum number of features combined in feature powerset mode, none if not enabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feature_powerset_depth: Option<usize>,
+    /// Maximum number of elementary cycles listed per cycle, none if they aren't enumerated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elementary_cycles_limit: Option<usize>,
+    /// Dependencies ranked by the number of elementary cycles they appear in, only computed when
+    /// the elementary cycles are enumerated.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub culprits: Vec<Culprit>,
+    /// The workspace graph with each cycle collapsed into a single node, only computed on request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condensation: Option<Condensation>,
+    /// Dependencies that would create a new cycle when added, only computed on request.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub near_cycles: Vec<NearCycle>,
+    /// Cycles of feature definitions, only collected when features are checked.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub feature_cycles: Vec<FeatureCycle>,
+    /// Suppression entries whose cycles no longer exist, only collected in strict mode.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stale_suppressions: Vec<StaleSuppression>,
+    /// Why cargo's resolve graph couldn't be used when it was requested, the declared dependencies
+    /// are checked instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolve_error: Option<String>,
+    /// Names of the workspace members left out of the analysis by `skip = true` in their package
+    /// metadata.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<String>,
+}
+
+/// How cycles that aren't suppressed are treated.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Cycles fail the check.
+    #[default]
+    Error,
+    /// Cycles are reported without failing the check.
+    Warning,
+}
+
+impl Severity {
+    /// Returns the lowercase name of the severity.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+        }
+    }
+}
+
+/// Identifies a workspace package.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageRef {
+    /// Name of the package.
+    pub name: String,
+    /// Unique id of the package as reported by cargo.
+    pub id: PackageId,
+}
+
+/// A workspace member that was analyzed.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Member {
+    /// Name of the package.
+    pub name: String,
+    /// Unique id of the package as reported by cargo.
+    pub id: PackageId,
+    /// Path to the `Cargo.toml` of the package.
+    pub manifest_path: Utf8PathBuf,
+    /// Version of the package.
+    #[serde(default)]
+    pub version: String,
+    /// Name of the configured layer the package is in, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layer: Option<String>,
+}
+
+/// A set of packages that cyclically depend on each other.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Cycle {
+    /// Stable identifier of the cycle, derived from the names of its packages and its edges.
+    pub fingerprint: String,
+    /// Packages participating in the cycle.
+    pub packages: Vec<PackageRef>,
+    /// Dependency edges between the packages of the cycle.
+    pub edges: Vec<Edge>,
+    /// Shortest path through the packages back to where it started, as package names in dependency
+    /// order, a concrete example of the cycle.
+    #[serde(default)]
+    pub shortest_path: Vec<String>,
+    /// Sorted names of the packages without which the remaining packages no longer all cyclically
+    /// depend on each other.
+    #[serde(default)]
+    pub critical_packages: Vec<String>,
+    /// Edges without which the packages no longer all cyclically depend on each other.
+    #[serde(default)]
+    pub critical_edges: Vec<Edge>,
+    /// Whether the cycle only exists because of dev-dependencies.
+    pub dev_only: bool,
+    /// Whether the cycle only exists because of dependencies redirected to workspace packages by
+    /// `[patch]` or `[replace]`.
+    #[serde(default)]
+    pub patch_only: bool,
+    /// Whether the cycle is known and accepted, e.g. listed in a baseline, and thus doesn't fail the check.
+    pub suppressed: bool,
+    /// Smallest combination of features, as `package/feature`, under which the cycle exists, only
+    /// computed in feature powerset mode. Empty if it exists without any features.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required_features: Option<Vec<String>>,
+    /// Expiry date of the suppression that no longer applies to the cycle, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suppression_expired: Option<Date>,
+    /// Individual cycles between the packages, visiting each package at most once, as package names
+    /// in dependency order, shortest first. Only enumerated on request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elementary_cycles: Option<Vec<Vec<String>>>,
+    /// Total number of elementary cycles, of which at most [`Report::elementary_cycles_limit`] are
+    /// listed. Counting stops at [`COUNT_LIMIT`](crate::elementary::COUNT_LIMIT), none if the
+    /// cycles weren't counted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elementary_cycle_count: Option<usize>,
+    /// Small set of the edges whose removal breaks the cycle, only computed on request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggested_cuts: Option<Vec<Edge>>,
+    /// Crates whose parts used within the cycle could move into a new crate to break it, only
+    /// computed on request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggested_extraction: Option<Extraction>,
+}
+
+/// Features that cyclically enable each other.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FeatureCycle {
+    /// Sorted names of the packages whose features participate in the cycle.
+    pub packages: Vec<String>,
+    /// Features of the cycle as `package/feature`, in the order they enable each other.
+    pub features: Vec<String>,
+}
+
+impl FeatureCycle {
+    /// Builds the cycle from the nodes of the feature graph.
+    pub fn new(nodes: &[FeatureNode]) -> Self {
+        let mut packages: Vec<(&str, &PackageId)> = nodes.iter().map(|n| (n.name.as_str(), &n.package)).collect();
+        packages.sort_unstable();
+        packages.dedup();
+        Self {
+            packages: packages.into_iter().map(|(name, _)| name.to_owned()).collect(),
+            features: nodes.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    /// Returns whether the features of several packages participate in the cycle.
+    #[must_use]
+    pub const fn is_cross_package(&self) -> bool {
+        self.packages.len() > 1
+    }
+
+    /// Describes the cycle for the reports, its chain of features noting whether it spans several
+    /// crates.
+    #[must_use]
+    pub fn describe(&self) -> String {
+        let note = if self.is_cross_package() { " (across crates)" } else { "" };
+        format!("Cyclic feature definitions detected: {}{note}", self.path())
+    }
+
+    /// Formats the cycle as a chain of features, e.g. `a/x -> a/y -> a/x`.
+    pub fn path(&self) -> String {
+        self.features
+            .iter()
+            .chain(self.features.first())
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" -> ")
+    }
+}
+
+/// A baseline or allowlist entry that matches none of the detected cycles.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StaleSuppression {
+    /// Where the entry is listed, the path of a baseline or `allowlist`.
+    pub source: String,
+    /// Package names listed in the entry.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub packages: Vec<String>,
+    /// Fingerprint listed in the entry.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+}
+
+/// A problem found by a rule, e.g. a forbidden dependency.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Violation {
+    /// Name of the rule that found the problem.
+    pub rule: String,
+    /// Description of the problem.
+    pub message: String,
+    /// Sorted names of the packages involved.
+    pub packages: Vec<String>,
+    /// Dependency edges causing the problem, if any.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub edges: Vec<Edge>,
+}
+
+impl Violation {
+    /// Creates a violation of the rule caused by the edges, involving their packages.
+    pub fn new(rule: &str, message: impl Into<String>, edges: Vec<Edge>) -> Self {
+        let mut packages: Vec<String> = edges.iter().flat_map(|e| [e.from.name.clone(), e.to.name.clone()]).collect();
+        packages.sort_unstable();
+        packages.dedup();
+        Self {
+            rule: rule.to_owned(),
+            message: message.into(),
+            packages,
+            edges,
+        }
+    }
+}
+
+/// A dependency that appears in elementary cycles.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Culprit {
+    /// The dependent package.
+    pub from: PackageRef,
+    /// The package being depended on.
+    pub to: PackageRef,
+    /// Number of elementary cycles the dependency appears in.
+    pub cycles: usize,
+}
+
+/// A dependency of one workspace package on another.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Edge {
+    /// The dependent package.
+    pub from: PackageRef,
+    /// The package being depended on.
+    pub to: PackageRef,
+    /// Line of the dependency declaration in the manifest of the dependent package, if found.
+    pub line: Option<usize>,
+    /// Line of the dependency in the `[workspace.dependencies]` table of the workspace manifest, if
+    /// the declaration inherits it with `workspace = true`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_line: Option<usize>,
+    /// How the dependency is declared, there can be several declarations e.g. in different sections.
+    pub declarations: Vec<Declaration>,
+}
+
+impl Report {
+    /// Builds the report for the cycles found in the workspace graph.
+    #[must_use]
+    pub fn new(metadata: &Metadata, graph: &WorkspaceGraph) -> Self {
+        Self::with_rules(metadata, graph, &rules::builtin())
+    }
+
+    /// Builds the report for the problems the rules find in the workspace graph.
+    #[must_use]
+    pub fn with_rules(metadata: &Metadata, graph: &WorkspaceGraph, rules: &[Box<dyn Rule>]) -> Self {
+        let context = RuleContext { metadata, graph };
+        let mut cycles = Vec::new();
+        let mut violations = Vec::new();
+        for diagnostic in rules.iter().flat_map(|rule| rule.check(&context)) {
+            match diagnostic {
+                Diagnostic::Cycle(cycle) => cycles.push(*cycle),
+                Diagnostic::Violation(violation) => violations.push(violation),
+            }
+        }
+
+        Self {
+            schema_version: SCHEMA_VERSION,
+            workspace_root: metadata.workspace_root.clone(),
+            members: metadata
+                .workspace_packages()
+                .into_iter()
+                .filter(|p| graph.packages().any(|id| *id == p.id))
+                .map(|p| Member {
+                    name: p.name.clone(),
+                    id: p.id.clone(),
+                    manifest_path: p.manifest_path.clone(),
+                    version: p.version.to_string(),
+                    layer: None,
+                })
+                .collect(),
+            dependencies: graph.edges().into_iter().map(|edge| Edge::new(edge, metadata)).collect(),
+            cycles,
+            out_of_focus: Vec::new(),
+            violations,
+            severity: Severity::default(),
+            max_cycles: None,
+            feature_powerset_depth: None,
+            elementary_cycles_limit: None,
+            culprits: Vec::new(),
+            condensation: None,
+            near_cycles: Vec::new(),
+            feature_cycles: Vec::new(),
+            stale_suppressions: Vec::new(),
+            resolve_error: None,
+            skipped: metadata
+                .workspace_packages()
+                .into_iter()
+                .filter(|p| is_skipped(p))
+                .map(|p| p.name.clone())
+                .collect(),
+        }
+    }
+
+    /// Returns whether any cycles are reported, including suppressed ones.
+    #[must_use]
+    pub fn has_cycles(&self) -> bool {
+        self.reported_cycles().next().is_some()
+    }
+
+    /// Returns whether any cycles that aren't suppressed were detected.
+    #[must_use]
+    pub fn has_failures(&self) -> bool {
+        self.failing_cycles().next().is_some()
+    }
+
+    /// Returns whether there are more cycles that aren't suppressed than [`Report::max_cycles`] allows.
+    #[must_use]
+    pub fn exceeds_budget(&self) -> bool {
+        self.failing_cycles().count() > self.max_cycles.unwrap_or(0)
+    }
+
+    /// Returns whether the check fails, i.e. there are more cycles that aren't suppressed than the
+    /// budget allows, feature cycles or rule violations and their severity is [`Severity::Error`], or
+    /// there are stale suppressions.
+    #[must_use]
+    pub fn is_failure(&self) -> bool {
+        (self.severity == Severity::Error && (self.exceeds_budget() || !self.feature_cycles.is_empty() || !self.violations.is_empty()))
+            || !self.stale_suppressions.is_empty()
+    }
+
+    /// Marks the cycles that only exist because of dev-dependencies as suppressed.
+    pub fn allow_dev_cycles(&mut self) {
+        for cycle in self.cycles.iter_mut().filter(|c| c.dev_only) {
+            cycle.suppressed = true;
+            cycle.suppression_expired = None;
+        }
+    }
+
+    /// Returns the detected cycles except those out of focus.
+    pub fn reported_cycles(&self) -> impl Iterator<Item = &Cycle> {
+        self.cycles.iter().filter(|c| !self.out_of_focus.contains(&c.fingerprint))
+    }
+
+    /// Returns the reported cycles that aren't suppressed.
+    pub fn failing_cycles(&self) -> impl Iterator<Item = &Cycle> {
+        self.reported_cycles().filter(|c| !c.suppressed)
+    }
+
+    /// Returns the reported cycles that are suppressed.
+    pub fn suppressed_cycles(&self) -> impl Iterator<Item = &Cycle> {
+        self.reported_cycles().filter(|c| c.suppressed)
+    }
+
+    /// Returns the position of the member in [`Report::members`].
+    #[must_use]
+    pub fn member_index(&self, id: &PackageId) -> Option<usize> {
+        self.members.iter().position(|m| &m.id == id)
+    }
+
+    /// Returns whether the package participates in any cycle.
+    #[must_use]
+    pub fn is_in_cycle(&self, id: &PackageId) -> bool {
+        self.cycles.iter().any(|c| c.contains(id))
+    }
+
+    /// Returns the first cycle the package participates in, if any.
+    #[must_use]
+    pub fn cycle_containing(&self, id: &PackageId) -> Option<&Cycle> {
+        self.cycles.iter().find(|c| c.contains(id))
+    }
+
+    /// Returns whether the dependency edge is part of any cycle.
+    #[must_use]
+    pub fn is_cycle_edge(&self, edge: &Edge) -> bool {
+        self.cycles.iter().any(|c| c.contains_edge(edge))
+    }
+
+    /// Looks up an analyzed workspace member by its id.
+    #[must_use]
+    pub fn member(&self, id: &PackageId) -> Option<&Member> {
+        self.members.iter().find(|m| &m.id == id)
+    }
+
+    /// Returns the path of the member's manifest relative to the workspace root, using `/` as separator.
+    #[must_use]
+    pub fn relative_manifest_path(&self, id: &PackageId) -> Option<String> {
+        let member = self.member(id)?;
+        Some(manifest::relative_path(&member.manifest_path, &self.workspace_root))
+    }
+
+    /// Returns where the dependency is declared, e.g. `crates/a/Cargo.toml:7`, relative to the
+    /// workspace root.
+    ///
+    /// Dependencies inherited from the workspace also point at the workspace manifest, e.g.
+    /// `crates/a/Cargo.toml:7, inherited from Cargo.toml:12`.
+    #[must_use]
+    pub fn declaration_location(&self, edge: &Edge) -> String {
+        let manifest = self
+            .relative_manifest_path(&edge.from.id)
+            .unwrap_or_else(|| String::from("Cargo.toml"));
+        let location = edge.line.map_or_else(|| manifest.clone(), |line| format!("{manifest}:{line}"));
+        match edge.workspace_line {
+            Some(line) => format!("{location}, inherited from Cargo.toml:{line}"),
+            None => location,
+        }
+    }
+
+    /// Returns the member defining the first feature of the cycle.
+    #[must_use]
+    pub fn feature_cycle_member(&self, cycle: &FeatureCycle) -> Option<&Member> {
+        let (package, _) = cycle.features.first()?.split_once('/')?;
+        self.members.iter().find(|m| m.name == package)
+    }
+
+    /// Looks up a cycle by its fingerprint.
+    #[must_use]
+    pub fn cycle(&self, fingerprint: &str) -> Option<&Cycle> {
+        self.cycles.iter().find(|c| c.fingerprint == fingerprint)
+    }
+}
+
+impl StaleSuppression {
+    /// Returns the path of the baseline listing the entry, none if it is listed in the allowlist of
+    /// the configuration.
+    #[must_use]
+    pub fn baseline_path(&self) -> Option<&str> {
+        (self.source != baseline::ALLOWLIST_SOURCE).then_some(self.source.as_str())
+    }
+
+    /// Describes the entry as a stale suppression, by its fingerprint or its packages and where it
+    /// is listed.
+    #[must_use]
+    pub fn message(&self) -> String {
+        let source = self
+            .baseline_path()
+            .map_or_else(|| String::from("the allowlist"), |path| format!("the baseline {path}"));
+        format!("Stale suppression of {} in {source}, its cycle no longer exists", self.describe())
+    }
+
+    /// Describes the entry by its fingerprint, or its packages if it has none.
+    #[must_use]
+    pub fn describe(&self) -> String {
+        self.fingerprint.as_ref().map_or_else(
+            || format!("packages [{}]", self.packages.join(", ")),
+            |f| format!("fingerprint {f}"),
+        )
+    }
+}
+
+impl Cycle {
+    /// Returns the sorted names of the packages participating in the cycle.
+    #[must_use]
+    pub fn package_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.packages.iter().map(|p| p.name.clone()).collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Returns whether the package participates in the cycle.
+    #[must_use]
+    pub fn contains(&self, id: &PackageId) -> bool {
+        self.packages.iter().any(|p| &p.id == id)
+    }
+
+    /// Returns whether the dependency edge is part of the cycle.
+    #[must_use]
+    pub fn contains_edge(&self, edge: &Edge) -> bool {
+        self.edges.iter().any(|e| e.from.id == edge.from.id && e.to.id == edge.to.id)
+    }
+
+    /// Formats the cycle as a chain of package names, e.g. `a -> b -> a`.
+    #[must_use]
+    pub fn path(&self) -> String {
+        let names: Vec<&str> = self.packages.iter().map(|p| p.name.as_str()).collect();
+
+        names.iter().chain(names.first()).copied().collect::<Vec<_>>().join(" -> ")
+    }
+
+    /// Formats the cycle as a chain of package names with arrows annotated by the dependency
+    /// declarations, e.g. `a --(dev)--> b --(normal, optional)--> a`.
+    #[must_use]
+    pub fn annotated_path(&self) -> String {
+        let Some(first) = self.packages.first() else {
+            return String::new();
+        };
+
+        let mut out = first.name.clone();
+        for (i, from) in self.packages.iter().enumerate() {
+            let to = self.packages.get(i + 1).unwrap_or(first);
+            match self.edges.iter().find(|e| e.from.id == from.id && e.to.id == to.id) {
+                Some(edge) => {
+                    out.push_str(" --(");
+                    out.push_str(&edge.label());
+                    out.push_str(")--> ");
+                }
+                None => out.push_str(" -> "),
+            }
+            out.push_str(&to.name);
+        }
+        out
+    }
+}
+
+impl PackageRef {
+    pub(crate) fn new(id: &PackageId, metadata: &Metadata) -> Self {
+        let name = metadata
+            .packages
+            .iter()
+            .find(|p| &p.id == id)
+            .map_or_else(|| id.to_string(), |p| p.name.clone());
+        Self { name, id: id.clone() }
+    }
+}
+
+impl Edge {
+    /// Describes the declarations of the dependency, e.g. `normal, optional`, `normal; dev, cfg(windows)`
+    /// `build, artifact bin` or `normal, via patch.crates-io`.
+    #[must_use]
+    pub fn label(&self) -> String {
+        self.declarations
+            .iter()
+            .map(|d| {
+                let mut label = d.kind.as_str().to_owned();
+                if d.optional {
+                    label.push_str(", optional");
+                }
+                if let Some(target) = &d.target {
+                    label.push_str(", ");
+                    label.push_str(target);
+                }
+                if let Some(artifact) = &d.artifact {
+                    label.push_str(", artifact ");
+                    label.push_str(artifact);
+                }
+                if let Some(section) = &d.patched {
+                    label.push_str(", via ");
+                    label.push_str(section);
+                }
+                label
+            })
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    pub(crate) fn new((from, to, declarations): (PackageId, PackageId, Vec<Declaration>), metadata: &Metadata) -> Self {
+        let from = PackageRef::new(&from, metadata);
+        let to = PackageRef::new(&to, metadata);
+        // Renamed dependencies are declared under their new name
+        let key = declarations.iter().find_map(|d| d.rename.as_deref()).unwrap_or(&to.name);
+        let declaration = metadata
+            .packages
+            .iter()
+            .find(|p| p.id == from.id)
+            .and_then(|p| manifest::dependency_declaration(&p.manifest_path, key));
+        let workspace_line = declaration
+            .as_ref()
+            .filter(|d| d.inherited)
+            .and_then(|_| manifest::workspace_dependency_line(&metadata.workspace_root.join("Cargo.toml"), key));
+        Self {
+            from,
+            to,
+            line: declaration.map(|d| d.line),
+            workspace_line,
+            declarations,
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Comparison of the declared dependencies with those cargo's resolver activates.
+
+use crate::report::{Cycle, Edge, Report};
+
+/// Differences between the graph of the declared dependencies and the resolved graph.
+#[derive(Debug)]
+pub struct ResolutionDiff<'a> {
+    /// Dependencies that are declared but not activated by cargo's resolver, e.g. optional ones
+    /// that no feature enables.
+    pub declared_only: Vec<&'a Edge>,
+    /// Dependencies that are only activated by cargo's resolver, e.g. enabled by feature
+    /// unification that the declared graph's feature selection doesn't account for.
+    pub resolved_only: Vec<&'a Edge>,
+    /// Cycles only between the declared dependencies.
+    pub declared_only_cycles: Vec<&'a Cycle>,
+    /// Cycles only between the resolved dependencies.
+    pub resolved_only_cycles: Vec<&'a Cycle>,
+}
+
+impl<'a> ResolutionDiff<'a> {
+    /// Compares the report of the declared dependencies with the one of the resolved dependencies
+    /// of the same workspace.
+    #[must_use]
+    pub fn new(declared: &'a Report, resolved: &'a Report) -> Self {
+        let missing = |edges: &'a [Edge], others: &[Edge]| -> Vec<&'a Edge> {
+            edges
+                .iter()
+                .filter(|e| !others.iter().any(|o| o.from.id == e.from.id && o.to.id == e.to.id))
+                .collect()
+        };
+        let missing_cycles = |cycles: &'a [Cycle], others: &[Cycle]| -> Vec<&'a Cycle> {
+            cycles
+                .iter()
+                .filter(|c| !others.iter().any(|o| o.fingerprint == c.fingerprint))
+                .collect()
+        };
+        Self {
+            declared_only: missing(&declared.dependencies, &resolved.dependencies),
+            resolved_only: missing(&resolved.dependencies, &declared.dependencies),
+            declared_only_cycles: missing_cycles(&declared.cycles, &resolved.cycles),
+            resolved_only_cycles: missing_cycles(&resolved.cycles, &declared.cycles),
+        }
+    }
+
+    /// Returns whether both graphs have the same dependencies and cycles.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.declared_only.is_empty()
+            && self.resolved_only.is_empty()
+            && self.declared_only_cycles.is_empty()
+            && self.resolved_only_cycles.is_empty()
+    }
+}
@@ -0,0 +1,116 @@
+//! Structural checks of the workspace graph, the cycle check being the first built-in one.
+
+mod cycles;
+mod deny;
+mod depth;
+mod fan;
+mod groups;
+mod layers;
+mod plugin;
+mod proc_macro;
+mod test_utils;
+mod visibility;
+
+use cargo_metadata::{Metadata, PackageId};
+
+pub use self::cycles::CycleRule;
+pub use self::deny::{DeniedDependency, DenyRule};
+pub use self::depth::DepthRule;
+pub use self::fan::{FanLimit, FanRule};
+pub use self::groups::{Group, GroupRule};
+pub use self::layers::{Layer, LayerRule};
+pub use self::plugin::{DEFAULT_RUNTIME, PluginRule};
+pub use self::proc_macro::ProcMacroRule;
+pub use self::test_utils::TestUtilsRule;
+pub use self::visibility::{Visibility, VisibilityRule};
+use crate::graph::WorkspaceGraph;
+use crate::report::{Cycle, Edge, PackageRef, Violation};
+
+/// A check of the workspace graph.
+///
+/// Rules beyond the built-in ones can be passed to [`Checker::rule`](crate::checker::Checker::rule):
+///
+/// ```no_run
+/// use cargo_ensure_no_cyclic_deps::rules::{Diagnostic, Rule, RuleContext};
+/// use cargo_ensure_no_cyclic_deps::report::Violation;
+///
+/// #[derive(Debug)]
+/// struct NoCoreToCli;
+///
+/// impl Rule for NoCoreToCli {
+///     fn name(&self) -> &'static str {
+///         "no-core-to-cli"
+///     }
+///
+///     fn description(&self) -> &'static str {
+///         "The core crate must not depend on the cli crate"
+///     }
+///
+///     fn check(&self, context: &RuleContext<'_>) -> Vec<Diagnostic> {
+///         context
+///             .edges()
+///             .into_iter()
+///             .filter(|e| e.from.name == "core" && e.to.name == "cli")
+///             .map(|e| Diagnostic::Violation(Violation::new(self.name(), "core depends on cli", vec![e])))
+///             .collect()
+///     }
+/// }
+/// ```
+pub trait Rule: core::fmt::Debug {
+    /// Name identifying the rule in the output, e.g. `cycles`.
+    fn name(&self) -> &'static str;
+
+    /// One-line description of what the rule checks.
+    fn description(&self) -> &'static str;
+
+    /// Checks the workspace graph, returning the problems found.
+    fn check(&self, context: &RuleContext<'_>) -> Vec<Diagnostic>;
+}
+
+/// A problem found by a rule.
+#[derive(Debug)]
+pub enum Diagnostic {
+    /// Packages that cyclically depend on each other.
+    Cycle(Box<Cycle>),
+    /// Any other problem.
+    Violation(Violation),
+}
+
+/// What a rule checks, the dependency graph of the analyzed workspace members.
+#[derive(Debug, Clone, Copy)]
+pub struct RuleContext<'a> {
+    /// Metadata of the workspace.
+    pub metadata: &'a Metadata,
+    /// Dependency graph of the analyzed workspace members.
+    pub graph: &'a WorkspaceGraph,
+}
+
+impl RuleContext<'_> {
+    /// Returns all dependency edges of the graph, with their manifest locations.
+    #[must_use]
+    pub fn edges(&self) -> Vec<Edge> {
+        self.graph.edges().into_iter().map(|edge| Edge::new(edge, self.metadata)).collect()
+    }
+
+    /// Returns the dependency edges between the packages, with their manifest locations.
+    #[must_use]
+    pub fn edges_within(&self, packages: &[PackageId]) -> Vec<Edge> {
+        self.graph
+            .edges_within(packages)
+            .into_iter()
+            .map(|edge| Edge::new(edge, self.metadata))
+            .collect()
+    }
+
+    /// Identifies the package by its name.
+    #[must_use]
+    pub fn package(&self, id: &PackageId) -> PackageRef {
+        PackageRef::new(id, self.metadata)
+    }
+}
+
+/// Returns the rules that are always checked, in the order they run.
+#[must_use]
+pub fn builtin() -> Vec<Box<dyn Rule>> {
+    vec![Box::new(CycleRule)]
+}
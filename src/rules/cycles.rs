@@ -0,0 +1,149 @@
+//! The cycle check.
+
+use std::collections::{HashMap, VecDeque};
+
+use cargo_metadata::PackageId;
+
+use super::{Diagnostic, Rule, RuleContext};
+use crate::fingerprint;
+use crate::graph::{self, DepKind};
+use crate::report::{Cycle, Edge, PackageRef};
+
+/// Detects packages that cyclically depend on each other.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CycleRule;
+
+impl Rule for CycleRule {
+    fn name(&self) -> &'static str {
+        "cycles"
+    }
+
+    fn description(&self) -> &'static str {
+        "Workspace crates must not cyclically depend on each other"
+    }
+
+    fn check(&self, context: &RuleContext<'_>) -> Vec<Diagnostic> {
+        context
+            .graph
+            .cycles()
+            .into_iter()
+            .map(|ids| {
+                let packages: Vec<PackageRef> = ids.iter().map(|id| context.package(id)).collect();
+                let edges: Vec<Edge> = context.edges_within(&ids);
+                let dev_only = !graph::has_cycle(
+                    edges
+                        .iter()
+                        .filter(|e| e.declarations.iter().any(|d| d.kind != DepKind::Dev))
+                        .map(|e| (&e.from.id, &e.to.id)),
+                );
+                let patch_only = !graph::has_cycle(
+                    edges
+                        .iter()
+                        .filter(|e| e.declarations.iter().any(|d| d.patched.is_none()))
+                        .map(|e| (&e.from.id, &e.to.id)),
+                );
+                let shortest_path = shortest_path(&packages, &edges);
+                let (critical_packages, critical_edges) = critical_parts(&packages, &edges);
+                Diagnostic::Cycle(Box::new(Cycle {
+                    fingerprint: fingerprint::cycle_fingerprint(
+                        &packages.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(),
+                        &edges.iter().map(|e| (e.from.name.as_str(), e.to.name.as_str())).collect::<Vec<_>>(),
+                    ),
+                    packages,
+                    edges,
+                    shortest_path,
+                    critical_packages,
+                    critical_edges,
+                    dev_only,
+                    patch_only,
+                    suppressed: false,
+                    required_features: None,
+                    suppression_expired: None,
+                    elementary_cycles: None,
+                    elementary_cycle_count: None,
+                    suggested_cuts: None,
+                    suggested_extraction: None,
+                }))
+            })
+            .collect()
+    }
+}
+
+/// Returns the shortest path from a package of the cycle back to itself, as package names in
+/// dependency order, starting with the alphabetically first package if there are several.
+fn shortest_path(packages: &[PackageRef], edges: &[Edge]) -> Vec<String> {
+    let mut starts: Vec<&PackageRef> = packages.iter().collect();
+    starts.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut shortest: Option<Vec<String>> = None;
+    for start in starts {
+        // Breadth-first search for the closest package depending on the start
+        let mut previous: HashMap<&PackageId, &PackageId> = HashMap::new();
+        let mut queue = VecDeque::from([&start.id]);
+        let mut last = None;
+        while let Some(id) = queue.pop_front() {
+            let successors = edges.iter().filter(|e| &e.from.id == id).map(|e| &e.to.id);
+            for next in successors {
+                if next == &start.id {
+                    last = Some(id);
+                    queue.clear();
+                    break;
+                }
+                if !previous.contains_key(next) {
+                    previous.insert(next, id);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let Some(mut id) = last else {
+            continue;
+        };
+        let mut path = vec![id];
+        while id != &start.id {
+            id = previous[id];
+            path.push(id);
+        }
+        if shortest.as_ref().is_none_or(|s| path.len() < s.len()) {
+            let name = |id: &PackageId| {
+                packages
+                    .iter()
+                    .find(|p| &p.id == id)
+                    .map_or_else(|| id.to_string(), |p| p.name.clone())
+            };
+            shortest = Some(path.into_iter().rev().map(name).collect());
+        }
+    }
+    shortest.unwrap_or_default()
+}
+
+/// Returns the packages and edges of the cycle without which the remaining packages no longer all
+/// cyclically depend on each other.
+fn critical_parts(packages: &[PackageRef], edges: &[Edge]) -> (Vec<String>, Vec<Edge>) {
+    let ids: Vec<&PackageId> = packages.iter().map(|p| &p.id).collect();
+
+    let mut critical_packages: Vec<String> = if ids.len() > 1 {
+        packages
+            .iter()
+            .filter(|removed| {
+                let remaining: Vec<&PackageId> = ids.iter().copied().filter(|id| *id != &removed.id).collect();
+                !graph::is_single_cycle(&remaining, edges.iter().map(|e| (&e.from.id, &e.to.id)))
+            })
+            .map(|p| p.name.clone())
+            .collect()
+    } else {
+        Vec::new()
+    };
+    critical_packages.sort_unstable();
+
+    let critical_edges = edges
+        .iter()
+        .filter(|removed| {
+            let remaining = edges.iter().filter(|e| !core::ptr::eq(*e, *removed));
+            !graph::is_single_cycle(&ids, remaining.map(|e| (&e.from.id, &e.to.id)))
+        })
+        .cloned()
+        .collect();
+
+    (critical_packages, critical_edges)
+}
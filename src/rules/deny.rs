@@ -0,0 +1,75 @@
+//! The check of forbidden dependencies.
+
+use anyhow::{Context, Result};
+use glob::Pattern;
+use serde::Deserialize;
+
+use super::{Diagnostic, Rule, RuleContext};
+use crate::report::Violation;
+
+/// Dependencies between workspace crates that aren't allowed, configured in the `deny` list.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeniedDependency {
+    /// Name or glob pattern of the dependent crates.
+    pub from: String,
+    /// Name or glob pattern of the crates they must not depend on.
+    pub to: String,
+    /// Why the dependency isn't allowed, shown with the violations.
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// Detects dependencies matching one of the denied ones, even if they don't create a cycle.
+#[derive(Debug)]
+pub struct DenyRule {
+    denied: Vec<(DeniedDependency, Pattern, Pattern)>,
+}
+
+impl DenyRule {
+    /// Creates the rule for the denied dependencies.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a crate pattern isn't a valid glob pattern.
+    pub fn new(denied: &[DeniedDependency]) -> Result<Self> {
+        let pattern = |p: &str| Pattern::new(p).with_context(|| format!("Invalid crate pattern `{p}` in `deny`"));
+        let denied = denied
+            .iter()
+            .map(|d| Ok((d.clone(), pattern(&d.from)?, pattern(&d.to)?)))
+            .collect::<Result<_>>()?;
+        Ok(Self { denied })
+    }
+}
+
+impl Rule for DenyRule {
+    fn name(&self) -> &'static str {
+        "deny"
+    }
+
+    fn description(&self) -> &'static str {
+        "Workspace crates must not have the dependencies denied in the configuration"
+    }
+
+    fn check(&self, context: &RuleContext<'_>) -> Vec<Diagnostic> {
+        let matches = |pattern: &Pattern, name: &str| pattern.as_str() == name || pattern.matches(name);
+        context
+            .edges()
+            .into_iter()
+            .filter_map(|edge| {
+                let (denied, ..) = self
+                    .denied
+                    .iter()
+                    .find(|(_, from, to)| matches(from, &edge.from.name) && matches(to, &edge.to.name))?;
+                let mut message = format!(
+                    "`{}` must not depend on `{}`, denied by `{}` -> `{}`",
+                    edge.from.name, edge.to.name, denied.from, denied.to
+                );
+                if let Some(reason) = &denied.reason {
+                    message = format!("{message}: {reason}");
+                }
+                Some(Diagnostic::Violation(Violation::new(self.name(), message, vec![edge])))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,115 @@
+//! The check of how long the dependency chains between workspace crates are.
+
+use std::collections::HashMap;
+
+use cargo_metadata::PackageId;
+
+use super::{Diagnostic, Rule, RuleContext};
+use crate::report::{Edge, Violation};
+
+/// Detects dependency chains between workspace crates with more hops than the maximum depth,
+/// configured with `max-depth`.
+///
+/// Dependencies between the crates of a cycle aren't counted, the cycle check reports those: the
+/// chains are those of the graph with each cycle collapsed into a single crate, so a chain entering
+/// a cycle continues with the dependencies of any of its crates. The longest chain is reported if
+/// it is too long.
+#[derive(Debug)]
+pub struct DepthRule {
+    max_depth: usize,
+}
+
+impl DepthRule {
+    /// Creates the rule for the maximum number of hops of a dependency chain.
+    #[must_use]
+    pub const fn new(max_depth: usize) -> Self {
+        Self { max_depth }
+    }
+}
+
+impl Rule for DepthRule {
+    fn name(&self) -> &'static str {
+        "depth"
+    }
+
+    fn description(&self) -> &'static str {
+        "Dependency chains between workspace crates must not be longer than the maximum depth"
+    }
+
+    fn check(&self, context: &RuleContext<'_>) -> Vec<Diagnostic> {
+        // Each crate of a cycle stands for the cycle, by its first crate
+        let cycles = context.graph.cycles();
+        let component: HashMap<&PackageId, &PackageId> = cycles
+            .iter()
+            .flat_map(|cycle| cycle.iter().map(move |id| (id, &cycle[0])))
+            .collect();
+        let component_of = |id: &PackageId| component.get(id).map_or_else(|| id.clone(), |&c| c.clone());
+        let edges: Vec<Edge> = context
+            .edges()
+            .into_iter()
+            .filter(|e| component_of(&e.from.id) != component_of(&e.to.id))
+            .collect();
+        let ends: Vec<PackageId> = edges.iter().map(|e| component_of(&e.to.id)).collect();
+        let mut dependencies: HashMap<PackageId, Vec<usize>> = HashMap::new();
+        for (i, edge) in edges.iter().enumerate() {
+            dependencies.entry(component_of(&edge.from.id)).or_default().push(i);
+        }
+
+        let mut starts: Vec<&Edge> = edges.iter().collect();
+        starts.sort_by(|a, b| a.from.name.cmp(&b.from.name));
+        let mut longest = HashMap::new();
+        // Of the longest chains, the one from the crate first by name
+        let Some((depth, start)) = starts
+            .into_iter()
+            .map(|e| {
+                let start = component_of(&e.from.id);
+                (longest_chain(&start, &ends, &dependencies, &mut longest).0, start)
+            })
+            .rev()
+            .max_by_key(|(depth, _)| *depth)
+        else {
+            return Vec::new();
+        };
+        if depth <= self.max_depth {
+            return Vec::new();
+        }
+
+        // Follow the longest chain from its first component
+        let mut chain = Vec::new();
+        let mut current = &start;
+        while let Some(&(_, Some(next))) = longest.get(current) {
+            chain.push(edges[next].clone());
+            current = &ends[next];
+        }
+        let (Some(first), Some(last)) = (chain.first(), chain.last()) else {
+            return Vec::new();
+        };
+        let message = format!(
+            "`{}` has a dependency chain of {depth} hops to `{}`, more than the maximum depth of {}",
+            first.from.name, last.to.name, self.max_depth
+        );
+        vec![Diagnostic::Violation(Violation::new(self.name(), message, chain))]
+    }
+}
+
+/// Returns the number of hops of the longest dependency chain from the component and the position
+/// of its first edge, memoized in `longest`. `ends` are the components the edges lead to.
+fn longest_chain(
+    component: &PackageId,
+    ends: &[PackageId],
+    dependencies: &HashMap<PackageId, Vec<usize>>,
+    longest: &mut HashMap<PackageId, (usize, Option<usize>)>,
+) -> (usize, Option<usize>) {
+    if let Some(&chain) = longest.get(component) {
+        return chain;
+    }
+    let mut chain = (0, None);
+    for &i in dependencies.get(component).into_iter().flatten() {
+        let depth = longest_chain(&ends[i], ends, dependencies, longest).0 + 1;
+        if depth > chain.0 {
+            chain = (depth, Some(i));
+        }
+    }
+    longest.insert(component.clone(), chain);
+    chain
+}
@@ -0,0 +1,112 @@
+//! The check of how many direct dependencies and dependents the workspace crates have.
+
+use anyhow::{Context, Result};
+use glob::Pattern;
+use serde::Deserialize;
+
+use super::{Diagnostic, Rule, RuleContext};
+use crate::report::{Edge, Violation};
+
+/// Limits for some crates, configured in the `fan-limits` list, instead of the defaults.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct FanLimit {
+    /// Name or glob pattern of the crates the limits are for.
+    #[serde(rename = "crate")]
+    pub krate: String,
+    /// Maximum number of direct workspace dependencies of the crates.
+    #[serde(default)]
+    pub max_dependencies: Option<usize>,
+    /// Maximum number of direct workspace dependents of the crates.
+    #[serde(default)]
+    pub max_dependents: Option<usize>,
+}
+
+/// Detects crates with more direct workspace dependencies or dependents than allowed.
+///
+/// The limits are `max-dependencies` and `max-dependents`, unless the first entry of `fan-limits`
+/// matching a crate sets them for it. Limits that aren't set don't restrict anything.
+#[derive(Debug)]
+pub struct FanRule {
+    max_dependencies: Option<usize>,
+    max_dependents: Option<usize>,
+    limits: Vec<(Pattern, FanLimit)>,
+}
+
+impl FanRule {
+    /// Creates the rule for the default limits and those of some crates.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a crate pattern isn't a valid glob pattern.
+    pub fn new(max_dependencies: Option<usize>, max_dependents: Option<usize>, limits: &[FanLimit]) -> Result<Self> {
+        let limits = limits
+            .iter()
+            .map(|l| {
+                let pattern = Pattern::new(&l.krate).with_context(|| format!("Invalid crate pattern `{}` in `fan-limits`", l.krate))?;
+                Ok((pattern, l.clone()))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            max_dependencies,
+            max_dependents,
+            limits,
+        })
+    }
+
+    /// Returns the limit of the crate, from the first matching entry setting it or the default.
+    fn limit(&self, name: &str, get: impl Fn(&FanLimit) -> Option<usize>, default: Option<usize>) -> Option<usize> {
+        self.limits
+            .iter()
+            .filter(|(pattern, _)| pattern.as_str() == name || pattern.matches(name))
+            .find_map(|(_, limit)| get(limit))
+            .or(default)
+    }
+}
+
+impl Rule for FanRule {
+    fn name(&self) -> &'static str {
+        "fan"
+    }
+
+    fn description(&self) -> &'static str {
+        "Workspace crates must not have more direct workspace dependencies or dependents than allowed"
+    }
+
+    fn check(&self, context: &RuleContext<'_>) -> Vec<Diagnostic> {
+        let mut edges: Vec<Edge> = context.edges().into_iter().filter(|e| e.from.id != e.to.id).collect();
+        edges.sort_by(|a, b| (&a.from.name, &a.to.name).cmp(&(&b.from.name, &b.to.name)));
+        let mut packages: Vec<_> = context.graph.packages().map(|id| context.package(id)).collect();
+        packages.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut diagnostics = Vec::new();
+        for package in packages {
+            let dependencies: Vec<Edge> = edges.iter().filter(|e| e.from.id == package.id).cloned().collect();
+            let dependents: Vec<Edge> = edges.iter().filter(|e| e.to.id == package.id).cloned().collect();
+            let checks = [
+                (
+                    "dependencies",
+                    dependencies,
+                    self.limit(&package.name, |l| l.max_dependencies, self.max_dependencies),
+                ),
+                (
+                    "dependents",
+                    dependents,
+                    self.limit(&package.name, |l| l.max_dependents, self.max_dependents),
+                ),
+            ];
+            for (what, edges, limit) in checks {
+                let Some(limit) = limit.filter(|&limit| edges.len() > limit) else {
+                    continue;
+                };
+                let message = format!(
+                    "`{}` has {} direct workspace {what}, more than the maximum of {limit}",
+                    package.name,
+                    edges.len()
+                );
+                diagnostics.push(Diagnostic::Violation(Violation::new(self.name(), message, edges)));
+            }
+        }
+        diagnostics
+    }
+}
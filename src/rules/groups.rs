@@ -0,0 +1,141 @@
+//! The check of cycles between groups of workspace crates, such as the directories of teams.
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{Context, Result};
+use cargo_metadata::PackageId;
+use glob::Pattern;
+use petgraph::algo::tarjan_scc;
+use petgraph::graph::DiGraph;
+use serde::Deserialize;
+
+use super::{Diagnostic, Rule, RuleContext};
+use crate::report::{Edge, Violation};
+
+/// A named group of workspace crates, configured in the `groups` list.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Group {
+    /// Name of the group, e.g. `billing`.
+    pub name: String,
+    /// Names or glob patterns of the crates in the group.
+    pub crates: Vec<String>,
+}
+
+/// Detects groups of workspace crates that cyclically depend on each other, even if the crates
+/// themselves don't.
+///
+/// A crate belongs to the first group it matches. With `group-by-directory`, a crate in no group
+/// belongs to the group named after the top-level directory it is in, relative to the workspace
+/// root. Crates in no group aren't checked.
+#[derive(Debug)]
+pub struct GroupRule {
+    groups: Vec<(String, Vec<Pattern>)>,
+    by_directory: bool,
+}
+
+impl GroupRule {
+    /// Creates the rule for the groups, `by_directory` also groups the crates by their top-level
+    /// directory.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a crate pattern isn't a valid glob pattern.
+    pub fn new(groups: &[Group], by_directory: bool) -> Result<Self> {
+        let groups = groups
+            .iter()
+            .map(|group| {
+                let patterns = group
+                    .crates
+                    .iter()
+                    .map(|p| Pattern::new(p).with_context(|| format!("Invalid crate pattern `{p}` in group `{}`", group.name)))
+                    .collect::<Result<_>>()?;
+                Ok((group.name.clone(), patterns))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { groups, by_directory })
+    }
+
+    /// Returns the name of the package's group, none if it isn't in any.
+    fn group_of(&self, context: &RuleContext<'_>, id: &PackageId) -> Option<String> {
+        let package = context.metadata.packages.iter().find(|p| &p.id == id)?;
+        let configured = self
+            .groups
+            .iter()
+            .find(|(_, patterns)| patterns.iter().any(|p| p.as_str() == package.name || p.matches(&package.name)));
+        if let Some((name, _)) = configured {
+            return Some(name.clone());
+        }
+        if !self.by_directory {
+            return None;
+        }
+        // The manifest is in the directory of the package, which must be below the workspace root
+        let relative = package.manifest_path.strip_prefix(&context.metadata.workspace_root).ok()?;
+        let mut components = relative.components();
+        let directory = components.next()?;
+        components.next()?;
+        Some(directory.as_str().to_owned())
+    }
+}
+
+impl Rule for GroupRule {
+    fn name(&self) -> &'static str {
+        "groups"
+    }
+
+    fn description(&self) -> &'static str {
+        "Groups of workspace crates must not cyclically depend on each other"
+    }
+
+    fn check(&self, context: &RuleContext<'_>) -> Vec<Diagnostic> {
+        let groups: HashMap<&PackageId, String> = context
+            .graph
+            .packages()
+            .filter_map(|id| Some((id, self.group_of(context, id)?)))
+            .collect();
+        let mut edges: Vec<(String, String, Edge)> = context
+            .edges()
+            .into_iter()
+            .filter_map(|edge| {
+                let from = groups.get(&edge.from.id)?;
+                let to = groups.get(&edge.to.id)?;
+                (from != to).then(|| (from.clone(), to.clone(), edge))
+            })
+            .collect();
+        edges.sort_by(|a, b| (&a.2.from.name, &a.2.to.name).cmp(&(&b.2.from.name, &b.2.to.name)));
+
+        // The graph of the groups, with the crates of each collapsed into a single node
+        let mut graph = DiGraph::<&str, ()>::new();
+        let mut nodes = BTreeMap::new();
+        for name in groups.values() {
+            nodes.entry(name.as_str()).or_insert_with(|| graph.add_node(name.as_str()));
+        }
+        for (from, to, _) in &edges {
+            graph.update_edge(nodes[from.as_str()], nodes[to.as_str()], ());
+        }
+
+        let mut cycles: Vec<Vec<&str>> = tarjan_scc(&graph)
+            .into_iter()
+            .filter(|scc| scc.len() > 1)
+            .map(|scc| {
+                let mut names: Vec<&str> = scc.into_iter().map(|idx| graph[idx]).collect();
+                names.sort_unstable();
+                names
+            })
+            .collect();
+        cycles.sort();
+        cycles
+            .into_iter()
+            .map(|names| {
+                let cycle_edges: Vec<Edge> = edges
+                    .iter()
+                    .filter(|(from, to, _)| names.contains(&from.as_str()) && names.contains(&to.as_str()))
+                    .map(|(_, _, edge)| edge.clone())
+                    .collect();
+                let list = names.iter().map(|name| format!("`{name}`")).collect::<Vec<_>>().join(", ");
+                let message = format!("The crate groups {list} depend on each other in a cycle");
+                Diagnostic::Violation(Violation::new(self.name(), message, cycle_edges))
+            })
+            .collect()
+    }
+}
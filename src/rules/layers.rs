@@ -0,0 +1,127 @@
+//! The layering check.
+
+use anyhow::{Context, Result};
+use glob::Pattern;
+use serde::Deserialize;
+
+use super::{Diagnostic, Rule, RuleContext};
+use crate::regex::Regex;
+use crate::report::Violation;
+
+/// A named group of workspace crates, configured in the `layers` list.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Layer {
+    /// Name of the layer, e.g. `domain`.
+    pub name: String,
+    /// Names or glob patterns of the crates in the layer, by default those named after the layer,
+    /// see [`LayerRule`].
+    #[serde(default)]
+    pub crates: Vec<String>,
+    /// Regular expression matching the names of further crates in the layer, e.g. `.*-api$`.
+    #[serde(default)]
+    pub regex: Option<String>,
+}
+
+/// Detects crates depending on crates of a higher layer.
+///
+/// The layers are ordered from the lowest to the highest, a crate may only depend on crates of its
+/// own or a lower layer. A crate belongs to the first layer it matches, crates in no layer aren't
+/// restricted. The crates of a layer are matched by names or glob patterns, e.g. `*-api`, and by a
+/// regular expression, e.g. `.*-api$`. A layer with neither is assigned the crates named after it
+/// by convention, for the layer `api` the crate `api` and those ending with `-api` or `_api`. With
+/// strict layering, a crate may only depend on crates of its own layer or the one right below it,
+/// not skip the layers in between.
+#[derive(Debug)]
+pub struct LayerRule {
+    layers: Vec<(String, Vec<Pattern>, Option<Regex>)>,
+    strict: bool,
+}
+
+impl LayerRule {
+    /// Creates the rule for the layers, from the lowest to the highest, `strict` also disallows
+    /// skipping layers.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a crate pattern isn't a valid glob pattern or regular expression.
+    pub fn new(layers: &[Layer], strict: bool) -> Result<Self> {
+        let layers = layers
+            .iter()
+            .map(|layer| {
+                let conventional;
+                let crates = if layer.crates.is_empty() && layer.regex.is_none() {
+                    let name = Pattern::escape(&layer.name);
+                    conventional = [name.clone(), format!("*-{name}"), format!("*_{name}")];
+                    conventional.as_slice()
+                } else {
+                    layer.crates.as_slice()
+                };
+                let patterns = crates
+                    .iter()
+                    .map(|p| Pattern::new(p).with_context(|| format!("Invalid crate pattern `{p}` in layer `{}`", layer.name)))
+                    .collect::<Result<_>>()?;
+                let regex = layer
+                    .regex
+                    .as_deref()
+                    .map(|r| Regex::new(r).with_context(|| format!("Invalid crate regex `{r}` in layer `{}`", layer.name)))
+                    .transpose()?;
+                Ok((layer.name.clone(), patterns, regex))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { layers, strict })
+    }
+
+    /// Returns the name of the crate's layer, none if it isn't in any.
+    #[must_use]
+    pub fn layer(&self, name: &str) -> Option<&str> {
+        self.layer_of(name).map(|i| self.layers[i].0.as_str())
+    }
+
+    /// Returns the position of the crate's layer, from the lowest, none if it isn't in any.
+    fn layer_of(&self, name: &str) -> Option<usize> {
+        self.layers.iter().position(|(_, patterns, regex)| {
+            patterns.iter().any(|p| p.as_str() == name || p.matches(name)) || regex.as_ref().is_some_and(|r| r.is_match(name))
+        })
+    }
+}
+
+impl Rule for LayerRule {
+    fn name(&self) -> &'static str {
+        "layers"
+    }
+
+    fn description(&self) -> &'static str {
+        "Workspace crates must not depend on crates of a higher layer"
+    }
+
+    fn check(&self, context: &RuleContext<'_>) -> Vec<Diagnostic> {
+        context
+            .edges()
+            .into_iter()
+            .filter_map(|edge| {
+                let from = self.layer_of(&edge.from.name)?;
+                let to = self.layer_of(&edge.to.name)?;
+                let message = if to > from {
+                    format!(
+                        "`{}` in layer `{}` depends on `{}` in the higher layer `{}`",
+                        edge.from.name, self.layers[from].0, edge.to.name, self.layers[to].0
+                    )
+                } else if self.strict && to + 1 < from {
+                    let skipped: Vec<String> = self.layers[to + 1..from].iter().map(|(name, ..)| format!("`{name}`")).collect();
+                    format!(
+                        "`{}` in layer `{}` depends on `{}` in layer `{}`, skipping {}",
+                        edge.from.name,
+                        self.layers[from].0,
+                        edge.to.name,
+                        self.layers[to].0,
+                        skipped.join(", ")
+                    )
+                } else {
+                    return None;
+                };
+                Some(Diagnostic::Violation(Violation::new(self.name(), message, vec![edge])))
+            })
+            .collect()
+    }
+}
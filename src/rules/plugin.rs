@@ -0,0 +1,193 @@
+//! Third-party rules compiled to WASM, run by a WASI runtime such as wasmtime.
+
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result, bail};
+use cargo_metadata::PackageId;
+use cargo_metadata::camino::Utf8Path;
+use serde::{Deserialize, Serialize};
+
+use super::{Diagnostic, Rule, RuleContext};
+use crate::report::{Edge, PackageRef, Violation};
+
+/// Command running the plugins by default, the path of the module is appended.
+pub const DEFAULT_RUNTIME: [&str; 2] = ["wasmtime", "run"];
+
+/// Runs a WASM module, configured in the `plugins` list, as a rule.
+///
+/// The module is run by the WASI runtime, with the workspace graph on its standard input, and the
+/// violations it writes to its standard output fail the check like those of the built-in rules. The
+/// runtime isn't embedded, it is an external program that has to be installed, `wasmtime` by
+/// default. A plugin that can't be run, e.g. because the runtime is missing, or whose output isn't
+/// valid is reported as a violation of the `plugin` rule so it doesn't pass silently.
+///
+/// A plugin is a WASI command module. It reads the workspace graph as JSON from its standard input:
+///
+/// ```json
+/// {
+///   "workspace_root": "/path/to/workspace",
+///   "members": [{ "name": "app", "id": "path+file:///path/to/workspace/app#0.1.0" }, ...],
+///   "dependencies": [{ "from": { "name": "app", ... }, "to": { "name": "core", ... }, ... }]
+/// }
+/// ```
+///
+/// and writes the problems it finds as JSON to its standard output, with the dependencies causing
+/// them by the ids of the packages, as packages of several workspaces may share a name. Each of
+/// them has to be one of the `dependencies` of the input:
+///
+/// ```json
+/// {
+///   "violations": [{
+///     "rule": "no-app-to-core",
+///     "message": "app must not depend on core",
+///     "dependencies": [{
+///       "from": "path+file:///path/to/workspace/app#0.1.0",
+///       "to": "path+file:///path/to/workspace/core#0.1.0"
+///     }]
+///   }]
+/// }
+/// ```
+#[derive(Debug)]
+pub struct PluginRule {
+    path: PathBuf,
+    runtime: Vec<String>,
+}
+
+/// What a plugin reads from its standard input.
+#[derive(Serialize)]
+struct Input<'a> {
+    workspace_root: &'a Utf8Path,
+    members: Vec<PackageRef>,
+    dependencies: &'a [Edge],
+}
+
+/// What a plugin writes to its standard output.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Output {
+    #[serde(default)]
+    violations: Vec<PluginViolation>,
+}
+
+/// A problem found by a plugin.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PluginViolation {
+    /// Name of the rule, `plugin` if not given.
+    rule: Option<String>,
+    message: String,
+    #[serde(default)]
+    dependencies: Vec<PluginDependency>,
+}
+
+/// A dependency causing a problem, by the ids of the packages.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PluginDependency {
+    from: PackageId,
+    to: PackageId,
+}
+
+impl PluginRule {
+    /// Creates the rule for the module at `path`, relative to the workspace root, which is run by
+    /// appending its path to the `runtime` command, [`DEFAULT_RUNTIME`] if empty.
+    #[must_use]
+    pub fn new(path: &Path, runtime: &[String]) -> Self {
+        let runtime = if runtime.is_empty() {
+            DEFAULT_RUNTIME.iter().map(|&s| s.to_owned()).collect()
+        } else {
+            runtime.to_vec()
+        };
+        Self {
+            path: path.to_owned(),
+            runtime,
+        }
+    }
+
+    /// Runs the module on the graph, returning the violations it found.
+    fn run(&self, context: &RuleContext<'_>) -> Result<Vec<Violation>> {
+        let root = &context.metadata.workspace_root;
+        let path = root.as_std_path().join(&self.path);
+        let edges = context.edges();
+        let input = serde_json::to_vec(&Input {
+            workspace_root: root,
+            members: context.graph.packages().map(|id| context.package(id)).collect(),
+            dependencies: &edges,
+        })?;
+
+        let Some((program, args)) = self.runtime.split_first() else {
+            bail!("The plugin runtime is empty");
+        };
+        let mut child = Command::new(program)
+            .args(args)
+            .arg(&path)
+            .current_dir(root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|err| match err.kind() {
+                ErrorKind::NotFound => anyhow::anyhow!(
+                    "The WASM runtime `{program}` isn't installed or isn't on the PATH, install it or set `wasm-runtime` in the configuration"
+                ),
+                _ => anyhow::Error::new(err).context(format!("Failed to run `{program}`")),
+            })?;
+        // Written while the output is read, the plugin may start writing before it read everything
+        let mut stdin = child.stdin.take().context("Failed to pass the graph to the plugin")?;
+        let writer = std::thread::spawn(move || stdin.write_all(&input));
+        let output = child.wait_with_output()?;
+        let written = writer.join().ok().context("Failed to pass the graph to the plugin")?;
+        if !output.status.success() {
+            bail!("It exited with {}", output.status);
+        }
+        // A plugin that succeeded without reading all of its input closes the pipe early
+        if let Err(err) = written
+            && err.kind() != ErrorKind::BrokenPipe
+        {
+            return Err(anyhow::Error::new(err).context("Failed to pass the graph to the plugin"));
+        }
+
+        let output: Output = serde_json::from_slice(&output.stdout).context("Its output isn't valid")?;
+        output
+            .violations
+            .into_iter()
+            .map(|violation| {
+                let causes = violation
+                    .dependencies
+                    .iter()
+                    .map(|d| {
+                        edges
+                            .iter()
+                            .find(|e| e.from.id == d.from && e.to.id == d.to)
+                            .cloned()
+                            .with_context(|| format!("It reported the dependency of {} on {}, which isn't in the graph", d.from, d.to))
+                    })
+                    .collect::<Result<_>>()?;
+                let rule = violation.rule.as_deref().unwrap_or_else(|| self.name());
+                Ok(Violation::new(rule, violation.message, causes))
+            })
+            .collect()
+    }
+}
+
+impl Rule for PluginRule {
+    fn name(&self) -> &'static str {
+        "plugin"
+    }
+
+    fn description(&self) -> &'static str {
+        "Workspace crates must pass the rules of the configured WASM plugins"
+    }
+
+    fn check(&self, context: &RuleContext<'_>) -> Vec<Diagnostic> {
+        match self.run(context) {
+            Ok(violations) => violations.into_iter().map(Diagnostic::Violation).collect(),
+            Err(err) => {
+                let message = format!("The plugin {} couldn't be run: {err:#}", self.path.display());
+                vec![Diagnostic::Violation(Violation::new(self.name(), message, Vec::new()))]
+            }
+        }
+    }
+}
@@ -0,0 +1,67 @@
+//! The check of what proc-macro crates depend on.
+
+use anyhow::{Context, Result};
+use cargo_metadata::Package;
+use glob::Pattern;
+
+use super::{Diagnostic, Rule, RuleContext};
+use crate::graph::{self, DepKind};
+use crate::report::Violation;
+
+/// Detects proc-macro crates of the workspace depending on runtime crates, configured in the
+/// `runtime-crates` list.
+///
+/// Every change to a runtime crate rebuilds the macro crate and all the crates using its macros, and
+/// the runtime crate can't use the macros itself without a cycle. Dev-dependencies, e.g. for the
+/// tests of the macros, are fine.
+#[derive(Debug)]
+pub struct ProcMacroRule {
+    runtime_crates: Vec<String>,
+}
+
+impl ProcMacroRule {
+    /// Creates the rule for the names or glob patterns of the runtime crates.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a crate pattern isn't a valid glob pattern.
+    pub fn new(runtime_crates: &[String]) -> Result<Self> {
+        for pattern in runtime_crates {
+            Pattern::new(pattern).with_context(|| format!("Invalid crate pattern `{pattern}` in `runtime-crates`"))?;
+        }
+        Ok(Self {
+            runtime_crates: runtime_crates.to_vec(),
+        })
+    }
+}
+
+impl Rule for ProcMacroRule {
+    fn name(&self) -> &'static str {
+        "proc-macro"
+    }
+
+    fn description(&self) -> &'static str {
+        "Proc-macro crates of the workspace must not depend on runtime crates"
+    }
+
+    fn check(&self, context: &RuleContext<'_>) -> Vec<Diagnostic> {
+        context
+            .edges()
+            .into_iter()
+            .filter(|edge| edge.declarations.iter().any(|d| d.kind != DepKind::Dev))
+            .filter(|edge| graph::matches_any(&self.runtime_crates, &edge.to.name))
+            .filter(|edge| context.metadata.packages.iter().any(|p| p.id == edge.from.id && is_proc_macro(p)))
+            .map(|edge| {
+                let message = format!(
+                    "The proc-macro crate `{}` must not depend on the runtime crate `{}`",
+                    edge.from.name, edge.to.name
+                );
+                Diagnostic::Violation(Violation::new(self.name(), message, vec![edge]))
+            })
+            .collect()
+    }
+}
+
+fn is_proc_macro(package: &Package) -> bool {
+    package.targets.iter().any(|t| t.kind.iter().any(|k| k == "proc-macro"))
+}
@@ -0,0 +1,57 @@
+//! The check of how test utility crates are depended on.
+
+use anyhow::{Context, Result};
+use glob::Pattern;
+
+use super::{Diagnostic, Rule, RuleContext};
+use crate::graph::{self, DepKind};
+use crate::report::Violation;
+
+/// Detects normal and build dependencies on test utility crates, configured in the `test-utils`
+/// list, which would ship them in the builds of the dependents.
+#[derive(Debug)]
+pub struct TestUtilsRule {
+    test_utils: Vec<String>,
+}
+
+impl TestUtilsRule {
+    /// Creates the rule for the names or glob patterns of the test utility crates.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a crate pattern isn't a valid glob pattern.
+    pub fn new(test_utils: &[String]) -> Result<Self> {
+        for pattern in test_utils {
+            Pattern::new(pattern).with_context(|| format!("Invalid crate pattern `{pattern}` in `test-utils`"))?;
+        }
+        Ok(Self {
+            test_utils: test_utils.to_vec(),
+        })
+    }
+}
+
+impl Rule for TestUtilsRule {
+    fn name(&self) -> &'static str {
+        "test-utils"
+    }
+
+    fn description(&self) -> &'static str {
+        "Test utility crates must only be depended on through dev-dependencies"
+    }
+
+    fn check(&self, context: &RuleContext<'_>) -> Vec<Diagnostic> {
+        context
+            .edges()
+            .into_iter()
+            .filter(|edge| edge.from.id != edge.to.id && graph::matches_any(&self.test_utils, &edge.to.name))
+            .filter(|edge| edge.declarations.iter().any(|d| d.kind != DepKind::Dev))
+            .map(|edge| {
+                let message = format!(
+                    "`{}` must only depend on the test utility crate `{}` through dev-dependencies",
+                    edge.from.name, edge.to.name
+                );
+                Diagnostic::Violation(Violation::new(self.name(), message, vec![edge]))
+            })
+            .collect()
+    }
+}
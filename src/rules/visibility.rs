@@ -0,0 +1,109 @@
+//! The check of which crates may depend on a crate.
+
+use anyhow::{Context, Result};
+use cargo_metadata::Package;
+use glob::Pattern;
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{Diagnostic, Rule, RuleContext};
+use crate::graph;
+use crate::report::Violation;
+
+/// Crates that only some crates may depend on, configured in the `visibility` list.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Visibility {
+    /// Name or glob pattern of the restricted crates.
+    #[serde(rename = "crate")]
+    pub krate: String,
+    /// Names or glob patterns of the crates that may depend on them.
+    pub visible_to: Vec<String>,
+}
+
+/// Detects dependencies on crates that the dependent isn't allowed to depend on.
+///
+/// A crate restricts its dependents with `visibility` in the `[package.metadata.ensure-no-cyclic-deps]`
+/// table of its manifest, or with an entry of the `visibility` list in the configuration. The
+/// crates allowed by either may depend on it, crates without restrictions are visible to all.
+#[derive(Debug)]
+pub struct VisibilityRule {
+    visibility: Vec<(Pattern, Visibility)>,
+}
+
+impl VisibilityRule {
+    /// Creates the rule for the configured restrictions, those in the package metadata are read
+    /// when checking.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a crate pattern of the configuration isn't a valid glob pattern.
+    pub fn new(visibility: &[Visibility]) -> Result<Self> {
+        let visibility = visibility
+            .iter()
+            .map(|v| {
+                for pattern in &v.visible_to {
+                    Pattern::new(pattern).with_context(|| format!("Invalid crate pattern `{pattern}` in `visibility`"))?;
+                }
+                let krate = Pattern::new(&v.krate).with_context(|| format!("Invalid crate pattern `{}` in `visibility`", v.krate))?;
+                Ok((krate, v.clone()))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { visibility })
+    }
+
+    /// Returns the names or patterns of the crates that may depend on the package, none if it isn't
+    /// restricted.
+    fn visible_to(&self, package: &Package) -> Option<Vec<String>> {
+        let declared = graph::package_option(package, "visibility")
+            .and_then(Value::as_array)
+            .map(|patterns| patterns.iter().filter_map(Value::as_str).map(String::from).collect::<Vec<_>>());
+        let configured: Vec<&Visibility> = self
+            .visibility
+            .iter()
+            .filter(|(krate, _)| krate.as_str() == package.name || krate.matches(&package.name))
+            .map(|(_, v)| v)
+            .collect();
+        if declared.is_none() && configured.is_empty() {
+            return None;
+        }
+        let mut visible_to = declared.unwrap_or_default();
+        visible_to.extend(configured.into_iter().flat_map(|v| v.visible_to.iter().cloned()));
+        Some(visible_to)
+    }
+}
+
+impl Rule for VisibilityRule {
+    fn name(&self) -> &'static str {
+        "visibility"
+    }
+
+    fn description(&self) -> &'static str {
+        "Workspace crates must only be depended on by the crates they are visible to"
+    }
+
+    fn check(&self, context: &RuleContext<'_>) -> Vec<Diagnostic> {
+        context
+            .edges()
+            .into_iter()
+            .filter(|edge| edge.from.id != edge.to.id)
+            .filter_map(|edge| {
+                let package = context.metadata.packages.iter().find(|p| p.id == edge.to.id)?;
+                let visible_to = self.visible_to(package)?;
+                if graph::matches_any(&visible_to, &edge.from.name) {
+                    return None;
+                }
+                let allowed = if visible_to.is_empty() {
+                    String::from("no other crate")
+                } else {
+                    visible_to.iter().map(|p| format!("`{p}`")).collect::<Vec<_>>().join(", ")
+                };
+                let message = format!(
+                    "`{}` must not depend on `{}`, which is only visible to {allowed}",
+                    edge.from.name, edge.to.name
+                );
+                Some(Diagnostic::Violation(Violation::new(self.name(), message, vec![edge])))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,100 @@
+//! Metrics of the workspace dependency graph, e.g. to track the health of the architecture.
+
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::condensation;
+use crate::report::Report;
+
+/// Metrics of the dependency graph between the analyzed workspace members.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Stats {
+    /// Number of analyzed workspace members.
+    pub members: usize,
+    /// Number of dependencies between the members.
+    pub dependencies: usize,
+    /// Number of cycles, i.e. strongly connected components with more than one member or a
+    /// dependency of a member on itself.
+    pub cycles: usize,
+    /// Number of members of each cycle, largest first.
+    pub cycle_sizes: Vec<usize>,
+    /// Number of dependencies in the longest chain of dependencies, with each cycle counting as a
+    /// single member.
+    pub longest_chain: usize,
+    /// Average number of dependencies of a member.
+    pub average_out_degree: f64,
+    /// Dependencies as a fraction of the dependencies possible between distinct members.
+    pub density: f64,
+}
+
+impl Stats {
+    /// Computes the metrics of the graph of the report.
+    #[must_use]
+    #[expect(clippy::cast_precision_loss, reason = "member counts are far below the precision limit")]
+    pub fn new(report: &Report) -> Self {
+        let members = report.members.len();
+        let dependencies = report.dependencies.len();
+        let mut cycle_sizes: Vec<usize> = report.cycles.iter().map(|c| c.packages.len()).collect();
+        cycle_sizes.sort_unstable_by(|a, b| b.cmp(a));
+
+        let possible = members * members.saturating_sub(1);
+        Self {
+            members,
+            dependencies,
+            cycles: cycle_sizes.len(),
+            cycle_sizes,
+            longest_chain: longest_chain(report),
+            average_out_degree: if members == 0 { 0.0 } else { dependencies as f64 / members as f64 },
+            density: if possible == 0 {
+                0.0
+            } else {
+                dependencies as f64 / possible as f64
+            },
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Members: {}", self.members)?;
+        writeln!(f, "Dependencies: {}", self.dependencies)?;
+        let sizes: Vec<String> = self.cycle_sizes.iter().map(ToString::to_string).collect();
+        if sizes.is_empty() {
+            writeln!(f, "Cycles: 0")?;
+        } else {
+            writeln!(f, "Cycles: {} (sizes {})", self.cycles, sizes.join(", "))?;
+        }
+        writeln!(f, "Longest chain: {} dependencies", self.longest_chain)?;
+        writeln!(f, "Average out-degree: {:.2}", self.average_out_degree)?;
+        write!(f, "Density: {:.3}", self.density)
+    }
+}
+
+/// Returns the number of dependencies in the longest path through the condensation of the graph.
+fn longest_chain(report: &Report) -> usize {
+    let condensation = condensation::condense(report);
+    // Longest chain starting at each component, computed once all its dependencies are known
+    let mut lengths: Vec<Option<usize>> = vec![None; condensation.components.len()];
+    // The condensation is acyclic, but stop rather than loop forever should a pass make no progress
+    let mut progress = true;
+    while progress && lengths.iter().any(Option::is_none) {
+        progress = false;
+        for idx in 0..lengths.len() {
+            if lengths[idx].is_some() {
+                continue;
+            }
+            let below: Option<Vec<usize>> = condensation
+                .dependencies
+                .iter()
+                .filter(|d| d.from == idx)
+                .map(|d| lengths[d.to])
+                .collect();
+            if let Some(below) = below {
+                lengths[idx] = Some(below.into_iter().max().map_or(0, |length| length + 1));
+                progress = true;
+            }
+        }
+    }
+    lengths.into_iter().flatten().max().unwrap_or(0)
+}
@@ -0,0 +1,49 @@
+//! Compilation targets for evaluating platform-specific dependencies.
+
+use std::env;
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+use cargo_platform::Cfg;
+
+/// A target triple along with the `cfg` values it enables.
+#[derive(Debug, Clone)]
+pub struct Target {
+    /// The target triple, e.g. `x86_64-pc-windows-msvc`.
+    pub name: String,
+    /// The `cfg` values of the target, as printed by `rustc --print cfg`.
+    pub cfg: Vec<Cfg>,
+}
+
+impl Target {
+    /// Queries rustc (or `$RUSTC`) for the `cfg` values of the target.
+    ///
+    /// # Errors
+    ///
+    /// Fails if rustc can't be run or doesn't know the target.
+    pub fn load(name: &str) -> Result<Self> {
+        let rustc = env::var("RUSTC").unwrap_or_else(|_| String::from("rustc"));
+        let output = Command::new(&rustc)
+            .args(["--print", "cfg", "--target", name])
+            .output()
+            .with_context(|| format!("Failed to run {rustc}"))?;
+        if !output.status.success() {
+            bail!(
+                "Failed to query the cfg values of target {name}: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        let cfg = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| {
+                line.parse()
+                    .with_context(|| format!("Failed to parse cfg value `{line}` of target {name}"))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            name: name.to_owned(),
+            cfg,
+        })
+    }
+}
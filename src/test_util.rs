@@ -0,0 +1,47 @@
+//! Support for running the check as a test of the workspace, enabled by the `test-util` feature.
+
+use std::path::Path;
+
+use crate::checker::{CheckError, Checker};
+use crate::output::{self, OutputFormat};
+
+/// Fails the test if the workspace containing the calling crate has cycles that fail the check.
+///
+/// The workspace is found from the manifest of the crate the test belongs to, and checked like the
+/// command line does, honouring its configuration file and allowlist. The panic message lists the
+/// detected cycles.
+///
+/// ```no_run
+/// #[test]
+/// fn no_cyclic_dependencies() {
+///     cargo_ensure_no_cyclic_deps::assert_no_cycles!();
+/// }
+/// ```
+///
+/// A manifest path can be passed to check another workspace, e.g.
+/// `assert_no_cycles!("../other/Cargo.toml")`, relative paths are resolved against the directory of
+/// the calling crate.
+#[macro_export]
+macro_rules! assert_no_cycles {
+    () => {
+        $crate::test_util::assert_no_cycles(&::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml"))
+    };
+    ($manifest_path:expr) => {
+        $crate::test_util::assert_no_cycles(&::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join($manifest_path))
+    };
+}
+
+/// Fails the test if the workspace of the manifest has cycles that fail the check, see
+/// [`assert_no_cycles!`](crate::assert_no_cycles).
+///
+/// # Panics
+///
+/// Panics if the check fails or can't be run.
+#[expect(clippy::panic, reason = "failing the test is the purpose of the assertion")]
+pub fn assert_no_cycles(manifest_path: &Path) {
+    match Checker::new().manifest_path(manifest_path).run() {
+        Ok(_) => {}
+        Err(CheckError::Failed(report)) => panic!("{}", output::render(&report, OutputFormat::Human).unwrap_or_default()),
+        Err(CheckError::Load(err)) => panic!("Failed to check {} for cyclic dependencies: {err:#}", manifest_path.display()),
+    }
+}
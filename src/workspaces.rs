@@ -0,0 +1,196 @@
+//! Checking several workspaces in one run, e.g. the independent workspaces nested in the directory
+//! tree of a root workspace or all the workspaces of a monorepo.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use cargo_metadata::camino::{Utf8Path, Utf8PathBuf};
+use cargo_metadata::{Metadata, PackageId};
+
+use crate::checker::Checker;
+use crate::output::{OutputFormat, WorkspaceSection};
+use crate::{load_metadata, manifest};
+
+/// Directories that never contain workspaces of their own, besides hidden ones.
+const SKIPPED_DIRS: &[&str] = &["target"];
+
+/// Returns the manifests of the workspace roots in the directory tree, sorted by path.
+///
+/// A manifest is a workspace root if it has a `[workspace]` table, or it is of a package that isn't
+/// a member of a workspace further up the tree, either because there is none or because that
+/// workspace excludes it. Build output and hidden directories are skipped.
+#[must_use]
+pub fn discover(dir: &Utf8Path) -> Vec<Utf8PathBuf> {
+    let mut manifests = Vec::new();
+    // Directories to walk, with the root of the workspace they are in
+    let mut pending: Vec<(Utf8PathBuf, Option<Utf8PathBuf>)> = vec![(dir.to_owned(), None)];
+    while let Some((dir, workspace_root)) = pending.pop() {
+        let manifest_path = dir.join("Cargo.toml");
+        let is_root = manifest::read(&manifest_path).is_some_and(|m| {
+            m.contains_key("workspace")
+                || (m.contains_key("package")
+                    && workspace_root
+                        .as_deref()
+                        .is_none_or(|root| manifest::excluded(root).contains(&manifest_path)))
+        });
+        let workspace_root = if is_root {
+            manifests.push(manifest_path);
+            Some(dir.clone())
+        } else {
+            workspace_root
+        };
+        pending.extend(subdirectories(&dir).into_iter().map(|d| (d, workspace_root.clone())));
+    }
+    manifests.sort();
+    manifests
+}
+
+/// Returns the manifests of the workspaces in the subdirectories of the workspace root, sorted by
+/// path, see [`discover`].
+#[must_use]
+pub fn nested(workspace_root: &Utf8Path) -> Vec<Utf8PathBuf> {
+    let root_manifest = workspace_root.join("Cargo.toml");
+    discover(workspace_root).into_iter().filter(|m| *m != root_manifest).collect()
+}
+
+/// Returns the manifests of the workspaces of the manifests, or of the one in the current directory,
+/// each followed by those of the workspaces nested in it, see [`nested`].
+///
+/// # Errors
+///
+/// Fails if the metadata of one of the workspaces can't be loaded.
+pub fn with_nested(manifest_paths: &[impl AsRef<Path>]) -> Result<Vec<Utf8PathBuf>> {
+    let outer: Vec<Option<&Path>> = if manifest_paths.is_empty() {
+        vec![None]
+    } else {
+        manifest_paths.iter().map(|p| Some(p.as_ref())).collect()
+    };
+    let mut manifests = Vec::new();
+    for manifest_path in outer {
+        let root = load_metadata(manifest_path)?.workspace_root;
+        manifests.push(root.join("Cargo.toml"));
+        manifests.extend(nested(&root));
+    }
+    Ok(manifests)
+}
+
+/// Checks each of the workspaces of the manifests in isolation and, if `combine` is set, combined
+/// into one graph, see [`combine`]. `checker` configures the check of each of them.
+///
+/// Returns the output format configured for the first workspace and a section per check.
+///
+/// # Errors
+///
+/// Fails if one of the workspaces can't be checked, see [`Checker::analyze`].
+pub fn check(
+    manifests: &[Utf8PathBuf],
+    combine: bool,
+    checker: impl Fn() -> Checker,
+) -> Result<(Option<OutputFormat>, Vec<WorkspaceSection>)> {
+    let mut format = None;
+    let mut sections = Vec::new();
+    for manifest_path in manifests {
+        let analysis = checker()
+            .manifest_path(manifest_path)
+            .analyze()
+            .with_context(|| format!("Failed to check the workspace of {manifest_path}"))?;
+        format = format.or(analysis.config.format);
+        sections.push(WorkspaceSection {
+            combined: false,
+            report: analysis.report,
+        });
+    }
+
+    if combine {
+        let mut metadata = manifests
+            .iter()
+            .map(|path| load_metadata(Some(path.as_std_path())))
+            .collect::<Result<Vec<_>>>()?;
+        let first = metadata.remove(0);
+        sections.push(WorkspaceSection {
+            combined: true,
+            report: checker().metadata(self::combine(first, metadata)).analyze()?.report,
+        });
+    }
+    Ok((format, sections))
+}
+
+/// Combines the metadata of several workspaces into one, whose members are the members of all of
+/// them, so dependencies between the workspaces become edges of a single graph.
+///
+/// The workspace root and the workspace metadata are those of the `root` workspace.
+#[must_use]
+pub fn combine(root: Metadata, others: Vec<Metadata>) -> Metadata {
+    let mut combined = root;
+    // Without a resolve graph for the combined workspaces, the declared dependencies are checked
+    combined.resolve = None;
+    for metadata in others {
+        merge(&mut combined, metadata);
+    }
+    combined
+}
+
+/// Adds the members of the workspaces that the members depend on with `path = ...` to the metadata.
+///
+/// The workspaces the members of those depend on are added in turn, so cycles across the
+/// workspaces are found. Each workspace is loaded with its own `cargo metadata` run.
+///
+/// # Errors
+///
+/// Fails if the metadata of one of the workspaces can't be loaded.
+pub fn include_path_workspaces(metadata: &mut Metadata) -> Result<()> {
+    metadata.resolve = None;
+    let mut pending = metadata.workspace_members.clone();
+    while let Some(id) = pending.pop() {
+        let paths: Vec<Utf8PathBuf> = metadata
+            .packages
+            .iter()
+            .filter(|p| p.id == id)
+            .flat_map(|p| &p.dependencies)
+            .filter_map(|d| d.path.clone())
+            .collect();
+        for path in paths {
+            let manifest_path = path.join("Cargo.toml");
+            let is_member = metadata
+                .packages
+                .iter()
+                .any(|p| p.manifest_path == manifest_path && metadata.workspace_members.contains(&p.id));
+            if is_member {
+                continue;
+            }
+            let workspace = load_metadata(Some(manifest_path.as_std_path()))
+                .with_context(|| format!("Failed to load the workspace of the path dependency at {path}"))?;
+            pending.extend(merge(metadata, workspace));
+        }
+    }
+    Ok(())
+}
+
+/// Adds the members of the other workspace to the metadata, returns the ids of those that weren't
+/// members before.
+fn merge(metadata: &mut Metadata, other: Metadata) -> Vec<PackageId> {
+    let mut added = Vec::new();
+    for id in other.workspace_members {
+        if !metadata.workspace_members.contains(&id) {
+            metadata.workspace_members.push(id.clone());
+            added.push(id);
+        }
+    }
+    let known: HashSet<PackageId> = metadata.packages.iter().map(|p| p.id.clone()).collect();
+    metadata
+        .packages
+        .extend(other.packages.into_iter().filter(|p| !known.contains(&p.id)));
+    added
+}
+
+fn subdirectories(dir: &Utf8Path) -> Vec<Utf8PathBuf> {
+    dir.read_dir_utf8()
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .filter(|entry| !entry.file_name().starts_with('.') && !SKIPPED_DIRS.contains(&entry.file_name()))
+        .map(|entry| entry.path().to_owned())
+        .collect()
+}
@@ -0,0 +1,3 @@
+pub fn hello_from_alpha() {
+    println!("Hello from alpha");
+}
@@ -0,0 +1,3 @@
+pub fn hello_from_gamma() {
+    println!("Hello from gamma");
+}
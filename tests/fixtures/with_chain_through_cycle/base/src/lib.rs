@@ -0,0 +1,3 @@
+pub fn hello_from_base() {
+    println!("Hello from base");
+}
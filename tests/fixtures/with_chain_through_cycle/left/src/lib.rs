@@ -0,0 +1,3 @@
+pub fn hello_from_left() {
+    println!("Hello from left");
+}
@@ -0,0 +1,3 @@
+pub fn hello_from_right() {
+    println!("Hello from right");
+}
@@ -0,0 +1,3 @@
+pub fn hello_from_app_testing() {
+    println!("Hello from app_testing");
+}
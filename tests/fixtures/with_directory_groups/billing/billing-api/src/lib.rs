@@ -0,0 +1,3 @@
+pub fn hello_from_billing_api() {
+    println!("Hello from billing-api");
+}
@@ -0,0 +1,3 @@
+pub fn hello_from_billing_core() {
+    println!("Hello from billing-core");
+}
@@ -0,0 +1,3 @@
+pub fn hello_from_orders_api() {
+    println!("Hello from orders-api");
+}
@@ -0,0 +1,3 @@
+pub fn hello_from_orders_core() {
+    println!("Hello from orders-core");
+}
@@ -0,0 +1,3 @@
+pub fn hello_from_xtask() {
+    println!("Hello from xtask");
+}
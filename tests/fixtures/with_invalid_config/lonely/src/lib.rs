@@ -0,0 +1,3 @@
+pub fn hello_from_lonely() {
+    println!("Hello from lonely");
+}
@@ -0,0 +1,3 @@
+pub fn hello_from_billing_model() {
+    println!("Hello from billing-model");
+}
@@ -0,0 +1,3 @@
+pub fn hello_from_orders_model() {
+    println!("Hello from orders-model");
+}
@@ -0,0 +1,3 @@
+pub fn hello_from_orders_service() {
+    println!("Hello from orders-service");
+}
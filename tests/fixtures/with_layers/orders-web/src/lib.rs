@@ -0,0 +1,3 @@
+pub fn hello_from_orders_web() {
+    println!("Hello from orders-web");
+}
@@ -0,0 +1,3 @@
+pub fn hello_from_beta() {
+    println!("Hello from beta");
+}
@@ -0,0 +1,3 @@
+pub fn hello_from_app() {
+    println!("Hello from app");
+}
@@ -0,0 +1,3 @@
+pub fn hello_from_core() {
+    println!("Hello from core");
+}
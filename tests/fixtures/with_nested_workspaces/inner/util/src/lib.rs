@@ -0,0 +1,3 @@
+pub fn hello_from_util() {
+    println!("Hello from util");
+}
@@ -0,0 +1,3 @@
+pub fn hello_from_a() {
+    println!("Hello from crate A");
+}
@@ -0,0 +1,3 @@
+pub fn hello_from_client() {
+    println!("Hello from client");
+}
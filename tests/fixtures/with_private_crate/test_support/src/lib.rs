@@ -0,0 +1,3 @@
+pub fn hello_from_test_support() {
+    println!("Hello from test_support");
+}
@@ -0,0 +1,6 @@
+use proc_macro::TokenStream;
+
+#[proc_macro]
+pub fn hello_from_app_macros(input: TokenStream) -> TokenStream {
+    input
+}
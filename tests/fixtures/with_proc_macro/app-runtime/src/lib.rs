@@ -0,0 +1,3 @@
+pub fn hello_from_app_runtime() {
+    println!("Hello from app-runtime");
+}
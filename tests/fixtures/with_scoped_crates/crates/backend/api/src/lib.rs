@@ -0,0 +1,3 @@
+pub fn hello_from_api() {
+    println!("Hello from api");
+}
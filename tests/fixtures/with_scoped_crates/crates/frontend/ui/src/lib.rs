@@ -0,0 +1,3 @@
+pub fn hello_from_ui() {
+    println!("Hello from ui");
+}
@@ -0,0 +1,3 @@
+pub fn hello_from_widgets() {
+    println!("Hello from widgets");
+}
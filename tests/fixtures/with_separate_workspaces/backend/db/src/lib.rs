@@ -0,0 +1,3 @@
+pub fn hello_from_db() {
+    println!("Hello from db");
+}
@@ -0,0 +1,3 @@
+pub fn hello_from_web() {
+    println!("Hello from web");
+}
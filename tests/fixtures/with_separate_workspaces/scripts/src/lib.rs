@@ -0,0 +1,3 @@
+pub fn hello_from_scripts() {
+    println!("Hello from scripts");
+}
@@ -0,0 +1,3 @@
+pub fn hello_from_bindings() {
+    println!("Hello from bindings");
+}
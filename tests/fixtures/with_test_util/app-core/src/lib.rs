@@ -0,0 +1,3 @@
+pub fn hello_from_app_core() {
+    println!("Hello from app-core");
+}
@@ -0,0 +1,3 @@
+pub fn hello_from_core_test_util() {
+    println!("Hello from core-test-util");
+}
@@ -0,0 +1,3 @@
+pub fn hello_from_app_cli() {
+    println!("Hello from app-cli");
+}
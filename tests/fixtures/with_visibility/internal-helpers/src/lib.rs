@@ -0,0 +1,3 @@
+pub fn hello_from_internal_helpers() {
+    println!("Hello from internal-helpers");
+}
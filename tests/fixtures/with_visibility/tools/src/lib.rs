@@ -0,0 +1,3 @@
+pub fn hello_from_tools() {
+    println!("Hello from tools");
+}
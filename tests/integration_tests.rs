@@ -103,3 +103,4196 @@ fn test_command_without_() {
     // We expect success since the oxidizer workspace shouldn't have cycles
     cmd.assert().success();
 }
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_json_output_with_cycle() {
+    let fixture_path = get_fixture_path("with_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--format")
+        .arg("json");
+
+    let output = cmd.assert().failure().code(1).get_output().stdout.clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+    assert_eq!(report["members"].as_array().unwrap().len(), 3);
+    let cycles = report["cycles"].as_array().unwrap();
+    assert_eq!(cycles.len(), 1);
+    let mut names: Vec<&str> = cycles[0]["packages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["name"].as_str().unwrap())
+        .collect();
+    names.sort_unstable();
+    assert_eq!(names, ["crate_a", "crate_b", "crate_c"]);
+    assert_eq!(cycles[0]["edges"].as_array().unwrap().len(), 3);
+    assert!(report["workspace_root"].as_str().unwrap().ends_with("with_cycle"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_json_output_deserializes_into_report() {
+    use cargo_ensure_no_cyclic_deps::report::{Report, SCHEMA_VERSION};
+
+    let fixture_path = get_fixture_path("with_dev_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--format")
+        .arg("json");
+
+    let output = cmd.assert().failure().code(1).get_output().stdout.clone();
+    let value: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(value["schema_version"], SCHEMA_VERSION);
+
+    let report: Report = serde_json::from_value(value).unwrap();
+    assert_eq!(report.schema_version, SCHEMA_VERSION);
+    assert_eq!(report.cycles.len(), 1);
+    assert!(report.cycles[0].dev_only);
+    assert!(report.is_failure());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_json_output_without_cycle() {
+    let fixture_path = get_fixture_path("without_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--format")
+        .arg("json");
+
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+    assert_eq!(report["members"].as_array().unwrap().len(), 3);
+    assert!(report["cycles"].as_array().unwrap().is_empty());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_sarif_output_with_cycle() {
+    let fixture_path = get_fixture_path("with_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--format")
+        .arg("sarif");
+
+    let output = cmd.assert().failure().code(1).get_output().stdout.clone();
+    let log: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+    assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "cyclic-dependency");
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["ruleId"], "cyclic-dependency");
+    assert!(results[0]["message"]["text"].as_str().unwrap().contains("crate_a"));
+    let mut uris: Vec<&str> = results[0]["locations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|l| l["physicalLocation"]["artifactLocation"]["uri"].as_str().unwrap())
+        .collect();
+    uris.sort_unstable();
+    assert_eq!(uris, ["crate_a/Cargo.toml", "crate_b/Cargo.toml", "crate_c/Cargo.toml"]);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_dot_output_highlights_cycle() {
+    let fixture_path = get_fixture_path("with_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--format")
+        .arg("dot");
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stdout(predicate::str::starts_with("digraph workspace {"))
+        .stdout(predicate::str::contains("[label=\"crate_a\", color=red, fontcolor=red]"))
+        .stdout(predicate::str::contains("[color=red, penwidth=2]").count(3));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_dot_output_without_cycle() {
+    let fixture_path = get_fixture_path("without_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--format")
+        .arg("dot");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("[label=\"crate_x\"];"))
+        .stdout(predicate::str::contains(" -> ").count(3))
+        .stdout(predicate::str::contains("red").not());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_mermaid_output_highlights_cycle() {
+    let fixture_path = get_fixture_path("with_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--format")
+        .arg("mermaid");
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stdout(predicate::str::starts_with("```mermaid\ngraph TD\n"))
+        .stdout(predicate::str::contains("[\"crate_a\"]"))
+        .stdout(predicate::str::contains("class n0,n1,n2 cycle"))
+        .stdout(predicate::str::contains("linkStyle 0,1,2 stroke"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_mermaid_output_without_cycle() {
+    let fixture_path = get_fixture_path("without_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--format")
+        .arg("mermaid");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(" --> ").count(3))
+        .stdout(predicate::str::contains("classDef").not());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_junit_output_with_cycle() {
+    let fixture_path = get_fixture_path("with_dev_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--format")
+        .arg("junit");
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stdout(predicate::str::starts_with("<?xml"))
+        .stdout(predicate::str::contains("tests=\"2\" failures=\"2\""))
+        .stdout(predicate::str::contains("<testcase name=\"lib_main\""))
+        .stdout(predicate::str::contains("<failure message=\"lib_main is part of a dependency cycle\"").count(1))
+        .stdout(predicate::str::contains("-&gt; lib_main (cycle ").or(predicate::str::contains("-&gt; lib_test_helpers (cycle ")));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_junit_output_without_cycle() {
+    let fixture_path = get_fixture_path("without_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--format")
+        .arg("junit");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("tests=\"3\" failures=\"0\""))
+        .stdout(predicate::str::contains(
+            "<testcase name=\"crate_z\" classname=\"cyclic-dependencies\"/>",
+        ))
+        .stdout(predicate::str::contains("<failure").not());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_github_output_points_at_declarations() {
+    let fixture_path = get_fixture_path("with_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.env_remove("GITHUB_WORKSPACE")
+        .arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--format")
+        .arg("github");
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stdout(predicate::str::contains("::error ").count(3))
+        .stdout(predicate::str::contains(
+            "::error file=crate_a/Cargo.toml,line=7,title=Cyclic dependency::Cyclic dependency detected: ",
+        ))
+        .stdout(predicate::str::contains("(crate_a depends on crate_b)"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_human_output_adds_annotations_in_github_actions() {
+    let fixture_path = get_fixture_path("with_self_dev_dep");
+    let manifest_path = fixture_path.join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.env("GITHUB_ACTIONS", "true")
+        .env("GITHUB_WORKSPACE", get_fixture_path(""))
+        .arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path);
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("self_dep_crate -> self_dep_crate"))
+        .stdout(predicate::str::contains(
+            "::error file=with_self_dev_dep/self_dep_crate/Cargo.toml,line=9,",
+        ));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_codeclimate_output_with_cycle() {
+    let fixture_path = get_fixture_path("with_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--format")
+        .arg("codeclimate");
+
+    let output = cmd.assert().failure().code(1).get_output().stdout.clone();
+    let issues: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let issues = issues.as_array().unwrap();
+
+    assert_eq!(issues.len(), 3);
+    let issue = issues.iter().find(|i| i["location"]["path"] == "crate_a/Cargo.toml").unwrap();
+    assert_eq!(issue["location"]["lines"]["begin"], 7);
+    assert_eq!(issue["severity"], "major");
+    assert!(issue["description"].as_str().unwrap().contains("crate_a depends on crate_b"));
+
+    let mut fingerprints: Vec<&str> = issues.iter().map(|i| i["fingerprint"].as_str().unwrap()).collect();
+    fingerprints.sort_unstable();
+    fingerprints.dedup();
+    assert_eq!(fingerprints.len(), 3);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_codeclimate_output_without_cycle() {
+    let fixture_path = get_fixture_path("without_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--format")
+        .arg("codeclimate");
+
+    cmd.assert().success().stdout("[]\n");
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_html_report_written_to_output_file() {
+    let fixture_path = get_fixture_path("with_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+    let dir = tempfile::tempdir().unwrap();
+    let report_path = dir.path().join("report.html");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--format")
+        .arg("html")
+        .arg("--output")
+        .arg(&report_path);
+
+    cmd.assert().failure().code(1).stdout("").stderr("");
+
+    let html = std::fs::read_to_string(&report_path).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("1 cycle(s) detected among 3 workspace crates."));
+    assert!(html.contains("<tr class=\"cycle\"><td>crate_b</td><td><code>crate_b/Cargo.toml</code></td><td>1</td></tr>"));
+    assert!(html.contains("<svg"));
+    assert_eq!(html.matches("<line ").count(), 3);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_html_report_without_cycle() {
+    let fixture_path = get_fixture_path("without_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--format")
+        .arg("html");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No cyclic dependencies found among 3 workspace crates."))
+        .stdout(predicate::str::contains("<svg").not());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_markdown_output_with_cycle() {
+    let fixture_path = get_fixture_path("with_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.env_remove("GITHUB_SHA")
+        .arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--format")
+        .arg("markdown");
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stdout(predicate::str::starts_with("<!-- cargo-ensure-no-cyclic-deps -->\n"))
+        .stdout(predicate::str::contains("| :x: Failed | 3 | 1 | 0 |"))
+        .stdout(predicate::str::contains("### Cycle 1"))
+        .stdout(predicate::str::contains("[`crate_a`](crate_a/Cargo.toml)"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_markdown_output_links_to_commit_in_github_actions() {
+    let fixture_path = get_fixture_path("with_self_dev_dep");
+    let manifest_path = fixture_path.join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.env("GITHUB_SERVER_URL", "https://github.com")
+        .env("GITHUB_REPOSITORY", "owner/repo")
+        .env("GITHUB_SHA", "abc123")
+        .env("GITHUB_WORKSPACE", get_fixture_path(""))
+        .arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--format")
+        .arg("markdown");
+
+    cmd.assert().failure().code(1).stdout(predicate::str::contains(
+        "[`self_dep_crate`](https://github.com/owner/repo/blob/abc123/with_self_dev_dep/self_dep_crate/Cargo.toml)",
+    ));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_markdown_output_without_cycle() {
+    let fixture_path = get_fixture_path("without_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--format")
+        .arg("markdown");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("| :white_check_mark: Passed | 3 | 0 | 0 |"))
+        .stdout(predicate::str::contains("### Cycle").not());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_yaml_output_with_cycle() {
+    let fixture_path = get_fixture_path("with_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--format")
+        .arg("yaml");
+
+    let output = cmd.assert().failure().code(1).get_output().stdout.clone();
+    let report: serde_yaml_ng::Value = serde_yaml_ng::from_slice(&output).unwrap();
+
+    assert_eq!(report["members"].as_sequence().unwrap().len(), 3);
+    assert_eq!(report["cycles"].as_sequence().unwrap().len(), 1);
+    assert_eq!(report["cycles"][0]["edges"].as_sequence().unwrap().len(), 3);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_toml_output_with_cycle() {
+    let fixture_path = get_fixture_path("with_self_dev_dep");
+    let manifest_path = fixture_path.join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--format")
+        .arg("toml");
+
+    let output = cmd.assert().failure().code(1).get_output().stdout.clone();
+    let report: toml::Table = toml::from_str(core::str::from_utf8(&output).unwrap()).unwrap();
+
+    let cycles = report["cycles"].as_array().unwrap();
+    assert_eq!(cycles.len(), 1);
+    assert_eq!(cycles[0]["packages"][0]["name"].as_str(), Some("self_dep_crate"));
+    assert_eq!(cycles[0]["edges"][0]["line"].as_integer(), Some(9));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_write_baseline_and_check_against_it() {
+    let fixture_path = get_fixture_path("with_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+    let dir = tempfile::tempdir().unwrap();
+    let baseline_path = dir.path().join("baseline.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--write-baseline")
+        .arg(&baseline_path);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No new cyclic dependencies found."))
+        .stdout(predicate::str::contains("Suppressed 1 known cycle(s):"));
+
+    let baseline = std::fs::read_to_string(&baseline_path).unwrap();
+    assert!(baseline.contains("[[cycles]]"));
+    assert!(baseline.contains("\"crate_a\""));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--baseline")
+        .arg(&baseline_path);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No new cyclic dependencies found."));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_baseline_fails_on_new_cycles() {
+    let fixture_path = get_fixture_path("with_dev_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+    let dir = tempfile::tempdir().unwrap();
+    let baseline_path = dir.path().join("baseline.toml");
+    std::fs::write(&baseline_path, "[[cycles]]\npackages = [\"crate_a\", \"crate_b\"]\n").unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--baseline")
+        .arg(&baseline_path);
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("Error: Cyclic dependencies detected!"))
+        .stderr(predicate::str::contains("Suppressed").not());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_missing_baseline_file_is_an_error() {
+    let fixture_path = get_fixture_path("with_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+    let dir = tempfile::tempdir().unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--baseline")
+        .arg(dir.path().join("missing.toml"));
+
+    cmd.assert().failure().stderr(predicate::str::contains("Failed to read baseline"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_cycle_fingerprint_is_stable() {
+    let fixture_path = get_fixture_path("with_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--format")
+        .arg("json");
+
+    let output = cmd.assert().failure().code(1).get_output().stdout.clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let fingerprint = report["cycles"][0]["fingerprint"].as_str().unwrap().to_owned();
+
+    // The fingerprint only depends on package names and edges, so it's the same everywhere
+    assert_eq!(fingerprint, "cd3979ad044f9580");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps").arg("--manifest-path").arg(&manifest_path);
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(format!("fingerprint: {fingerprint}")));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_baseline_matches_by_fingerprint() {
+    let fixture_path = get_fixture_path("with_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+    let dir = tempfile::tempdir().unwrap();
+    let baseline_path = dir.path().join("baseline.toml");
+    std::fs::write(&baseline_path, "[[cycles]]\nfingerprint = \"cd3979ad044f9580\"\n").unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--baseline")
+        .arg(&baseline_path);
+
+    cmd.assert().success().stdout(predicate::str::contains("(cd3979ad044f9580)"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_allowlist_in_workspace_metadata() {
+    let fixture_path = get_fixture_path("with_allowed_cycles");
+    let manifest_path = fixture_path.join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps").arg("--manifest-path").arg(manifest_path);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No new cyclic dependencies found."))
+        .stdout(predicate::str::contains("Suppressed 2 known cycle(s):"))
+        .stdout(predicate::str::contains("gamma -> gamma (8731746066e5dbd5)"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_invalid_workspace_metadata_is_an_error() {
+    let fixture_path = get_fixture_path("with_invalid_config");
+    let manifest_path = fixture_path.join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps").arg("--manifest-path").arg(manifest_path);
+
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Failed to parse [workspace.metadata.ensure-no-cyclic-deps]",
+    ));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_config_file_in_workspace_root() {
+    let fixture_path = get_fixture_path("with_config_file");
+    let manifest_path = fixture_path.join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps").arg("--manifest-path").arg(manifest_path);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No cyclic dependencies found."));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_config_file_ignores_packages() {
+    let fixture_path = get_fixture_path("with_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    std::fs::write(&config_path, "ignore = [\"crate_b\"]\nformat = \"json\"\n").unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--config")
+        .arg(&config_path);
+
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+    assert_eq!(report["members"].as_array().unwrap().len(), 2);
+    assert!(report["cycles"].as_array().unwrap().is_empty());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_config_file_warning_severity() {
+    let fixture_path = get_fixture_path("with_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    std::fs::write(&config_path, "severity = \"warning\"\nformat = \"sarif\"\n").unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--config")
+        .arg(&config_path);
+
+    cmd.assert().success().stdout(predicate::str::contains("\"level\": \"warning\""));
+
+    // Command line options take precedence over the config file
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--config")
+        .arg(&config_path)
+        .arg("--format")
+        .arg("human");
+
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Warning: Cyclic dependencies detected!"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_invalid_config_file_is_an_error() {
+    let fixture_path = get_fixture_path("with_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    std::fs::write(&config_path, "kinds = [\"optional\"]\n").unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--config")
+        .arg(&config_path);
+
+    cmd.assert().failure().stderr(predicate::str::contains("Failed to parse config"));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--config")
+        .arg(dir.path().join("missing.toml"));
+
+    cmd.assert().failure().stderr(predicate::str::contains("Failed to read config"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_expired_suppression_no_longer_applies() {
+    let fixture_path = get_fixture_path("with_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+    let dir = tempfile::tempdir().unwrap();
+    let baseline_path = dir.path().join("baseline.toml");
+    std::fs::write(
+        &baseline_path,
+        "[[cycles]]\npackages = [\"crate_a\", \"crate_b\", \"crate_c\"]\nexpires = \"2000-01-01\"\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--baseline")
+        .arg(&baseline_path);
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("Error: Cyclic dependencies detected!"))
+        .stderr(predicate::str::contains("suppression expired on 2000-01-01"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_suppression_with_future_expiry_applies() {
+    let fixture_path = get_fixture_path("with_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    std::fs::write(
+        &config_path,
+        "allow = [{ fingerprint = \"cd3979ad044f9580\", expires = \"2999-12-31\" }]\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--config")
+        .arg(&config_path);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Suppressed 1 known cycle(s):"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_invalid_expiry_date_is_an_error() {
+    let fixture_path = get_fixture_path("with_cycle");
+    let manifest_path = fixture_path.join("Cargo.toml");
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    std::fs::write(
+        &config_path,
+        "allow = [{ fingerprint = \"cd3979ad044f9580\", expires = \"next year\" }]\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(manifest_path)
+        .arg("--config")
+        .arg(&config_path);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("expected a date in the YYYY-MM-DD format"));
+
+    // 2999 isn't a leap year
+    std::fs::write(
+        &config_path,
+        "allow = [{ fingerprint = \"cd3979ad044f9580\", expires = \"2999-02-29\" }]\n",
+    )
+    .unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(fixture_path.join("Cargo.toml"))
+        .arg("--config")
+        .arg(&config_path);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid day in `2999-02-29`, the month has 28 days"));
+
+    std::fs::write(
+        &config_path,
+        "allow = [{ fingerprint = \"cd3979ad044f9580\", expires = \"2996-02-29\" }]\n",
+    )
+    .unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(fixture_path.join("Cargo.toml"))
+        .arg("--config")
+        .arg(&config_path);
+    cmd.assert().success();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_suppress_appends_to_config_file() {
+    let manifest_path = get_fixture_path("with_cycle").join("Cargo.toml");
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    std::fs::write(&config_path, "# Keep this comment\nignore = []\n").unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("suppress")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--config")
+        .arg(&config_path)
+        .arg("--reason")
+        .arg("tracked in #123");
+
+    cmd.assert().success().stdout(predicate::str::contains("Suppressed 1 cycle(s)"));
+
+    let config = std::fs::read_to_string(&config_path).unwrap();
+    assert!(config.contains("# Keep this comment"));
+    assert!(config.contains("[[allow]]"));
+    assert!(config.contains("fingerprint = \"cd3979ad044f9580\""));
+    assert!(config.contains("reason = \"tracked in #123\""));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--config")
+        .arg(&config_path);
+
+    cmd.assert().success();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_suppress_appends_to_inline_allowlist() {
+    let manifest_path = get_fixture_path("with_cycle").join("Cargo.toml");
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    std::fs::write(&config_path, "allow = [{ packages = [\"other_a\", \"other_b\"] }]\n").unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("suppress")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--config")
+        .arg(&config_path);
+
+    cmd.assert().success();
+
+    let config = std::fs::read_to_string(&config_path).unwrap();
+    assert!(config.contains("\"other_a\""));
+    assert!(config.contains("fingerprint = \"cd3979ad044f9580\""));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_suppress_to_baseline() {
+    let manifest_path = get_fixture_path("with_cycle").join("Cargo.toml");
+    let dir = tempfile::tempdir().unwrap();
+    let baseline_path = dir.path().join("baseline.toml");
+
+    for _ in 0..2 {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+        cmd.arg("ensure-no-cyclic-deps")
+            .arg("suppress")
+            .arg("--manifest-path")
+            .arg(&manifest_path)
+            .arg("--baseline")
+            .arg(&baseline_path);
+
+        cmd.assert().success();
+    }
+
+    // The second run finds the cycle already suppressed and doesn't add it again
+    let baseline = std::fs::read_to_string(&baseline_path).unwrap();
+    assert_eq!(baseline.matches("[[cycles]]").count(), 1);
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--baseline")
+        .arg(&baseline_path);
+
+    cmd.assert().success();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_strict_baseline_fails_on_stale_entries() {
+    let manifest_path = get_fixture_path("with_cycle").join("Cargo.toml");
+    let dir = tempfile::tempdir().unwrap();
+    let baseline_path = dir.path().join("baseline.toml");
+    std::fs::write(
+        &baseline_path,
+        "[[cycles]]\nfingerprint = \"cd3979ad044f9580\"\n\n[[cycles]]\npackages = [\"crate_a\", \"crate_z\"]\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--baseline")
+        .arg(&baseline_path);
+
+    cmd.assert().success();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--baseline")
+        .arg(&baseline_path)
+        .arg("--strict-baseline");
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("Stale suppressions found"))
+        .stderr(predicate::str::contains("packages [crate_a, crate_z]"))
+        .stderr(predicate::str::contains("cd3979ad044f9580 (").not());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_strict_baseline_checks_allowlist() {
+    let manifest_path = get_fixture_path("without_cycle").join("Cargo.toml");
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    std::fs::write(&config_path, "allow = [{ fingerprint = \"cd3979ad044f9580\" }]\n").unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--config")
+        .arg(&config_path)
+        .arg("--strict-baseline")
+        .arg("--format")
+        .arg("json");
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stdout(predicate::str::contains("\"stale_suppressions\""))
+        .stdout(predicate::str::contains("\"source\": \"allowlist\""));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_ratchet_allows_existing_cycles_and_fails_on_growth() {
+    let manifest_path = get_fixture_path("with_cycle").join("Cargo.toml");
+    let dir = tempfile::tempdir().unwrap();
+    let state_path = dir.path().join("ratchet.json");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--ratchet")
+        .arg(&state_path);
+
+    cmd.assert().success();
+
+    let state = std::fs::read_to_string(&state_path).unwrap();
+    assert!(state.contains("\"cycles\": 1"));
+    assert!(state.contains("\"sizes\""));
+
+    std::fs::write(&state_path, "{ \"cycles\": 0 }").unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--ratchet")
+        .arg(&state_path);
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("The number of cycles increased from 0 to 1"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_ratchet_updates_state_when_cycles_decrease() {
+    let manifest_path = get_fixture_path("without_cycle").join("Cargo.toml");
+    let dir = tempfile::tempdir().unwrap();
+    let state_path = dir.path().join("ratchet.json");
+    std::fs::write(&state_path, "{ \"cycles\": 3, \"sizes\": [2, 2, 2] }").unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--ratchet")
+        .arg(&state_path);
+
+    cmd.assert().success().stderr(predicate::str::contains("decreased from 3 to 0"));
+
+    let state = std::fs::read_to_string(&state_path).unwrap();
+    assert!(state.contains("\"cycles\": 0"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_ratchet_fails_when_a_cycle_grows() {
+    let manifest_path = get_fixture_path("with_cycle").join("Cargo.toml");
+    let dir = tempfile::tempdir().unwrap();
+    let state_path = dir.path().join("ratchet.json");
+    std::fs::write(&state_path, "{ \"cycles\": 1, \"sizes\": [2] }").unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--ratchet")
+        .arg(&state_path);
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("A cycle grew, the cycle sizes went from [2] to [3]"));
+
+    let state = std::fs::read_to_string(&state_path).unwrap();
+    assert!(state.contains("[2]"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_ratchet_updates_state_when_a_cycle_shrinks() {
+    let manifest_path = get_fixture_path("with_cycle").join("Cargo.toml");
+    let dir = tempfile::tempdir().unwrap();
+    let state_path = dir.path().join("ratchet.json");
+    std::fs::write(&state_path, "{ \"cycles\": 1, \"sizes\": [5] }").unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--ratchet")
+        .arg(&state_path);
+
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("The cycle sizes shrank from [5] to [3]"));
+
+    let state: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&state_path).unwrap()).unwrap();
+    assert_eq!(state["sizes"], serde_json::json!([3]));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_max_cycles_budget() {
+    let manifest_path = get_fixture_path("with_cycle").join("Cargo.toml");
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    std::fs::write(&config_path, "max-cycles = 1\n").unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--max-cycles")
+        .arg("0");
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("the budget allows 0"));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--config")
+        .arg(&config_path);
+
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Warning: Cyclic dependencies detected!"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_multiple_baselines_are_combined() {
+    let manifest_path = get_fixture_path("with_multiple_cycles").join("Cargo.toml");
+    let dir = tempfile::tempdir().unwrap();
+    let team_a = dir.path().join("team_a.toml");
+    std::fs::write(&team_a, "[[cycles]]\npackages = [\"alpha\", \"beta\"]\n").unwrap();
+    let team_b = dir.path().join("team_b.toml");
+    std::fs::write(&team_b, "[[cycles]]\nfingerprint = \"8731746066e5dbd5\"\n").unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--baseline")
+        .arg(&team_a);
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        // The cycle of gamma is only in the baseline of team B
+        .stderr(predicate::str::contains("gamma -> gamma"));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--baseline")
+        .arg(&team_a)
+        .arg("--baseline")
+        .arg(&team_b)
+        .arg("--strict-baseline");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Suppressed 2 known cycle(s)"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_kind_filter() {
+    let manifest_path = get_fixture_path("with_dev_cycle").join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--kind")
+        .arg("normal,build");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No cyclic dependencies found."));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--kind")
+        .arg("normal")
+        .arg("--kind")
+        .arg("dev");
+
+    cmd.assert().failure().code(1);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_kind_flag_overrides_config() {
+    let manifest_path = get_fixture_path("with_config_file").join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--kind")
+        .arg("normal,dev");
+
+    cmd.assert().failure().code(1);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_cycle_output_shows_dependency_kinds() {
+    let manifest_path = get_fixture_path("with_dev_cycle").join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps").arg("--manifest-path").arg(&manifest_path);
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("--(dev)-->"))
+        .stderr(predicate::str::contains("--(normal)-->"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_allow_dev_cycles() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_dev_cycle").join("Cargo.toml"))
+        .arg("--allow-dev-cycles");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Suppressed 1 known cycle(s)"))
+        .stdout(predicate::str::contains("dev-dependencies only"));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_cycle").join("Cargo.toml"))
+        .arg("--allow-dev-cycles");
+
+    cmd.assert().failure().code(1);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_allow_self_dev_dependency() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_self_dev_dep").join("Cargo.toml"))
+        .arg("--allow-self-dev-dependency");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No cyclic dependencies found."));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_allow_self_dev_dependency_in_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    std::fs::write(&config_path, "allow-self-dev-dependency = true\n").unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_multiple_cycles").join("Cargo.toml"))
+        .arg("--config")
+        .arg(&config_path);
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("alpha"))
+        .stderr(predicate::str::contains("gamma").not());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_target_specific_dependencies() {
+    let manifest_path = get_fixture_path("with_target_cycle").join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps").arg("--manifest-path").arg(&manifest_path);
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("platform --(normal, cfg(windows))--> app"));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--target")
+        .arg("x86_64-unknown-linux-gnu");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No cyclic dependencies found."));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--target")
+        .arg("x86_64-pc-windows-msvc");
+
+    cmd.assert().failure().code(1);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_feature_selection() {
+    let manifest_path = get_fixture_path("with_feature_cycle").join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps").arg("--manifest-path").arg(&manifest_path);
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("engine -> plugin -> engine"));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--no-default-features");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No cyclic dependencies found."));
+
+    // `app/full` enables `engine/plugins`, which enables the optional dependency closing the cycle
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--no-default-features")
+        .arg("--features")
+        .arg("app/full");
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("engine --(normal, optional)--> plugin"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_unknown_feature_is_an_error() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_feature_cycle").join("Cargo.toml"))
+        .arg("--features")
+        .arg("engine/nope");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Package `engine` has no feature `nope`"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_cycle_output_shows_features_enabling_optional_dependencies() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_feature_cycle").join("Cargo.toml"));
+
+    cmd.assert().failure().code(1).stderr(predicate::str::contains(
+        "engine -> plugin is enabled by any of the features: app/full, engine/plugins",
+    ));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_check_features_detects_cyclic_feature_definitions() {
+    let manifest_path = get_fixture_path("with_feature_definition_cycle").join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--no-default-features");
+
+    cmd.assert().success();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--no-default-features")
+        .arg("--check-features");
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("Error: Cyclic feature definitions detected!"))
+        .stderr(predicate::str::contains("toolkit/extras -> toolkit/full -> toolkit/extras\n"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_check_features_detects_cycles_across_crates() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_feature_definition_cycle").join("Cargo.toml"))
+        .arg("--check-features")
+        .arg("--format")
+        .arg("json");
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stdout(predicate::str::contains("\"feature_cycles\""))
+        .stdout(predicate::str::contains("\"toolkit/sync\""))
+        .stdout(predicate::str::contains("\"widgets/sync\""));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_feature_powerset_reports_required_features() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_feature_cycle").join("Cargo.toml"))
+        .arg("--feature-powerset");
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("requires features: app/full"));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_cycle").join("Cargo.toml"))
+        .arg("--feature-powerset")
+        .arg("--depth")
+        .arg("0");
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("exists without any features"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_library_detects_cycles() {
+    use cargo_ensure_no_cyclic_deps::graph::GraphOptions;
+
+    let manifest_path = get_fixture_path("with_cycle").join("Cargo.toml");
+    let metadata = cargo_ensure_no_cyclic_deps::load_metadata(Some(&manifest_path)).unwrap();
+
+    let cycles = cargo_ensure_no_cyclic_deps::detect_cycles(&metadata);
+    assert_eq!(cycles.len(), 1);
+    assert_eq!(cycles[0].package_names(), ["crate_a", "crate_b", "crate_c"]);
+    assert_eq!(cycles[0].fingerprint, "cd3979ad044f9580");
+
+    let manifest_path = get_fixture_path("without_cycle").join("Cargo.toml");
+    let metadata = cargo_ensure_no_cyclic_deps::load_metadata(Some(&manifest_path)).unwrap();
+
+    let report = cargo_ensure_no_cyclic_deps::analyze(&metadata, &GraphOptions::default());
+    assert!(!report.has_cycles());
+    assert!(!report.members.is_empty());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_library_detects_cycles_in_existing_metadata() {
+    use cargo_ensure_no_cyclic_deps::graph::{DepKind, GraphOptions};
+
+    // Metadata loaded by the caller instead of the library
+    let manifest_path = get_fixture_path("with_dev_cycle").join("Cargo.toml");
+    let metadata = cargo_metadata::MetadataCommand::new()
+        .manifest_path(manifest_path)
+        .no_deps()
+        .exec()
+        .unwrap();
+
+    assert_eq!(cargo_ensure_no_cyclic_deps::detect_cycles(&metadata).len(), 1);
+
+    let options = GraphOptions {
+        kinds: vec![DepKind::Normal, DepKind::Build],
+        ..GraphOptions::default()
+    };
+    assert!(cargo_ensure_no_cyclic_deps::detect_cycles_with(&metadata, &options).is_empty());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_library_checker_builder() {
+    use cargo_ensure_no_cyclic_deps::checker::{CheckError, Checker};
+    use cargo_ensure_no_cyclic_deps::graph::DepKind;
+
+    let manifest_path = get_fixture_path("with_dev_cycle").join("Cargo.toml");
+
+    let Err(CheckError::Failed(report)) = Checker::new().manifest_path(&manifest_path).run() else {
+        panic!("the dev cycle should fail the check");
+    };
+    assert_eq!(report.failing_cycles().count(), 1);
+    assert!(report.is_failure());
+
+    let analysis = Checker::new().manifest_path(&manifest_path).analyze().unwrap();
+    assert!(analysis.report.is_failure());
+
+    let report = Checker::new().manifest_path(&manifest_path).allow_dev_cycles(true).run().unwrap();
+    assert_eq!(report.cycles.len(), 1);
+    assert!(!report.is_failure());
+
+    let report = Checker::new()
+        .manifest_path(&manifest_path)
+        .dependency_kinds([DepKind::Normal, DepKind::Build])
+        .run()
+        .unwrap();
+    assert!(!report.has_cycles());
+
+    let report = Checker::new()
+        .manifest_path(get_fixture_path("with_cycle").join("Cargo.toml"))
+        .max_cycles(1)
+        .run()
+        .unwrap();
+    assert!(!report.is_failure());
+
+    let err = Checker::new()
+        .manifest_path(get_fixture_path("missing").join("Cargo.toml"))
+        .run()
+        .unwrap_err();
+    assert!(matches!(err, CheckError::Load(_)));
+    assert!(err.to_string().starts_with("Failed to load cargo metadata"));
+}
+
+#[test]
+#[cfg(feature = "test-util")]
+#[cfg_attr(miri, ignore)]
+fn test_assert_no_cycles_passes_on_acyclic_workspace() {
+    cargo_ensure_no_cyclic_deps::assert_no_cycles!();
+    cargo_ensure_no_cyclic_deps::assert_no_cycles!("tests/fixtures/with_allowed_cycles/Cargo.toml");
+}
+
+#[test]
+#[cfg(feature = "test-util")]
+#[cfg_attr(miri, ignore)]
+#[should_panic(expected = "crate_a -> crate_b -> crate_c -> crate_a")]
+fn test_assert_no_cycles_fails_on_cycle() {
+    cargo_ensure_no_cyclic_deps::assert_no_cycles!("tests/fixtures/with_cycle/Cargo.toml");
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_build_script_instructions() {
+    use cargo_ensure_no_cyclic_deps::build_script;
+    use cargo_ensure_no_cyclic_deps::checker::Checker;
+
+    let fixture_path = get_fixture_path("with_cycle");
+    let report = Checker::new()
+        .manifest_path(fixture_path.join("Cargo.toml"))
+        .analyze()
+        .unwrap()
+        .report;
+    let instructions = build_script::instructions(&report);
+
+    let root_manifest = fixture_path.join("Cargo.toml");
+    assert!(instructions.contains(&format!("cargo:rerun-if-changed={}\n", root_manifest.display())));
+    assert!(instructions.contains(&format!(
+        "cargo:rerun-if-changed={}\n",
+        fixture_path.join("crate_a").join("Cargo.toml").display()
+    )));
+    assert!(
+        instructions.contains("cargo:warning=Cyclic dependency: crate_a -> crate_b -> crate_c -> crate_a (fingerprint cd3979ad044f9580)\n")
+    );
+
+    let fixture_path = get_fixture_path("with_allowed_cycles");
+    let report = Checker::new().manifest_path(fixture_path.join("Cargo.toml")).run().unwrap();
+    assert!(!build_script::instructions(&report).contains("cargo:warning="));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_rules_lists_builtin_rules() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_cycle").join("Cargo.toml"))
+        .arg("--rules");
+
+    cmd.assert().success().stdout(predicate::str::starts_with(
+        "cycles - Workspace crates must not cyclically depend on each other\n",
+    ));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_rules_lists_configured_rules() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_layers").join("Cargo.toml"))
+        .arg("--rules");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "cycles - Workspace crates must not cyclically depend on each other\n",
+        ))
+        .stdout(predicate::str::contains(
+            "layers - Workspace crates must not depend on crates of a higher layer\n",
+        ));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_library_write_baseline_and_ratchet() {
+    use cargo_ensure_no_cyclic_deps::checker::Checker;
+    use cargo_ensure_no_cyclic_deps::ratchet::Outcome;
+
+    let manifest_path = get_fixture_path("with_cycle").join("Cargo.toml");
+    let dir = tempfile::tempdir().unwrap();
+    let baseline_path = dir.path().join("baseline.toml");
+    let state_path = dir.path().join("ratchet.json");
+
+    let analysis = Checker::new()
+        .manifest_path(&manifest_path)
+        .write_baseline(&baseline_path)
+        .analyze()
+        .unwrap();
+    assert!(!analysis.report.is_failure());
+    assert!(analysis.ratchet.is_none());
+    let report = Checker::new().manifest_path(&manifest_path).baseline(&baseline_path).run().unwrap();
+    assert_eq!(report.suppressed_cycles().count(), 1);
+
+    let analysis = Checker::new().manifest_path(&manifest_path).ratchet(&state_path).analyze().unwrap();
+    assert_eq!(analysis.ratchet, Some(Outcome::Created));
+    let analysis = Checker::new().manifest_path(&manifest_path).ratchet(&state_path).analyze().unwrap();
+    assert_eq!(analysis.ratchet, Some(Outcome::Unchanged));
+    assert!(!analysis.report.is_failure());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_library_custom_rule() {
+    use cargo_ensure_no_cyclic_deps::checker::{CheckError, Checker};
+    use cargo_ensure_no_cyclic_deps::output::{self, OutputFormat};
+    use cargo_ensure_no_cyclic_deps::report::Violation;
+    use cargo_ensure_no_cyclic_deps::rules::{Diagnostic, Rule, RuleContext};
+
+    #[derive(Debug)]
+    struct NoDependencyOnCrateY;
+
+    impl Rule for NoDependencyOnCrateY {
+        fn name(&self) -> &'static str {
+            "no-crate-y"
+        }
+
+        fn description(&self) -> &'static str {
+            "Nothing may depend on crate_y"
+        }
+
+        fn check(&self, context: &RuleContext<'_>) -> Vec<Diagnostic> {
+            context
+                .edges()
+                .into_iter()
+                .filter(|e| e.to.name == "crate_y")
+                .map(|e| Diagnostic::Violation(Violation::new(self.name(), format!("{} depends on crate_y", e.from.name), vec![e])))
+                .collect()
+        }
+    }
+
+    let manifest_path = get_fixture_path("without_cycle").join("Cargo.toml");
+    let Err(CheckError::Failed(report)) = Checker::new().manifest_path(&manifest_path).rule(NoDependencyOnCrateY).run() else {
+        panic!("the rule should fail the check");
+    };
+    assert!(!report.has_cycles());
+    assert_eq!(report.violations.len(), 1);
+    assert_eq!(report.violations[0].rule, "no-crate-y");
+    assert_eq!(report.violations[0].packages, ["crate_x", "crate_y"]);
+
+    let rendered = output::render(&report, OutputFormat::Human).unwrap();
+    assert!(
+        rendered.contains("Error: Rule violations detected!\n  [no-crate-y] crate_x depends on crate_y\n    crate_x -> crate_y (normal) at crate_x/Cargo.toml:7\n")
+    );
+}
+
+#[test]
+#[cfg(feature = "cdylib")]
+#[cfg_attr(miri, ignore)]
+fn test_ffi_check() {
+    use cargo_ensure_no_cyclic_deps::checker::Checker;
+    use cargo_ensure_no_cyclic_deps::ffi;
+    use cargo_ensure_no_cyclic_deps::rules::{Diagnostic, Rule, RuleContext};
+    use core::ffi::CStr;
+    use std::ffi::CString;
+
+    #[derive(Debug)]
+    struct PanickingRule;
+
+    impl Rule for PanickingRule {
+        fn name(&self) -> &'static str {
+            "panicking"
+        }
+
+        fn description(&self) -> &'static str {
+            "Panics"
+        }
+
+        fn check(&self, _context: &RuleContext<'_>) -> Vec<Diagnostic> {
+            panic!("broken rule");
+        }
+    }
+
+    let check = |path: &CString| {
+        let mut output = core::ptr::null_mut();
+        // SAFETY: the path is NUL-terminated and the output is a valid pointer
+        let status = unsafe { ffi::ensure_no_cyclic_deps_check(path.as_ptr(), &raw mut output) };
+        // SAFETY: the output was set by the check
+        let text = unsafe { CStr::from_ptr(output) }.to_str().unwrap().to_owned();
+        // SAFETY: the output was returned by the check and is released once
+        unsafe { ffi::ensure_no_cyclic_deps_free_string(output) };
+        (status, text)
+    };
+
+    let path = CString::new(get_fixture_path("with_cycle").join("Cargo.toml").to_str().unwrap()).unwrap();
+    let (status, json) = check(&path);
+    assert_eq!(status, ffi::STATUS_FAILED);
+    let report: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(report["cycles"].as_array().unwrap().len(), 1);
+
+    let path = CString::new(get_fixture_path("without_cycle").join("Cargo.toml").to_str().unwrap()).unwrap();
+    assert_eq!(check(&path).0, ffi::STATUS_PASSED);
+
+    let path = CString::new(get_fixture_path("missing").join("Cargo.toml").to_str().unwrap()).unwrap();
+    let (status, message) = check(&path);
+    assert_eq!(status, ffi::STATUS_ERROR);
+    assert!(message.starts_with("Failed to load cargo metadata"));
+
+    // A panic, e.g. of a rule, is returned instead of unwinding into the caller
+    let checker = Checker::new()
+        .manifest_path(get_fixture_path("without_cycle").join("Cargo.toml"))
+        .rule(PanickingRule);
+    assert_eq!(
+        ffi::check(checker),
+        (ffi::STATUS_PANIC, "The check panicked: broken rule".to_owned())
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_elementary_cycles_counted_without_all_cycles() {
+    let manifest_path = get_fixture_path("with_tangled_cycles").join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps").arg("--manifest-path").arg(&manifest_path);
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("  elementary cycles: 3\n"))
+        .stderr(predicate::str::contains("Found 3 elementary cycle(s) in 1 cycle(s).\n"))
+        .stderr(predicate::str::contains("elementary cycles (").not());
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--format")
+        .arg("json");
+
+    let output = cmd.assert().failure().code(1).get_output().stdout.clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(report["cycles"][0]["elementary_cycle_count"], 3);
+    assert!(report["cycles"][0].get("elementary_cycles").is_none());
+    assert!(report.get("culprits").is_none());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_all_cycles_lists_elementary_cycles() {
+    let manifest_path = get_fixture_path("with_tangled_cycles").join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--all-cycles");
+
+    cmd.assert().failure().code(1).stderr(predicate::str::contains(
+        "  elementary cycles (3):\n    api -> engine -> api\n    engine -> storage -> engine\n    api -> engine -> storage -> api\n",
+    ));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--all-cycles")
+        .arg("--max-cycles-per-scc")
+        .arg("1")
+        .arg("--format")
+        .arg("json");
+
+    let output = cmd.assert().failure().code(1).get_output().stdout.clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(report["elementary_cycles_limit"], 1);
+    assert_eq!(report["cycles"][0]["elementary_cycles"].as_array().unwrap().len(), 1);
+    assert_eq!(report["cycles"][0]["elementary_cycles"], serde_json::json!([["api", "engine"]]));
+    assert_eq!(report["cycles"][0]["elementary_cycle_count"], 3);
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--all-cycles")
+        .arg("--max-cycles-per-scc")
+        .arg("2");
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("    engine -> storage -> engine\n    ... and 1 more\n"))
+        .stderr(predicate::str::contains("Found 3 elementary cycle(s) in 1 cycle(s).\n"))
+        .stderr(predicate::str::contains(
+            "  api -> engine: 2 cycle(s)\n  engine -> storage: 2 cycle(s)\n",
+        ));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_suggest_cuts() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_tangled_cycles").join("Cargo.toml"))
+        .arg("--suggest-cuts");
+
+    cmd.assert().failure().code(1).stderr(predicate::str::contains(
+        "  to break the cycle:\n    remove dependency `api` (normal) from `engine` (engine/Cargo.toml:7)\n    remove dependency `storage` (normal) from `engine` (engine/Cargo.toml:8)\n",
+    ));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_cycle").join("Cargo.toml"))
+        .arg("--suggest-cuts")
+        .arg("--format")
+        .arg("json");
+
+    let output = cmd.assert().failure().code(1).get_output().stdout.clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let cuts = report["cycles"][0]["suggested_cuts"].as_array().unwrap();
+    assert_eq!(cuts.len(), 1);
+    assert_eq!(cuts[0]["from"]["name"], "crate_c");
+    assert_eq!(cuts[0]["to"]["name"], "crate_a");
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_suggest_shared_crate_extraction() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_tangled_cycles").join("Cargo.toml"))
+        .arg("--suggest-cuts");
+
+    cmd.assert().failure().code(1).stderr(predicate::str::contains(
+        "  or extract a shared crate:\n    move the parts of `engine` used by `api`, `storage` into a new crate\n",
+    ));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_tangled_cycles").join("Cargo.toml"))
+        .arg("--suggest-cuts")
+        .arg("--format")
+        .arg("json");
+
+    let output = cmd.assert().failure().code(1).get_output().stdout.clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let extraction = &report["cycles"][0]["suggested_extraction"];
+    assert_eq!(extraction["crates"], serde_json::json!(["engine"]));
+    assert_eq!(extraction["dependents"], serde_json::json!(["api", "storage"]));
+    assert_eq!(extraction["edges"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_near_cycles() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("without_cycle").join("Cargo.toml"))
+        .arg("--near-cycles");
+
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Adding any of these dependencies would create a cycle:\n  crate_y -> crate_x (existing path: crate_x -> crate_y)\n  crate_z -> crate_x (existing path: crate_x -> crate_z)\n  crate_z -> crate_y (existing path: crate_y -> crate_z)\n",
+    ));
+
+    // Members of the same cycle are left out
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_tangled_cycles").join("Cargo.toml"))
+        .arg("--near-cycles")
+        .arg("--format")
+        .arg("json");
+
+    let output = cmd.assert().failure().code(1).get_output().stdout.clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let near_cycles = report["near_cycles"].as_array().unwrap();
+    assert_eq!(near_cycles.len(), 3);
+    assert!(near_cycles.iter().all(|n| n["from"] == "util"));
+    assert_eq!(near_cycles[0]["to"], "api");
+    assert_eq!(near_cycles[0]["path"], serde_json::json!(["api", "engine", "util"]));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_why_prints_dependency_path() {
+    let manifest_path = get_fixture_path("with_tangled_cycles").join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("why")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--from")
+        .arg("storage")
+        .arg("--to")
+        .arg("util");
+    cmd.assert().success().stdout("storage --(normal)--> engine --(normal)--> util\n");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("why")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--from")
+        .arg("util")
+        .arg("--to")
+        .arg("api");
+    cmd.assert().failure().code(1).stdout("`util` doesn't depend on `api`.\n");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("why")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--from")
+        .arg("nonexistent")
+        .arg("--to")
+        .arg("api");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("`nonexistent` isn't a member of the workspace"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_rdeps_lists_dependents_with_depth() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("rdeps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_tangled_cycles").join("Cargo.toml"))
+        .arg("util");
+    cmd.assert()
+        .success()
+        .stdout("engine (depth 1)\napi (depth 2)\nstorage (depth 2)\n");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("rdeps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("without_cycle").join("Cargo.toml"))
+        .arg("crate_x");
+    cmd.assert().success().stdout("No workspace member depends on `crate_x`.\n");
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_graph_subcommand() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("graph")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_cycle").join("Cargo.toml"));
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::starts_with("digraph workspace {\n"))
+        .stdout(predicate::str::contains("// cycle cd3979ad044f9580"));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("graph")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("without_cycle").join("Cargo.toml"))
+        .arg("-p")
+        .arg("crate_y");
+    cmd.assert()
+        .success()
+        .stdout("digraph workspace {\n    node [shape=box];\n    n0 [label=\"crate_y\"];\n    n1 [label=\"crate_z\"];\n    n0 -> n1;\n}\n");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("graph")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("without_cycle").join("Cargo.toml"))
+        .arg("--format")
+        .arg("json");
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(report["members"].as_array().unwrap().len(), 3);
+    assert_eq!(report["dependencies"].as_array().unwrap().len(), 3);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_order_prints_members_in_dependency_order() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("order")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("without_cycle").join("Cargo.toml"));
+    cmd.assert().success().stdout("crate_z\ncrate_y\ncrate_x\n");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("order")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_cycle").join("Cargo.toml"));
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stdout("")
+        .stderr(predicate::str::contains("can't be ordered"))
+        .stderr(predicate::str::contains("(cd3979ad044f9580)"));
+
+    // Without the dev-dependencies there is an order
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("order")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_dev_cycle").join("Cargo.toml"))
+        .arg("--kind")
+        .arg("normal,build");
+    cmd.assert().success().stdout("lib_main\nlib_test_helpers\n");
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_check_add_simulates_dependency() {
+    let manifest_path = get_fixture_path("with_tangled_cycles").join("Cargo.toml");
+    let check_add = |package: &str, dep: &str| {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+        cmd.arg("ensure-no-cyclic-deps")
+            .arg("check-add")
+            .arg("--manifest-path")
+            .arg(&manifest_path)
+            .arg("--package")
+            .arg(package)
+            .arg("--dep")
+            .arg(dep);
+        cmd
+    };
+
+    check_add("util", "api")
+        .assert()
+        .failure()
+        .code(1)
+        .stdout("Adding `api` to `util` would create a cycle: util -> api --(normal)--> engine --(normal)--> util\n");
+    check_add("api", "util")
+        .assert()
+        .success()
+        .stdout("Adding `util` to `api` doesn't create a cycle.\n");
+    check_add("util", "serde")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("`serde` isn't a member of the workspace"));
+    check_add("nonexistent", "util")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("`nonexistent` isn't a member of the workspace"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_focus_only_reports_cycles_of_matching_crates() {
+    let manifest_path = get_fixture_path("with_multiple_cycles").join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--focus")
+        .arg("al*");
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("alpha -> beta -> alpha"))
+        .stderr(predicate::str::contains("gamma -> gamma").not());
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--focus")
+        .arg("delta");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No cyclic dependencies found."));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--focus")
+        .arg("[");
+    cmd.assert().failure().stderr(predicate::str::contains("Invalid focus pattern `[`"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_focus_keeps_other_cycles_in_graph_analyses() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_multiple_cycles").join("Cargo.toml"))
+        .arg("--focus")
+        .arg("gamma")
+        .arg("--format")
+        .arg("dsm-csv");
+    cmd.assert()
+        .failure()
+        .stdout("crate,alpha,beta,gamma\nalpha,,normal,\nbeta,dev,,\ngamma,,,dev\n");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_multiple_cycles").join("Cargo.toml"))
+        .arg("--focus")
+        .arg("gamma")
+        .arg("--condense")
+        .arg("--format")
+        .arg("dot");
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("[label=\"alpha, beta\", color=red, fontcolor=red]"));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_multiple_cycles").join("Cargo.toml"))
+        .arg("--focus")
+        .arg("gamma")
+        .arg("--format")
+        .arg("json");
+    let output = cmd.output().unwrap();
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["cycles"].as_array().unwrap().len(), 2);
+    assert_eq!(report["out_of_focus"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_stats_prints_graph_metrics() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("stats")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("without_cycle").join("Cargo.toml"));
+    cmd.assert()
+        .success()
+        .stdout("Members: 3\nDependencies: 3\nCycles: 0\nLongest chain: 2 dependencies\nAverage out-degree: 1.00\nDensity: 0.500\n");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("stats")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_tangled_cycles").join("Cargo.toml"))
+        .arg("--format")
+        .arg("json");
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let stats: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(stats["members"], 4);
+    assert_eq!(stats["dependencies"], 6);
+    assert_eq!(stats["cycles"], 1);
+    assert_eq!(stats["cycle_sizes"], serde_json::json!([3]));
+    assert_eq!(stats["longest_chain"], 1);
+    assert_eq!(stats["average_out_degree"], 1.5);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_stats_with_focus_on_no_cycle() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    std::fs::write(&config_path, "focus = [\"crate_a\"]\n").unwrap();
+
+    let mut cmd = assert_cmd::Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("stats")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_multiple_cycles").join("Cargo.toml"))
+        .arg("--config")
+        .arg(&config_path)
+        .timeout(core::time::Duration::from_secs(60));
+    cmd.assert().success().stdout(
+        "Members: 3\nDependencies: 3\nCycles: 2 (sizes 2, 1)\nLongest chain: 0 dependencies\nAverage out-degree: 1.00\nDensity: 0.500\n",
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_explain_cycle_by_fingerprint() {
+    let manifest_path = get_fixture_path("with_cycle").join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("explain")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("cd3979ad044f9580");
+    cmd.assert().success().stdout(
+        "Cycle cd3979ad044f9580: crate_a -> crate_b -> crate_c -> crate_a\n  dependencies:\n    crate_a -> crate_b (normal) at crate_a/Cargo.toml:7\n    crate_b -> crate_c (normal) at crate_b/Cargo.toml:7\n    crate_c -> crate_a (normal) at crate_c/Cargo.toml:7\n",
+    );
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("explain")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("0123456789abcdef");
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stdout("No cycle with fingerprint 0123456789abcdef exists in the workspace.\n");
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_print_members_and_edges() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_dev_cycle").join("Cargo.toml"))
+        .arg("--print")
+        .arg("members")
+        .arg("--print")
+        .arg("edges");
+    cmd.assert().success().stdout(
+        "lib_main (lib_main/Cargo.toml)\nlib_test_helpers (lib_test_helpers/Cargo.toml)\nlib_main -> lib_test_helpers (dev) at lib_main/Cargo.toml:9\nlib_test_helpers -> lib_main (normal) at lib_test_helpers/Cargo.toml:7\n",
+    );
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_dev_cycle").join("Cargo.toml"))
+        .arg("--kind")
+        .arg("normal")
+        .arg("--print")
+        .arg("edges");
+    cmd.assert()
+        .success()
+        .stdout("lib_test_helpers -> lib_main (normal) at lib_test_helpers/Cargo.toml:7\n");
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_why_prints_k_shortest_paths() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("why")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_tangled_cycles").join("Cargo.toml"))
+        .arg("--from")
+        .arg("api")
+        .arg("--to")
+        .arg("api")
+        .arg("--paths")
+        .arg("5");
+    cmd.assert()
+        .success()
+        .stdout("api --(normal)--> engine --(normal)--> api\napi --(normal)--> engine --(normal)--> storage --(normal)--> api\n");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("why")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_tangled_cycles").join("Cargo.toml"))
+        .arg("--from")
+        .arg("storage")
+        .arg("--to")
+        .arg("api")
+        .arg("--paths")
+        .arg("1");
+    cmd.assert().success().stdout("storage --(normal)--> api\n");
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_renamed_dependency() {
+    let manifest_path = get_fixture_path("with_renamed_dep").join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--print")
+        .arg("edges");
+    cmd.assert()
+        .success()
+        .stdout("app -> my-foo (normal) at app/Cargo.toml:7\nmy-foo -> app (normal) at my-foo/Cargo.toml:7\n");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--format")
+        .arg("json");
+    let output = cmd.assert().failure().code(1).get_output().stdout.clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let edge = &report["cycles"][0]["edges"][0];
+    assert_eq!(edge["from"]["name"], "app");
+    assert_eq!(edge["to"]["name"], "my-foo");
+    assert_eq!(edge["line"], 7);
+    assert_eq!(edge["declarations"][0]["rename"], "foo");
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_registry_dependency_with_member_name_is_not_an_edge() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_registry_namesake").join("Cargo.toml"));
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No cyclic dependencies found."));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_registry_namesake").join("Cargo.toml"))
+        .arg("--print")
+        .arg("edges");
+    cmd.assert().success().stdout("log -> app (normal) at log/Cargo.toml:7\n");
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_inherited_dependency_points_at_workspace_manifest() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_workspace_dep").join("Cargo.toml"))
+        .arg("--print")
+        .arg("edges");
+    cmd.assert().success().stdout(
+        "api -> core (normal) at api/Cargo.toml:7, inherited from Cargo.toml:7\n\
+         core -> api (dev) at core/Cargo.toml:6, inherited from Cargo.toml:6\n",
+    );
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_workspace_dep").join("Cargo.toml"))
+        .arg("--format")
+        .arg("json");
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("\"line\": 7,\n      \"workspace_line\": 7,"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_artifact_dependency_is_tagged() {
+    use cargo_ensure_no_cyclic_deps::checker::Checker;
+    use cargo_ensure_no_cyclic_deps::report::Edge;
+
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    std::fs::write(
+        root.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"app\", \"tool\"]\nresolver = \"2\"\n",
+    )
+    .unwrap();
+    for (name, section, dependency) in [("app", "build-dependencies", "tool"), ("tool", "dependencies", "app")] {
+        std::fs::create_dir_all(root.join(name).join("src")).unwrap();
+        std::fs::write(root.join(name).join("src").join("lib.rs"), "").unwrap();
+        std::fs::write(
+            root.join(name).join("Cargo.toml"),
+            format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[{section}]\n{dependency} = {{ path = \"../{dependency}\" }}\n"),
+        )
+        .unwrap();
+    }
+    let metadata = cargo_metadata::MetadataCommand::new()
+        .manifest_path(root.join("Cargo.toml"))
+        .no_deps()
+        .exec()
+        .unwrap();
+
+    // Stable cargo rejects artifact dependencies, so they are only declared after loading the metadata
+    let manifest = root.join("app").join("Cargo.toml");
+    let contents = std::fs::read_to_string(&manifest).unwrap();
+    std::fs::write(
+        &manifest,
+        contents.replace("path = \"../tool\"", "path = \"../tool\", artifact = \"bin\""),
+    )
+    .unwrap();
+
+    let report = Checker::new().metadata(metadata).analyze().unwrap().report;
+    assert_eq!(report.cycles.len(), 1);
+    let labels: Vec<String> = report.dependencies.iter().map(Edge::label).collect();
+    assert_eq!(labels, ["build, artifact bin", "normal"]);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_patch_to_workspace_member_creates_edge() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_patch_cycle").join("Cargo.toml"));
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "dependencies: app --(normal, via patch.crates-io)--> log --(normal)--> app",
+        ))
+        .stderr(predicate::str::contains(
+            "only exists because of [patch] or [replace] in the workspace manifest",
+        ));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_patch_cycle").join("Cargo.toml"))
+        .arg("--format")
+        .arg("json");
+    let output = cmd.assert().failure().get_output().stdout.clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(report["cycles"][0]["patch_only"], true);
+    assert_eq!(report["dependencies"][0]["declarations"][0]["patched"], "patch.crates-io");
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_include_path_deps_outside_workspace() {
+    let manifest_path = get_fixture_path("with_outside_path_dep").join("workspace").join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps").arg("--manifest-path").arg(&manifest_path);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No cyclic dependencies found."));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--include-path-deps");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("dependencies: app --(normal)--> helper --(dev)--> app"));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--include-path-deps")
+        .arg("--print")
+        .arg("edges");
+    cmd.assert().success().stdout(
+        "app -> helper (normal) at app/Cargo.toml:7\n\
+         helper -> app (dev) at ../helper/Cargo.toml:7\n",
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_resolved_graph() {
+    // Cargo resolves dev-dependency cycles, the resolved graph has the same cycle
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_dev_cycle").join("Cargo.toml"))
+        .arg("--resolved");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("fingerprint: 2357a91a4875c8ba"))
+        .stderr(predicate::str::contains("Note:").not());
+
+    // Cargo rejects cycles of normal dependencies, the declared ones are checked instead
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_cycle").join("Cargo.toml"))
+        .arg("--resolved");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("fingerprint: cd3979ad044f9580"))
+        .stderr(predicate::str::contains(
+            "Note: The declared dependencies were checked, they couldn't be resolved: Failed to resolve the dependencies",
+        ));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_cycle").join("Cargo.toml"))
+        .arg("--resolved")
+        .arg("--format")
+        .arg("json");
+    let output = cmd.assert().failure().get_output().stdout.clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert!(report["resolve_error"].as_str().unwrap().contains("cyclic package dependency"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_resolve_diff() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("resolve-diff")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_inactive_optional").join("Cargo.toml"));
+    cmd.assert().failure().stdout(
+        "Declared but not activated by cargo's resolver:\n  \
+         engine -> plugin (normal, optional) at engine/Cargo.toml:10\n\
+         Cycles only between the declared dependencies:\n  \
+         engine -> plugin -> engine (96c5b89ea3d4e93a)\n",
+    );
+
+    // Without the optional dependency, both graphs are the same
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("resolve-diff")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_inactive_optional").join("Cargo.toml"))
+        .arg("--no-default-features");
+    cmd.assert()
+        .success()
+        .stdout("The declared and the resolved dependencies are the same.\n");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("resolve-diff")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_cycle").join("Cargo.toml"));
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("The dependencies can't be resolved"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_path_dependency_with_version_is_an_edge() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_versioned_path_deps").join("Cargo.toml"))
+        .arg("--print")
+        .arg("edges");
+    cmd.assert().success().stdout(
+        "serde_core -> serde_derive (normal) at serde_core/Cargo.toml:7\n\
+         serde_derive -> serde_core (dev) at serde_derive/Cargo.toml:6\n",
+    );
+
+    for resolved in [false, true] {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+        cmd.arg("ensure-no-cyclic-deps")
+            .arg("--manifest-path")
+            .arg(get_fixture_path("with_versioned_path_deps").join("Cargo.toml"));
+        if resolved {
+            cmd.arg("--resolved");
+        }
+        cmd.assert().failure().stderr(predicate::str::contains(
+            "dependencies: serde_core --(normal)--> serde_derive --(dev)--> serde_core",
+        ));
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_package_selection() {
+    let manifest_path = get_fixture_path("with_multiple_cycles").join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("-p")
+        .arg("al*")
+        .arg("--package")
+        .arg("beta")
+        .arg("--print")
+        .arg("edges");
+    cmd.assert().success().stdout(
+        "alpha -> beta (normal) at alpha/Cargo.toml:7\n\
+         beta -> alpha (dev) at beta/Cargo.toml:9\n",
+    );
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--exclude")
+        .arg("b*");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("gamma -> gamma"))
+        .stderr(predicate::str::contains("alpha").not());
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--exclude")
+        .arg("gamma")
+        .arg("-p")
+        .arg("[");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid package pattern `[`"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_default_members_only() {
+    let manifest_path = get_fixture_path("with_default_members").join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps").arg("--manifest-path").arg(&manifest_path);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("app -> core -> xtask -> app"));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--default-members-only");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No cyclic dependencies found."));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--default-members-only")
+        .arg("--print")
+        .arg("members");
+    cmd.assert().success().stdout("app (app/Cargo.toml)\ncore (core/Cargo.toml)\n");
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_include_excluded() {
+    let manifest_path = get_fixture_path("with_excluded").join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--print")
+        .arg("members");
+    cmd.assert().success().stdout("app (app/Cargo.toml)\n");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--include-excluded")
+        .arg("--print")
+        .arg("members");
+    cmd.assert()
+        .success()
+        .stdout("app (app/Cargo.toml)\ntools (tools/Cargo.toml)\ngen (vendor/gen/Cargo.toml)\n");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--include-excluded");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("dependencies: app --(normal)--> gen --(dev)--> app"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_nested_workspaces() {
+    let fixture = get_fixture_path("with_nested_workspaces");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(fixture.join("Cargo.toml"))
+        .arg("--nested");
+    cmd.assert().success().stdout(format!(
+        "Workspace {}:\nNo cyclic dependencies found.\n\nWorkspace {}:\nNo cyclic dependencies found.\n\n",
+        fixture.display(),
+        fixture.join("inner").display()
+    ));
+
+    // The cycle goes through both workspaces, so only the combined graph has it
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(fixture.join("Cargo.toml"))
+        .arg("--nested")
+        .arg("--combine");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "All workspaces combined:\nError: Cyclic dependencies detected!",
+        ))
+        .stderr(predicate::str::contains("app -> core -> app"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_nested_workspaces_json() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_nested_workspaces").join("Cargo.toml"))
+        .arg("--nested")
+        .arg("--combine")
+        .arg("--format")
+        .arg("json");
+    let output = cmd.assert().failure().get_output().stdout.clone();
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let workspaces = json["workspaces"].as_array().unwrap();
+    assert_eq!(workspaces.len(), 3);
+    assert_eq!(workspaces[2]["combined"], true);
+    assert_eq!(workspaces[2]["report"]["cycles"].as_array().unwrap().len(), 1);
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_nested_workspaces").join("Cargo.toml"))
+        .arg("--nested")
+        .arg("--format")
+        .arg("sarif");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("The sarif format only supports a single workspace"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_recurse() {
+    let fixture = get_fixture_path("with_separate_workspaces");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps").arg("--recurse").arg(&fixture);
+    // The members of the workspaces aren't workspace roots, the standalone package is
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(format!(
+            "Workspace {}:\nError: Cyclic dependencies detected!",
+            fixture.join("backend").display()
+        )))
+        .stderr(predicate::str::contains("api -> db -> api"))
+        .stdout(format!(
+            "Workspace {}:\nNo cyclic dependencies found.\n\nWorkspace {}:\nNo cyclic dependencies found.\n\n",
+            fixture.join("frontend").display(),
+            fixture.join("scripts").display()
+        ));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--recurse")
+        .arg(&fixture)
+        .arg("--allow-dev-cycles");
+    cmd.assert().success();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_recurse_skips_build_output() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join("target/package/copy")).unwrap();
+    std::fs::write(dir.path().join("target/package/copy/Cargo.toml"), "[workspace]\n").unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps").arg("--recurse").arg(dir.path());
+    cmd.assert().failure().stderr(predicate::str::contains("No workspaces found in"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_multiple_manifest_paths() {
+    let without_cycle = get_fixture_path("without_cycle");
+    let with_cycle = get_fixture_path("with_cycle");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(without_cycle.join("Cargo.toml"))
+        .arg("--manifest-path")
+        .arg(with_cycle.join("Cargo.toml"));
+    cmd.assert()
+        .failure()
+        .stdout(format!("Workspace {}:\nNo cyclic dependencies found.\n\n", without_cycle.display()))
+        .stderr(predicate::str::contains(format!(
+            "Workspace {}:\nError: Cyclic dependencies detected!",
+            with_cycle.display()
+        )));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps").arg("--manifest-path").arg(format!(
+        "{},{}",
+        without_cycle.join("Cargo.toml").display(),
+        get_fixture_path("with_registry_namesake").join("Cargo.toml").display()
+    ));
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(format!("Workspace {}:\n", without_cycle.display())))
+        .stdout(predicate::str::contains("with_registry_namesake:\n"));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("order")
+        .arg("--manifest-path")
+        .arg(without_cycle.join("Cargo.toml"))
+        .arg("--manifest-path")
+        .arg(with_cycle.join("Cargo.toml"));
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Only one --manifest-path can be given, except for the check",
+    ));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_include_path_workspaces() {
+    let fixture = get_fixture_path("with_nested_workspaces");
+
+    // Starting from the inner workspace, its path dependency leads into the outer one
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(fixture.join("inner/Cargo.toml"))
+        .arg("--include-path-workspaces")
+        .arg("--print")
+        .arg("members");
+    cmd.assert()
+        .success()
+        .stdout("core (core/Cargo.toml)\nutil (util/Cargo.toml)\napp (../app/Cargo.toml)\n");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(fixture.join("inner/Cargo.toml"))
+        .arg("--include-path-workspaces");
+    cmd.assert().failure().stderr(predicate::str::contains("core -> app -> core"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_combine_manifest_paths() {
+    let fixture = get_fixture_path("with_nested_workspaces");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(fixture.join("Cargo.toml"))
+        .arg("--manifest-path")
+        .arg(fixture.join("inner/Cargo.toml"))
+        .arg("--combine");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "All workspaces combined:\nError: Cyclic dependencies detected!",
+    ));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(fixture.join("Cargo.toml"))
+        .arg("--combine");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--combine needs several workspaces"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_combine_workspaces_with_same_named_members() {
+    // Both workspaces have an `app` and a `core`, only those of the second depend on each other
+    let fixture = get_fixture_path("with_same_named_members");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(fixture.join("first/Cargo.toml"))
+        .arg("--manifest-path")
+        .arg(fixture.join("second/Cargo.toml"))
+        .arg("--combine")
+        .arg("--check-features")
+        .arg("--scope")
+        .arg("app");
+    cmd.assert()
+        .failure()
+        .code(1)
+        // The `app` in the scope of the combined graph is the one of the first workspace
+        .stdout(predicate::str::contains(
+            "All workspaces combined:\nNo cyclic dependencies found.\n",
+        ))
+        // `logging` enables `tracing` in one `core` and `tracing` enables `logging` in the other
+        .stderr(predicate::str::contains("Cyclic feature definitions detected").not());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_scope() {
+    let manifest_path = get_fixture_path("with_scoped_crates").join("Cargo.toml");
+
+    // The cycle of the backend crate goes through a shared crate outside the scope
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--scope")
+        .arg("crates/backend/");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("api -> core -> api"))
+        .stderr(predicate::str::contains("ui").not());
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--scope")
+        .arg("./crates/frontend");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("ui -> widgets -> ui"))
+        .stderr(predicate::str::contains("api").not());
+
+    // Prefixes match whole directory names
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--scope")
+        .arg("crates/back");
+    cmd.assert().success().stdout("No cyclic dependencies found.\n");
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_skipped_by_package_metadata() {
+    let manifest_path = get_fixture_path("with_skipped_crate").join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps").arg("--manifest-path").arg(&manifest_path);
+    cmd.assert().success().stdout("No cyclic dependencies found.\n").stderr("");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--verbose");
+    cmd.assert().success().stderr("Note: Skipped by their package metadata: bindings\n");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--format")
+        .arg("json");
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(report["skipped"], serde_json::json!(["bindings"]));
+    assert_eq!(report["members"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_publishable_only() {
+    let manifest_path = get_fixture_path("with_private_crate").join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps").arg("--manifest-path").arg(&manifest_path);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("core -> test_support -> core"));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--publishable-only");
+    cmd.assert().success().stdout("No cyclic dependencies found.\n");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("order")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--publishable-only");
+    cmd.assert().success().stdout("core\nclient\n");
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_layers() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_layers").join("Cargo.toml"));
+    // Dependencies on lower layers and of crates in no layer are fine
+    cmd.assert().failure().stderr(
+        "No cyclic dependencies found.\n\n\
+         Error: Rule violations detected!\n  \
+         [layers] `billing-model` in layer `domain` depends on `orders-web` in the higher layer `ui`\n    \
+         billing-model -> orders-web (normal) at billing-model/Cargo.toml:7\n",
+    );
+
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.toml");
+    std::fs::write(&config, "layers = [{ name = \"domain\", crates = [\"[\"] }]\n").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_layers").join("Cargo.toml"))
+        .arg("--config")
+        .arg(&config);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid crate pattern `[` in layer `domain`"));
+
+    std::fs::write(
+        &config,
+        "layers = [{ name = \"domain\", regex = \".*-model$\" }, { name = \"ui\", regex = \"-(web|cli)$\" }]\n",
+    )
+    .unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_layers").join("Cargo.toml"))
+        .arg("--config")
+        .arg(&config);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "`billing-model` in layer `domain` depends on `orders-web` in the higher layer `ui`",
+        ))
+        .stderr(predicate::str::contains("orders-service").not());
+
+    std::fs::write(&config, "layers = [{ name = \"domain\", regex = \"(model\" }]\n").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_layers").join("Cargo.toml"))
+        .arg("--config")
+        .arg(&config);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid crate regex `(model` in layer `domain`"));
+}
+
+#[test]
+#[cfg(unix)]
+#[cfg_attr(miri, ignore)]
+fn test_plugins() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("input.json");
+    // A shell script standing in for a WASM module, run by `sh` instead of a WASI runtime
+    let plugin = dir.path().join("rule.sh");
+    let id = |name: &str| format!("path+file://{}#0.1.0", get_fixture_path("with_layers").join(name).display());
+    std::fs::write(
+        &plugin,
+        format!(
+            "cat > '{}'\necho '{{\"violations\": [{{\"rule\": \"no-web-in-model\", \"message\": \"billing-model must not depend on orders-web\", \
+             \"dependencies\": [{{\"from\": \"{}\", \"to\": \"{}\"}}]}}]}}'\n",
+            input.display(),
+            id("billing-model"),
+            id("orders-web")
+        ),
+    )
+    .unwrap();
+    let config = dir.path().join("config.toml");
+    std::fs::write(&config, format!("plugins = [\"{}\"]\nwasm-runtime = [\"sh\"]\n", plugin.display())).unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_layers").join("Cargo.toml"))
+        .arg("--config")
+        .arg(&config);
+    cmd.assert().failure().code(1).stderr(predicate::str::contains(
+        "  [no-web-in-model] billing-model must not depend on orders-web\n    \
+         billing-model -> orders-web (normal) at billing-model/Cargo.toml:7\n",
+    ));
+    let input: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&input).unwrap()).unwrap();
+    assert_eq!(input["members"].as_array().unwrap().len(), 5);
+    assert!(
+        input["dependencies"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|d| d["to"]["name"] == "orders-web")
+    );
+
+    std::fs::write(
+        &config,
+        format!("plugins = [\"{}\"]\nwasm-runtime = [\"missing-wasm-runtime\"]\n", plugin.display()),
+    )
+    .unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_layers").join("Cargo.toml"))
+        .arg("--config")
+        .arg(&config);
+    cmd.assert().failure().code(1).stderr(predicate::str::contains(format!(
+        "  [plugin] The plugin {} couldn't be run: The WASM runtime `missing-wasm-runtime` isn't installed or isn't on the PATH",
+        plugin.display()
+    )));
+
+    // Dependencies that aren't in the graph aren't silently dropped
+    std::fs::write(
+        &plugin,
+        format!(
+            "cat > /dev/null\necho '{{\"violations\": [{{\"message\": \"no\", \"dependencies\": [{{\"from\": \"{}\", \"to\": \"{}\"}}]}}]}}'\n",
+            id("orders-web"),
+            id("billing-model")
+        ),
+    )
+    .unwrap();
+    std::fs::write(&config, format!("plugins = [\"{}\"]\nwasm-runtime = [\"sh\"]\n", plugin.display())).unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_layers").join("Cargo.toml"))
+        .arg("--config")
+        .arg(&config);
+    cmd.assert().failure().code(1).stderr(predicate::str::contains(format!(
+        "couldn't be run: It reported the dependency of {} on {}, which isn't in the graph",
+        id("orders-web"),
+        id("billing-model")
+    )));
+
+    // A plugin may succeed without reading the graph
+    std::fs::write(&plugin, "echo '{\"violations\": []}'\n").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_layers").join("Cargo.toml"))
+        .arg("--config")
+        .arg(&config);
+    cmd.assert().success();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_deny() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.toml");
+    std::fs::write(
+        &config,
+        r#"deny = [
+    { from = "*-model", to = "*-web" },
+    { from = "xtask", to = "billing-*", reason = "tools go through the service layer" },
+    { from = "orders-web", to = "xtask" },
+]
+"#,
+    )
+    .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_layers").join("Cargo.toml"))
+        .arg("--config")
+        .arg(&config);
+    cmd.assert().failure().stderr(
+        "No cyclic dependencies found.\n\n\
+         Error: Rule violations detected!\n  \
+         [deny] `billing-model` must not depend on `orders-web`, denied by `*-model` -> `*-web`\n    \
+         billing-model -> orders-web (normal) at billing-model/Cargo.toml:7\n  \
+         [deny] `xtask` must not depend on `billing-model`, denied by `xtask` -> `billing-*`: tools go through the service layer\n    \
+         xtask -> billing-model (normal) at xtask/Cargo.toml:8\n",
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_visibility() {
+    // One crate restricts its dependents in its package metadata, the other in the configuration
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_visibility").join("Cargo.toml"));
+    cmd.assert().failure().stderr(
+        "No cyclic dependencies found.\n\n\
+         Error: Rule violations detected!\n  \
+         [visibility] `app-cli` must not depend on `internal-helpers`, which is only visible to `app-core`\n    \
+         app-cli -> internal-helpers (normal) at app-cli/Cargo.toml:8\n  \
+         [visibility] `tools` must not depend on `app-core`, which is only visible to `app-*`\n    \
+         tools -> app-core (normal) at tools/Cargo.toml:7\n",
+    );
+
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.toml");
+    std::fs::write(
+        &config,
+        "visibility = [{ crate = \"app-core\", visible-to = [\"app-*\", \"tools\"] }]\n",
+    )
+    .unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_visibility").join("Cargo.toml"))
+        .arg("--config")
+        .arg(&config);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("`tools` must not depend").not())
+        .stderr(predicate::str::contains("`app-cli` must not depend on `internal-helpers`"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_max_depth() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.toml");
+    std::fs::write(&config, "max-depth = 3\n").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_layers").join("Cargo.toml"))
+        .arg("--config")
+        .arg(&config);
+    cmd.assert().failure().stderr(
+        "No cyclic dependencies found.\n\n\
+         Error: Rule violations detected!\n  \
+         [depth] `xtask` has a dependency chain of 4 hops to `orders-model`, more than the maximum depth of 3\n    \
+         xtask -> billing-model (normal) at xtask/Cargo.toml:8\n    \
+         billing-model -> orders-web (normal) at billing-model/Cargo.toml:7\n    \
+         orders-web -> orders-service (normal) at orders-web/Cargo.toml:7\n    \
+         orders-service -> orders-model (normal) at orders-service/Cargo.toml:7\n",
+    );
+
+    std::fs::write(&config, "max-depth = 4\n").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_layers").join("Cargo.toml"))
+        .arg("--config")
+        .arg(&config);
+    cmd.assert().success();
+
+    // The dependencies between the crates of a cycle aren't counted
+    std::fs::write(&config, "max-depth = 0\n").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_cycle").join("Cargo.toml"))
+        .arg("--config")
+        .arg(&config);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Cyclic dependencies detected").and(predicate::str::contains("[depth]").not()));
+
+    // The chain continues from the cycle of `left` and `right` with the dependencies of either
+    std::fs::write(&config, "max-depth = 1\n").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_chain_through_cycle").join("Cargo.toml"))
+        .arg("--config")
+        .arg(&config);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "  [depth] `app` has a dependency chain of 2 hops to `base`, more than the maximum depth of 1\n    \
+         app -> left (normal) at app/Cargo.toml:7\n    \
+         right -> base (normal) at right/Cargo.toml:8\n",
+    ));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("stats")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_chain_through_cycle").join("Cargo.toml"));
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Longest chain: 2 dependencies\n"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_fan_limits() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.toml");
+    std::fs::write(&config, "max-dependencies = 1\nmax-dependents = 1\n").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_layers").join("Cargo.toml"))
+        .arg("--config")
+        .arg(&config);
+    cmd.assert().failure().stderr(
+        "No cyclic dependencies found.\n\n\
+         Error: Rule violations detected!\n  \
+         [fan] `orders-web` has 2 direct workspace dependents, more than the maximum of 1\n    \
+         billing-model -> orders-web (normal) at billing-model/Cargo.toml:7\n    \
+         xtask -> orders-web (normal) at xtask/Cargo.toml:7\n  \
+         [fan] `xtask` has 2 direct workspace dependencies, more than the maximum of 1\n    \
+         xtask -> billing-model (normal) at xtask/Cargo.toml:8\n    \
+         xtask -> orders-web (normal) at xtask/Cargo.toml:7\n",
+    );
+
+    // The first matching entry takes precedence over the defaults
+    std::fs::write(
+        &config,
+        "max-dependencies = 1\nmax-dependents = 1\n\
+         fan-limits = [{ crate = \"xtask\", max-dependencies = 2 }, { crate = \"orders-*\", max-dependents = 2 }, \
+         { crate = \"*\", max-dependents = 0 }]\n",
+    )
+    .unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_layers").join("Cargo.toml"))
+        .arg("--config")
+        .arg(&config);
+    cmd.assert().failure().stderr(
+        "No cyclic dependencies found.\n\n\
+         Error: Rule violations detected!\n  \
+         [fan] `billing-model` has 1 direct workspace dependents, more than the maximum of 0\n    \
+         xtask -> billing-model (normal) at xtask/Cargo.toml:8\n",
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_strict_layering() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.toml");
+    std::fs::write(
+        &config,
+        "strict-layering = true\n\
+         layers = [\n    \
+         { name = \"domain\", crates = [\"*-model\"] },\n    \
+         { name = \"application\", crates = [\"*-service\"] },\n    \
+         { name = \"ui\", crates = [\"*-web\"] },\n    \
+         { name = \"tools\", crates = [\"xtask\"] },\n\
+         ]\n",
+    )
+    .unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_layers").join("Cargo.toml"))
+        .arg("--config")
+        .arg(&config);
+    // Depending on the layer right below is fine
+    cmd.assert().failure().stderr(
+        "No cyclic dependencies found.\n\n\
+         Error: Rule violations detected!\n  \
+         [layers] `billing-model` in layer `domain` depends on `orders-web` in the higher layer `ui`\n    \
+         billing-model -> orders-web (normal) at billing-model/Cargo.toml:7\n  \
+         [layers] `xtask` in layer `tools` depends on `billing-model` in layer `domain`, skipping `application`, `ui`\n    \
+         xtask -> billing-model (normal) at xtask/Cargo.toml:8\n",
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_layers_by_naming_convention() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.toml");
+    std::fs::write(
+        &config,
+        "layers = [{ name = \"model\" }, { name = \"service\" }, { name = \"web\" }]\n",
+    )
+    .unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_layers").join("Cargo.toml"))
+        .arg("--config")
+        .arg(&config);
+    cmd.assert().failure().stderr(
+        "No cyclic dependencies found.\n\n\
+         Error: Rule violations detected!\n  \
+         [layers] `billing-model` in layer `model` depends on `orders-web` in the higher layer `web`\n    \
+         billing-model -> orders-web (normal) at billing-model/Cargo.toml:7\n",
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_proc_macro_runtime_crates() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_proc_macro").join("Cargo.toml"));
+    // The dev-dependency of the macros is fine, as are the runtime dependencies of other crates
+    cmd.assert().failure().stderr(
+        "No cyclic dependencies found.\n\n\
+         Error: Rule violations detected!\n  \
+         [proc-macro] The proc-macro crate `app-macros` must not depend on the runtime crate `app-runtime`\n    \
+         app-macros -> app-runtime (normal) at app-macros/Cargo.toml:10\n",
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_test_utils() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_test_util").join("Cargo.toml"));
+    // The dev-dependency of `app-core` is fine
+    cmd.assert().failure().stderr(
+        "No cyclic dependencies found.\n\n\
+         Error: Rule violations detected!\n  \
+         [test-utils] `app` must only depend on the test utility crate `core-test-util` through dev-dependencies\n    \
+         app -> core-test-util (normal) at app/Cargo.toml:8\n  \
+         [test-utils] `tools` must only depend on the test utility crate `core-test-util` through dev-dependencies\n    \
+         tools -> core-test-util (build) at tools/Cargo.toml:7\n",
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_directory_groups() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_directory_groups").join("Cargo.toml"));
+    // The crates themselves don't depend on each other in a cycle
+    cmd.assert().failure().stderr(
+        "No cyclic dependencies found.\n\n\
+         Error: Rule violations detected!\n  \
+         [groups] The crate groups `billing`, `orders` depend on each other in a cycle\n    \
+         billing-api -> orders-core (normal) at billing/billing-api/Cargo.toml:8\n    \
+         orders-api -> billing-core (normal) at orders/orders-api/Cargo.toml:8\n",
+    );
+
+    // Configured groups take precedence over the directories
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.toml");
+    std::fs::write(
+        &config,
+        "groups = [{ name = \"commerce\", crates = [\"billing-*\", \"orders-*\"] }]\n",
+    )
+    .unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_directory_groups").join("Cargo.toml"))
+        .arg("--config")
+        .arg(&config);
+    cmd.assert().success();
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_svg_output_and_open() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_cycle").join("Cargo.toml"))
+        .arg("--format")
+        .arg("svg");
+    cmd.assert().failure().stdout(
+        predicate::str::starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\"")
+            .and(predicate::str::contains(">crate_a</text>"))
+            .and(predicate::str::ends_with("</svg>\n")),
+    );
+
+    // The browser is given the path of the written file
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cycles.svg");
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_cycle").join("Cargo.toml"))
+        .arg("--open")
+        .arg("--output")
+        .arg(&path)
+        .env("BROWSER", "ls");
+    cmd.assert().failure().stdout(format!("{}\n", path.display()));
+    assert!(std::fs::read_to_string(&path).unwrap().contains(">crate_b</text>"));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_cycle").join("Cargo.toml"))
+        .arg("--open")
+        .arg("--format")
+        .arg("json");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--open needs the svg, html or html-interactive format"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_explore() {
+    // Commands are read from stdin, which `std::process::Command` doesn't take
+    let mut cmd = assert_cmd::Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("explore")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_multiple_cycles").join("Cargo.toml"))
+        .write_stdin("list a\nshow alpha\nprev\nnext\nshow delta\nfrobnicate\nquit\ncycles\n");
+    cmd.assert().success().stdout(
+        "Exploring 3 workspace crates with 2 cycle(s), type `help` for the commands.\n\
+         > * alpha\n\
+         * beta\n\
+         * gamma\n\
+         > alpha\n  \
+         dependencies:\n    \
+         beta (normal) [cycle]\n  \
+         dependents:\n    \
+         beta (dev) [cycle]\n\
+         > Cycle 2 of 2: gamma -> gamma (8731746066e5dbd5)\n    \
+         gamma -> gamma (dev) at gamma/Cargo.toml:9\n\
+         > Cycle 1 of 2: alpha -> beta -> alpha (1ac98cc2ef07e685)\n    \
+         alpha -> beta (normal) at alpha/Cargo.toml:7\n    \
+         beta -> alpha (dev) at beta/Cargo.toml:9\n\
+         > `delta` isn't a member of the workspace at "
+            .to_owned()
+            + get_fixture_path("with_multiple_cycles").to_str().unwrap()
+            + "\n> Unknown command `frobnicate`, type `help` for the commands.\n> ",
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_tree_output() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_tangled_cycles").join("Cargo.toml"))
+        .arg("--format")
+        .arg("tree");
+    cmd.assert().failure().stdout(
+        "Cycle c6341f1e3b5678c8: api -> engine -> storage -> api\n\
+         api\n\
+         └── engine (normal)\n    \
+         ├── api (normal) (cycle)\n    \
+         └── storage (normal)\n        \
+         ├── api (normal) (cycle)\n        \
+         └── engine (normal) (cycle)\n",
+    );
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("without_cycle").join("Cargo.toml"))
+        .arg("--format")
+        .arg("tree");
+    cmd.assert().success().stdout("No cyclic dependencies found.\n");
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_d2_output() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_cycle").join("Cargo.toml"))
+        .arg("--format")
+        .arg("d2");
+    cmd.assert().failure().stdout(
+        "classes: {\n  \
+         cycle: {style: {stroke: \"#d00\"; font-color: \"#d00\"; stroke-width: 2}}\n  \
+         cycle-dependency: {style: {stroke: \"#d00\"; stroke-width: 2}}\n\
+         }\n\
+         \"crate_a\": {class: cycle}\n\
+         \"crate_b\": {class: cycle}\n\
+         \"crate_c\": {class: cycle}\n\
+         \"crate_a\" -> \"crate_b\": {class: cycle-dependency}\n\
+         \"crate_b\" -> \"crate_c\": {class: cycle-dependency}\n\
+         \"crate_c\" -> \"crate_a\": {class: cycle-dependency}\n\
+         # cycle cd3979ad044f9580: crate_a -> crate_b -> crate_c -> crate_a\n",
+    );
+
+    // The crates are in containers for their directories
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("graph")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_directory_groups").join("Cargo.toml"))
+        .arg("--format")
+        .arg("d2");
+    cmd.assert().success().stdout(
+        predicate::str::contains("\"billing\".\"billing-api\" -> \"orders\".\"orders-core\"\n")
+            .and(predicate::str::contains("\"tools\".\"xtask\"\n"))
+            .and(predicate::str::contains("classes").not()),
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_plantuml_output() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_tangled_cycles").join("Cargo.toml"))
+        .arg("--format")
+        .arg("plantuml");
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::starts_with("@startuml\n").and(predicate::str::ends_with("@enduml\n")));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_cycle").join("Cargo.toml"))
+        .arg("--format")
+        .arg("plantuml");
+    cmd.assert().failure().stdout(
+        "@startuml\n\
+         component \"crate_a\" as n0 #line:red;text:red\n\
+         component \"crate_b\" as n1 #line:red;text:red\n\
+         component \"crate_c\" as n2 #line:red;text:red\n\
+         n0 -[#red,bold]-> n1\n\
+         n1 -[#red,bold]-> n2\n\
+         n2 -[#red,bold]-> n0\n\
+         ' cycle cd3979ad044f9580: crate_a -> crate_b -> crate_c -> crate_a\n\
+         @enduml\n",
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_graphml_and_gexf_output() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("graph")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_layers").join("Cargo.toml"))
+        .arg("--format")
+        .arg("graphml");
+    cmd.assert().success().stdout(
+        predicate::str::starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<graphml")
+            .and(predicate::str::contains(
+                "    <node id=\"n0\">\n      \
+                 <data key=\"name\">billing-model</data>\n      \
+                 <data key=\"version\">0.1.0</data>\n      \
+                 <data key=\"path\">billing-model/Cargo.toml</data>\n      \
+                 <data key=\"layer\">domain</data>\n    \
+                 </node>\n",
+            ))
+            .and(predicate::str::contains(
+                "    <edge id=\"e0\" source=\"n0\" target=\"n1\">\n      \
+                 <data key=\"kinds\">normal</data>\n      \
+                 <data key=\"cycle\">false</data>\n    \
+                 </edge>\n",
+            ))
+            .and(predicate::str::ends_with("</graphml>\n")),
+    );
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_cycle").join("Cargo.toml"))
+        .arg("--format")
+        .arg("gexf");
+    cmd.assert().failure().stdout(
+        predicate::str::contains(
+            "      <node id=\"n0\" label=\"crate_a\">\n        <attvalues>\n          \
+             <attvalue for=\"version\" value=\"0.1.0\"/>\n          \
+             <attvalue for=\"path\" value=\"crate_a/Cargo.toml\"/>\n          \
+             <attvalue for=\"scc\" value=\"cd3979ad044f9580\"/>\n        \
+             </attvalues>\n      </node>\n",
+        )
+        .and(predicate::str::contains("<attvalue for=\"cycle\" value=\"true\"/>"))
+        .and(predicate::str::ends_with("</gexf>\n")),
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_cytoscape_output() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_cycle").join("Cargo.toml"))
+        .arg("--format")
+        .arg("cytoscape");
+    let output = cmd.output().unwrap();
+    assert!(!output.status.success());
+
+    let graph: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let nodes = graph["elements"]["nodes"].as_array().unwrap();
+    assert_eq!(nodes.len(), 3);
+    assert_eq!(
+        nodes[0],
+        serde_json::json!({
+            "data": {
+                "id": "n0",
+                "label": "crate_a",
+                "version": "0.1.0",
+                "path": "crate_a/Cargo.toml",
+                "scc": "cd3979ad044f9580",
+                "cycle": true,
+            },
+            "classes": "cycle",
+        })
+    );
+    let edges = graph["elements"]["edges"].as_array().unwrap();
+    assert_eq!(edges.len(), 3);
+    assert_eq!(
+        edges[0]["data"],
+        serde_json::json!({ "id": "e0", "source": "n0", "target": "n1", "kinds": "normal", "cycle": true })
+    );
+}
+
+#[test]
+fn test_html_interactive_output() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_cycle").join("Cargo.toml"))
+        .arg("--format")
+        .arg("html-interactive");
+    let output = cmd.output().unwrap();
+    assert!(!output.status.success());
+
+    let html = String::from_utf8(output.stdout).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<input id=\"search\""));
+    assert!(html.contains("<li class=\"cycle-name\" data-scc=\"cd3979ad044f9580\">"));
+    // The details are built from the graph data, which is escaped before it is inserted
+    assert!(html.contains("(${esc(e.kinds)})"));
+
+    let (_, script) = html.split_once("const GRAPH = ").unwrap();
+    let (graph, _) = script.split_once(";\n").unwrap();
+    let graph: serde_json::Value = serde_json::from_str(graph).unwrap();
+    assert_eq!(graph["nodes"].as_array().unwrap().len(), 3);
+    assert_eq!(
+        graph["nodes"][0],
+        serde_json::json!({ "name": "crate_a", "path": "crate_a/Cargo.toml", "scc": "cd3979ad044f9580" })
+    );
+    assert_eq!(graph["edges"].as_array().unwrap().len(), 3);
+    assert!(graph["edges"].as_array().unwrap().iter().all(|edge| edge["cycle"] == true));
+}
+
+#[test]
+fn test_dsm_output() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_tangled_cycles").join("Cargo.toml"))
+        .arg("--format")
+        .arg("dsm-csv");
+    cmd.assert().failure().stdout(
+        "crate,util,api,engine,storage\n\
+         util,,,,\n\
+         api,,,normal,\n\
+         engine,normal,normal,,normal\n\
+         storage,,normal,normal,\n",
+    );
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_tangled_cycles").join("Cargo.toml"))
+        .arg("--format")
+        .arg("dsm-html");
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::starts_with("<!DOCTYPE html>"))
+        .stdout(predicate::str::contains(
+            "2 of 6 dependencies are above the diagonal, in 1 cycle(s).",
+        ))
+        .stdout(predicate::str::contains(
+            "<tr><th class=\"name\">2 api</th><td></td><td class=\"diagonal\"></td>\
+             <td class=\"scc above\" title=\"api depends on engine (normal)\">x</td><td class=\"scc\"></td></tr>",
+        ));
+}
+
+#[test]
+fn test_dsm_output_without_cycles() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("without_cycle").join("Cargo.toml"))
+        .arg("--format")
+        .arg("dsm-csv");
+    cmd.assert()
+        .success()
+        .stdout("crate,crate_z,crate_y,crate_x\ncrate_z,,,\ncrate_y,normal,,\ncrate_x,normal,normal,\n");
+}
+
+#[test]
+fn test_only_sccs() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_tangled_cycles").join("Cargo.toml"))
+        .arg("--format")
+        .arg("dot")
+        .arg("--only-sccs");
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("n0 [label=\"api\", color=red, fontcolor=red];"))
+        .stdout(predicate::str::contains("n2 [label=\"storage\", color=red, fontcolor=red];"))
+        .stdout(predicate::str::contains("n2 -> n1 [color=red, penwidth=2];"))
+        .stdout(predicate::str::contains("util").not());
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("graph")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_self_dev_dep").join("Cargo.toml"))
+        .arg("--format")
+        .arg("dsm-csv")
+        .arg("--only-sccs");
+    cmd.assert().success().stdout("crate,self_dep_crate\nself_dep_crate,dev\n");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("graph")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("without_cycle").join("Cargo.toml"))
+        .arg("--only-sccs");
+    cmd.assert().success().stdout("digraph workspace {\n    node [shape=box];\n}\n");
+}
+
+#[test]
+fn test_only_sccs_needs_graph_format() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_cycle").join("Cargo.toml"))
+        .arg("--only-sccs");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "--only-sccs needs a graph output format, such as dot, mermaid or graphml",
+    ));
+}
+
+#[test]
+fn test_rule_violations_in_github_output() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_layers").join("Cargo.toml"))
+        .arg("--format")
+        .arg("github")
+        .env_remove("GITHUB_WORKSPACE");
+    cmd.assert().failure().stdout(
+        "::error file=billing-model/Cargo.toml,line=7,title=Rule violation (layers)::`billing-model` in layer `domain` \
+         depends on `orders-web` in the higher layer `ui`\n",
+    );
+}
+
+#[test]
+fn test_rule_violations_in_junit_output() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_layers").join("Cargo.toml"))
+        .arg("--format")
+        .arg("junit");
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "<testsuites name=\"cargo-ensure-no-cyclic-deps\" tests=\"6\" failures=\"1\">",
+        ))
+        .stdout(predicate::str::contains(
+            "<testsuite name=\"dependency-rules\" tests=\"1\" failures=\"1\">",
+        ))
+        .stdout(predicate::str::contains(
+            "<testcase name=\"layers: billing-model, orders-web\" classname=\"dependency-rules\">",
+        ))
+        .stdout(predicate::str::contains(
+            "type=\"layers\">billing-model -&gt; orders-web (normal) at billing-model/Cargo.toml:7</failure>",
+        ));
+}
+
+#[test]
+fn test_rule_violations_in_markdown_output() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_layers").join("Cargo.toml"))
+        .arg("--format")
+        .arg("markdown")
+        .env_remove("GITHUB_SHA");
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("| :x: Failed | 5 | 0 | 0 |"))
+        .stdout(predicate::str::contains(
+            "### Rule violations\n\n\
+             - **layers**: `billing-model` in layer `domain` depends on `orders-web` in the higher layer `ui`\n  \
+             - [`billing-model`](billing-model/Cargo.toml) &rarr; [`orders-web`](orders-web/Cargo.toml) (normal) at \
+             `billing-model/Cargo.toml:7`\n",
+        ));
+}
+
+#[test]
+fn test_rule_violations_in_sarif_output() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_layers").join("Cargo.toml"))
+        .arg("--format")
+        .arg("sarif");
+    let output = cmd.output().unwrap();
+    assert!(!output.status.success());
+
+    let log: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let run = &log["runs"][0];
+    assert_eq!(run["tool"]["driver"]["rules"][1]["id"], "layers");
+    assert_eq!(
+        run["results"],
+        serde_json::json!([{
+            "ruleId": "layers",
+            "level": "error",
+            "message": { "text": "`billing-model` in layer `domain` depends on `orders-web` in the higher layer `ui`" },
+            "locations": [{
+                "physicalLocation": {
+                    "artifactLocation": { "uri": "billing-model/Cargo.toml", "uriBaseId": "%SRCROOT%" },
+                    "region": { "startLine": 7 },
+                },
+            }],
+        }])
+    );
+}
+
+#[test]
+fn test_rule_violations_in_codeclimate_output() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_layers").join("Cargo.toml"))
+        .arg("--format")
+        .arg("codeclimate");
+    let output = cmd.output().unwrap();
+    assert!(!output.status.success());
+
+    let issues: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let issues = issues.as_array().unwrap();
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0]["check_name"], "layers");
+    assert_eq!(issues[0]["severity"], "major");
+    assert_eq!(
+        issues[0]["location"],
+        serde_json::json!({ "path": "billing-model/Cargo.toml", "lines": { "begin": 7 } })
+    );
+}
+
+#[test]
+fn test_rule_violations_in_html_output() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_layers").join("Cargo.toml"))
+        .arg("--format")
+        .arg("html");
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("<h2>Rule violations</h2>"))
+        .stdout(predicate::str::contains(
+            "<li><code>billing-model</code> &rarr; <code>orders-web</code> (normal) at <code>billing-model/Cargo.toml:7</code></li>",
+        ));
+}
+
+#[test]
+fn test_feature_cycles_in_report_outputs() {
+    let expected = [
+        (
+            "github",
+            "::error file=toolkit/Cargo.toml,title=Cyclic feature definition::Cyclic feature definitions detected: toolkit/extras -> toolkit/full -> toolkit/extras\n",
+        ),
+        (
+            "junit",
+            "<failure message=\"Cyclic feature definitions detected: toolkit/sync -&gt; widgets/sync -&gt; toolkit/sync (across crates)\" type=\"cyclic-feature\">",
+        ),
+        (
+            "markdown",
+            "### Cyclic feature definitions\n\n- `toolkit/extras -> toolkit/full -> toolkit/extras`\n- `toolkit/sync -> widgets/sync -> toolkit/sync` (across crates)\n",
+        ),
+        ("sarif", "\"ruleId\": \"cyclic-feature\""),
+        ("codeclimate", "\"check_name\": \"cyclic-feature\""),
+        (
+            "html",
+            "<li class=\"cycle\"><code>toolkit/sync -&gt; widgets/sync -&gt; toolkit/sync</code> (across crates)</li>",
+        ),
+    ];
+    for (format, output) in expected {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+        cmd.arg("ensure-no-cyclic-deps")
+            .arg("--manifest-path")
+            .arg(get_fixture_path("with_feature_definition_cycle").join("Cargo.toml"))
+            .arg("--no-default-features")
+            .arg("--check-features")
+            .arg("--format")
+            .arg(format)
+            .env_remove("GITHUB_WORKSPACE");
+        cmd.assert().failure().stdout(predicate::str::contains(output));
+    }
+}
+
+#[test]
+fn test_stale_suppressions_in_report_outputs() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("baseline.toml"),
+        "[[cycles]]\npackages = [\"crate_a\", \"crate_z\"]\n",
+    )
+    .unwrap();
+
+    let message = "Stale suppression of packages [crate_a, crate_z] in the baseline baseline.toml, its cycle no longer exists";
+    let expected = [
+        ("github", format!("::error file=baseline.toml,title=Stale suppression::{message}\n")),
+        ("junit", format!("<failure message=\"{message}\" type=\"stale-suppression\"/>")),
+        (
+            "markdown",
+            String::from(
+                "### Stale suppressions\n\nTheir cycles no longer exist, remove them:\n\n- packages [crate_a, crate_z] (`baseline.toml`)\n",
+            ),
+        ),
+        ("sarif", String::from("\"ruleId\": \"stale-suppression\"")),
+        ("codeclimate", String::from("\"check_name\": \"stale-suppression\"")),
+        (
+            "html",
+            String::from("<li class=\"cycle\">packages [crate_a, crate_z] (<code>baseline.toml</code>)</li>"),
+        ),
+    ];
+    for (format, output) in expected {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+        cmd.arg("ensure-no-cyclic-deps")
+            .arg("--manifest-path")
+            .arg(get_fixture_path("with_cycle").join("Cargo.toml"))
+            .arg("--baseline")
+            .arg("baseline.toml")
+            .arg("--strict-baseline")
+            .arg("--format")
+            .arg(format)
+            .current_dir(dir.path())
+            .env_remove("GITHUB_WORKSPACE");
+        cmd.assert().failure().stdout(predicate::str::contains(output));
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_shortest_path_within_cycle() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_tangled_cycles").join("Cargo.toml"));
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("  shortest cycle: api -> engine -> api\n"));
+
+    let manifest_path = get_fixture_path("with_multiple_cycles").join("Cargo.toml");
+    let metadata = cargo_ensure_no_cyclic_deps::load_metadata(Some(&manifest_path)).unwrap();
+    let cycles = cargo_ensure_no_cyclic_deps::detect_cycles(&metadata);
+    let paths: Vec<&[String]> = cycles.iter().map(|c| c.shortest_path.as_slice()).collect();
+    assert_eq!(paths, [&["alpha", "beta"][..], &["gamma"][..]]);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_condensation_output() {
+    let manifest_path = get_fixture_path("with_tangled_cycles").join("Cargo.toml");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--condense")
+        .arg("--format")
+        .arg("dot");
+
+    cmd.assert().failure().code(1).stdout(predicate::str::diff(
+        "digraph workspace {\n    node [shape=box];\n    c0 [label=\"api, engine, storage\", color=red, fontcolor=red];\n    c1 [label=\"util\"];\n    c0 -> c1;\n}\n",
+    ));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .arg("--condense")
+        .arg("--format")
+        .arg("json");
+
+    let output = cmd.assert().failure().code(1).get_output().stdout.clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let condensation = &report["condensation"];
+    assert_eq!(condensation["components"].as_array().unwrap().len(), 2);
+    assert_eq!(
+        condensation["components"][0]["packages"],
+        serde_json::json!(["api", "engine", "storage"])
+    );
+    assert_eq!(condensation["components"][0]["cycle"], "c6341f1e3b5678c8");
+    assert_eq!(
+        condensation["dependencies"],
+        serde_json::json!([{ "from": 0, "to": 1, "count": 1 }])
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_critical_parts_of_cycle() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_tangled_cycles").join("Cargo.toml"));
+
+    cmd.assert().failure().code(1).stderr(predicate::str::contains(
+        "  critical crates: engine\n  critical dependencies: api -> engine, engine -> storage\n",
+    ));
+
+    // In a simple ring every crate and dependency is critical, which isn't worth pointing out
+    let manifest_path = get_fixture_path("with_cycle").join("Cargo.toml");
+    let metadata = cargo_ensure_no_cyclic_deps::load_metadata(Some(&manifest_path)).unwrap();
+    let cycles = cargo_ensure_no_cyclic_deps::detect_cycles(&metadata);
+    assert_eq!(cycles[0].critical_packages, ["crate_a", "crate_b", "crate_c"]);
+    assert_eq!(cycles[0].critical_edges.len(), 3);
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps").arg("--manifest-path").arg(manifest_path);
+    cmd.assert().failure().code(1).stderr(predicate::str::contains("critical").not());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_culprits_of_cycles_with_the_same_crate_names() {
+    use cargo_ensure_no_cyclic_deps::elementary;
+    use cargo_ensure_no_cyclic_deps::report::Report;
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_cycle").join("Cargo.toml"))
+        .arg("--format")
+        .arg("json");
+    let output = cmd.assert().failure().code(1).get_output().stdout.clone();
+    let mut value: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+    // The same cycle in another workspace, as in metadata combined from several workspaces
+    let other = serde_json::to_string(&value["cycles"][0])
+        .unwrap()
+        .replace("with_cycle", "with_cycle_copy");
+    let other: serde_json::Value = serde_json::from_str(&other).unwrap();
+    value["cycles"].as_array_mut().unwrap().push(other);
+    let mut report: Report = serde_json::from_value(value).unwrap();
+
+    elementary::apply(&mut report, 10);
+    assert_ne!(report.culprits[0].from.id, report.culprits[1].from.id);
+    let culprits: Vec<(&str, &str, usize)> = report
+        .culprits
+        .iter()
+        .map(|c| (c.from.name.as_str(), c.to.name.as_str(), c.cycles))
+        .collect();
+    assert_eq!(
+        culprits,
+        [
+            ("crate_a", "crate_b", 1),
+            ("crate_a", "crate_b", 1),
+            ("crate_b", "crate_c", 1),
+            ("crate_b", "crate_c", 1),
+            ("crate_c", "crate_a", 1),
+            ("crate_c", "crate_a", 1),
+        ]
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_all_cycles_ranks_culprit_dependencies() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("cargo-ensure-no-cyclic-deps"));
+    cmd.arg("ensure-no-cyclic-deps")
+        .arg("--manifest-path")
+        .arg(get_fixture_path("with_tangled_cycles").join("Cargo.toml"))
+        .arg("--all-cycles");
+
+    cmd.assert().failure().code(1).stderr(predicate::str::contains(
+        "Dependencies in the most cycles:\n  api -> engine: 2 cycle(s)\n  engine -> storage: 2 cycle(s)\n  engine -> api: 1 cycle(s)\n",
+    ));
+}